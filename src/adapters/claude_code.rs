@@ -2,6 +2,7 @@ use super::traits::*;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -63,6 +64,13 @@ impl ClaudeCodeAdapter {
         let mut ended_at: Option<DateTime<Utc>> = None;
         let mut cwd: Option<String> = None;
         let mut conversation_id: Option<String> = None;
+        let mut git_branch: Option<String> = None;
+        // Thread links: lines we keep as messages, and for lines we drop
+        // (tool results, system entries) the kept ancestor they stand for
+        let mut kept: HashSet<String> = HashSet::new();
+        let mut skipped: HashMap<String, Option<String>> = HashMap::new();
+        // `tool_use` ids to their index in `tool_calls`, to mark failed results
+        let mut tool_ids: HashMap<String, usize> = HashMap::new();
 
         for line in content.lines() {
             if line.trim().is_empty() {
@@ -89,6 +97,13 @@ impl ClaudeCodeAdapter {
                 }
             }
 
+            // Keep the last branch seen, where the work ended up
+            if let Some(b) = v.get("gitBranch").and_then(|b| b.as_str()) {
+                if !b.is_empty() && b != "HEAD" {
+                    git_branch = Some(b.to_string());
+                }
+            }
+
             // Parse timestamp
             let ts = v
                 .get("timestamp")
@@ -105,8 +120,25 @@ impl ClaudeCodeAdapter {
                 }
             }
 
+            let uuid = v.get("uuid").and_then(|u| u.as_str()).map(|u| u.to_string());
+            // Compaction boundaries carry the real link in logicalParentUuid
+            let parent_uuid = v
+                .get("parentUuid")
+                .and_then(|p| p.as_str())
+                .or_else(|| v.get("logicalParentUuid").and_then(|p| p.as_str()))
+                .and_then(|p| match skipped.get(p) {
+                    Some(ancestor) if !kept.contains(p) => ancestor.clone(),
+                    _ => Some(p.to_string()),
+                });
+            let msg_count = messages.len();
+
             match msg_type {
                 "user" => {
+                    for id in error_result_ids(&v) {
+                        if let Some(&i) = tool_ids.get(id) {
+                            tool_calls[i].is_error = true;
+                        }
+                    }
                     let content_text = extract_message_content(&v);
                     if !content_text.is_empty() {
                         messages.push(MessageData {
@@ -114,6 +146,8 @@ impl ClaudeCodeAdapter {
                             content: content_text,
                             timestamp: ts,
                             files_changed: Vec::new(),
+                            uuid: uuid.clone(),
+                            parent_uuid: parent_uuid.clone(),
                         });
                     }
                 }
@@ -123,6 +157,7 @@ impl ClaudeCodeAdapter {
 
                     let mut text_parts: Vec<String> = Vec::new();
                     let mut file_changes: Vec<String> = Vec::new();
+                    let mut line_tool_calls: Vec<ToolCallData> = Vec::new();
 
                     if let Some(Value::Array(arr)) = content_arr {
                         for item in arr {
@@ -156,10 +191,21 @@ impl ClaudeCodeAdapter {
                                         }
                                     }
 
-                                    tool_calls.push(ToolCallData {
+                                    if let Some(id) = item.get("id").and_then(|i| i.as_str()) {
+                                        tool_ids.insert(id.to_string(), tool_calls.len() + line_tool_calls.len());
+                                    }
+                                    let is_commit = item
+                                        .get("input")
+                                        .and_then(|i| i.get("command"))
+                                        .and_then(|c| c.as_str())
+                                        .is_some_and(runs_git_commit);
+                                    line_tool_calls.push(ToolCallData {
                                         tool_name,
                                         file_path,
                                         timestamp: ts,
+                                        message_uuid: None,
+                                        is_error: false,
+                                        is_commit,
                                     });
                                 }
                                 _ => {}
@@ -170,17 +216,37 @@ impl ClaudeCodeAdapter {
                     }
 
                     let combined_text = text_parts.join("\n");
+                    // Tool-only lines attach their calls to the nearest kept message
+                    let owner = if combined_text.is_empty() {
+                        parent_uuid.clone()
+                    } else {
+                        uuid.clone()
+                    };
+                    for mut tc in line_tool_calls {
+                        tc.message_uuid = owner.clone();
+                        tool_calls.push(tc);
+                    }
                     if !combined_text.is_empty() {
                         messages.push(MessageData {
                             role: Role::Assistant,
                             content: combined_text,
                             timestamp: ts,
                             files_changed: file_changes,
+                            uuid: uuid.clone(),
+                            parent_uuid: parent_uuid.clone(),
                         });
                     }
                 }
                 _ => {}
             }
+
+            if let Some(id) = uuid {
+                if messages.len() > msg_count {
+                    kept.insert(id);
+                } else {
+                    skipped.insert(id, parent_uuid);
+                }
+            }
         }
 
         let resolved_project = cwd
@@ -205,6 +271,7 @@ impl ClaudeCodeAdapter {
             messages,
             tool_calls,
             tags: Vec::new(),
+            git_branch,
         };
 
         // Extract summary from first user message (first sentence, 120 chars)
@@ -399,6 +466,18 @@ fn extract_message_content(v: &Value) -> String {
     String::new()
 }
 
+/// `tool_use_id`s of the `tool_result` blocks in a user line that report an error
+fn error_result_ids(v: &Value) -> Vec<&str> {
+    let Some(Value::Array(arr)) = v.get("message").and_then(|m| m.get("content")) else {
+        return Vec::new();
+    };
+    arr.iter()
+        .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+        .filter(|item| item.get("is_error").and_then(|e| e.as_bool()) == Some(true))
+        .filter_map(|item| item.get("tool_use_id").and_then(|i| i.as_str()))
+        .collect()
+}
+
 /// Try to resolve an encoded path by checking if directories exist
 fn resolve_encoded_path(encoded: &str) -> PathBuf {
     // The path has had all '/' replaced with '-', then we replaced the first one back.
@@ -1,7 +1,9 @@
 use super::traits::*;
 use anyhow::Result;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
 
 pub struct CodexAdapter {
     data_dir: PathBuf,
@@ -14,6 +16,10 @@ impl CodexAdapter {
             data_dir: home.join(".codex"),
         }
     }
+
+    pub fn with_data_dir(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
 }
 
 impl AgentAdapter for CodexAdapter {
@@ -41,19 +47,7 @@ impl AgentAdapter for CodexAdapter {
             return Ok(sessions);
         }
 
-        for entry in fs::read_dir(&sessions_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if !path.is_file() {
-                continue;
-            }
-
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if ext != "jsonl" && ext != "json" {
-                continue;
-            }
-
+        for path in session_files(&sessions_dir)? {
             let session_id = path
                 .file_stem()
                 .map(|s| s.to_string_lossy().to_string())
@@ -77,9 +71,9 @@ impl AgentAdapter for CodexAdapter {
             return Ok(None);
         }
 
-        for ext in &["jsonl", "json"] {
-            let path = sessions_dir.join(format!("{}.{}", session_id, ext));
-            if path.exists() {
+        // Rollout files live under sessions/YYYY/MM/DD/
+        for path in session_files(&sessions_dir)? {
+            if path.file_stem().is_some_and(|s| s == session_id) {
                 return parse_codex_session(&path, session_id);
             }
         }
@@ -88,7 +82,7 @@ impl AgentAdapter for CodexAdapter {
     }
 
     fn resume_command(&self, session_id: &str, project_path: Option<&str>) -> String {
-        let mut cmd = format!("codex --resume {}", session_id);
+        let mut cmd = format!("codex resume {}", session_id);
         if let Some(p) = project_path {
             cmd = format!("cd {} && {}", p, cmd);
         }
@@ -96,39 +90,77 @@ impl AgentAdapter for CodexAdapter {
     }
 }
 
-fn parse_codex_session(path: &std::path::Path, session_id: &str) -> Result<Option<SessionData>> {
+/// All `.jsonl`/`.json` files under `dir`, including the dated rollout subdirectories
+fn session_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(session_files(&path)?);
+            continue;
+        }
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if ext == "jsonl" || ext == "json" {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn parse_codex_session(path: &Path, session_id: &str) -> Result<Option<SessionData>> {
     let content = fs::read_to_string(path)?;
 
     let mut messages = Vec::new();
-    let tool_calls = Vec::new();
+    let mut tool_calls = Vec::new();
     let mut started_at = None;
     let mut ended_at = None;
     let mut project_path = None;
+    let mut conversation_id = None;
+    let mut git_branch = None;
+    // `call_id`s to their index in `tool_calls`, to mark failed outputs
+    let mut call_ids: HashMap<String, usize> = HashMap::new();
 
     for line in content.lines() {
         if line.trim().is_empty() {
             continue;
         }
-        let v: serde_json::Value = match serde_json::from_str(line) {
+        let v: Value = match serde_json::from_str(line) {
             Ok(v) => v,
             Err(_) => continue,
         };
 
-        let role_str = v
+        // Rollout files wrap each entry as {"type": ..., "payload": {...}}
+        let line_type = v.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        let item = match line_type {
+            "session_meta" | "response_item" => v.get("payload").unwrap_or(&v),
+            _ => &v,
+        };
+
+        if line_type == "session_meta" && conversation_id.is_none() {
+            conversation_id = item.get("id").and_then(|i| i.as_str()).map(|i| i.to_string());
+        }
+        if line_type == "session_meta" && git_branch.is_none() {
+            git_branch = item
+                .get("git")
+                .and_then(|g| g.get("branch"))
+                .and_then(|b| b.as_str())
+                .map(|b| b.to_string());
+        }
+
+        let role_str = item
             .get("role")
-            .or_else(|| v.get("message").and_then(|m| m.get("role")))
+            .or_else(|| item.get("message").and_then(|m| m.get("role")))
             .and_then(|r| r.as_str())
             .unwrap_or("");
 
-        let content_text = v
+        let content_text = item
             .get("content")
-            .or_else(|| v.get("message").and_then(|m| m.get("content")))
-            .and_then(|c| c.as_str())
-            .unwrap_or("")
-            .to_string();
+            .or_else(|| item.get("message").and_then(|m| m.get("content")))
+            .map(content_to_text)
+            .unwrap_or_default();
 
         if project_path.is_none() {
-            if let Some(cwd) = v.get("cwd").and_then(|c| c.as_str()) {
+            if let Some(cwd) = item.get("cwd").and_then(|c| c.as_str()) {
                 project_path = Some(PathBuf::from(cwd));
             }
         }
@@ -148,12 +180,50 @@ fn parse_codex_session(path: &std::path::Path, session_id: &str) -> Result<Optio
             }
         }
 
+        if item.get("type").and_then(|t| t.as_str()) == Some("function_call") {
+            let arguments: Value = item
+                .get("arguments")
+                .and_then(|a| a.as_str())
+                .and_then(|a| serde_json::from_str(a).ok())
+                .unwrap_or(Value::Null);
+            if let Some(id) = item.get("call_id").and_then(|i| i.as_str()) {
+                call_ids.insert(id.to_string(), tool_calls.len());
+            }
+            tool_calls.push(ToolCallData {
+                tool_name: item.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string(),
+                file_path: arguments
+                    .get("file_path")
+                    .or_else(|| arguments.get("path"))
+                    .and_then(|p| p.as_str())
+                    .map(|p| p.to_string()),
+                timestamp: ts,
+                message_uuid: None,
+                is_error: false,
+                is_commit: command_commits(&arguments),
+            });
+            continue;
+        }
+        if item.get("type").and_then(|t| t.as_str()) == Some("function_call_output") {
+            let call = item.get("call_id").and_then(|i| i.as_str()).and_then(|id| call_ids.get(id));
+            if let Some(&i) = call {
+                tool_calls[i].is_error = output_failed(item.get("output"));
+            }
+            continue;
+        }
+
+        // Codex prepends its own environment and instruction blocks as user messages
+        if content_text.starts_with("<environment_context>") || content_text.starts_with("<user_instructions>") {
+            continue;
+        }
+
         if !role_str.is_empty() && !content_text.is_empty() {
             messages.push(MessageData {
                 role: Role::from_str(role_str),
                 content: content_text,
                 timestamp: ts,
                 files_changed: Vec::new(),
+                uuid: None,
+                parent_uuid: None,
             });
         }
     }
@@ -169,7 +239,7 @@ fn parse_codex_session(path: &std::path::Path, session_id: &str) -> Result<Optio
 
     let mut session = SessionData {
         id: session_id.to_string(),
-        conversation_id: None,
+        conversation_id,
         agent: AgentType::Codex,
         project_path,
         project_name,
@@ -180,6 +250,7 @@ fn parse_codex_session(path: &std::path::Path, session_id: &str) -> Result<Optio
         messages,
         tool_calls,
         tags: Vec::new(),
+        git_branch,
     };
 
     session.summary = session.extract_summary();
@@ -187,3 +258,47 @@ fn parse_codex_session(path: &std::path::Path, session_id: &str) -> Result<Optio
 
     Ok(Some(session))
 }
+
+/// Shell outputs carry the exit code, either as JSON metadata or as an
+/// `Exit code: N` first line
+fn output_failed(output: Option<&Value>) -> bool {
+    let Some(text) = output.and_then(|o| o.as_str()) else {
+        return false;
+    };
+    if let Ok(json) = serde_json::from_str::<Value>(text) {
+        if let Some(code) = json.get("metadata").and_then(|m| m.get("exit_code")).and_then(|c| c.as_i64()) {
+            return code != 0;
+        }
+    }
+    text.lines()
+        .next()
+        .and_then(|l| l.strip_prefix("Exit code: "))
+        .and_then(|c| c.trim().parse::<i64>().ok())
+        .is_some_and(|code| code != 0)
+}
+
+/// `shell` calls pass `command` as an argv, often `["bash", "-lc", "..."]`,
+/// or as a single command line
+fn command_commits(arguments: &Value) -> bool {
+    match arguments.get("command").or_else(|| arguments.get("cmd")) {
+        Some(Value::String(line)) => runs_git_commit(line),
+        Some(Value::Array(argv)) => {
+            let words: Vec<&str> = argv.iter().filter_map(|w| w.as_str()).collect();
+            runs_git_commit(&words.join(" ")) || words.iter().any(|w| runs_git_commit(w))
+        }
+        _ => false,
+    }
+}
+
+/// Message content is either a plain string or a list of `input_text`/`output_text` parts.
+fn content_to_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
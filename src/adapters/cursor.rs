@@ -116,6 +116,8 @@ fn parse_cursor_session(path: &std::path::Path) -> Result<Option<SessionData>> {
                     content: text,
                     timestamp: None,
                     files_changed: Vec::new(),
+                    uuid: None,
+                    parent_uuid: None,
                 });
             }
         }
@@ -139,6 +141,8 @@ fn parse_cursor_session(path: &std::path::Path) -> Result<Option<SessionData>> {
                         content: text,
                         timestamp: None,
                         files_changed: Vec::new(),
+                        uuid: None,
+                        parent_uuid: None,
                     });
                 }
             }
@@ -162,6 +166,7 @@ fn parse_cursor_session(path: &std::path::Path) -> Result<Option<SessionData>> {
         messages,
         tool_calls: Vec::new(),
         tags: Vec::new(),
+        git_branch: None,
     };
 
     session.summary = session.extract_summary();
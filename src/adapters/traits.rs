@@ -89,6 +89,9 @@ pub struct SessionData {
     pub messages: Vec<MessageData>,
     pub tool_calls: Vec<ToolCallData>,
     pub tags: Vec<String>,
+    /// Git branch checked out in the session's working directory
+    #[serde(default)]
+    pub git_branch: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +100,13 @@ pub struct MessageData {
     pub content: String,
     pub timestamp: Option<DateTime<Utc>>,
     pub files_changed: Vec<String>,
+    /// Agent-assigned message id (Claude Code `uuid`), if any
+    #[serde(default)]
+    pub uuid: Option<String>,
+    /// Id of the message this one follows in the conversation tree.
+    /// Rewinds and retries show up as several messages sharing a parent.
+    #[serde(default)]
+    pub parent_uuid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +114,38 @@ pub struct ToolCallData {
     pub tool_name: String,
     pub file_path: Option<String>,
     pub timestamp: Option<DateTime<Utc>>,
+    /// `uuid` of the message that issued the call
+    #[serde(default)]
+    pub message_uuid: Option<String>,
+    /// The call's result was an error (failed command, rejected edit)
+    #[serde(default)]
+    pub is_error: bool,
+    /// The call ran `git commit`
+    #[serde(default)]
+    pub is_commit: bool,
+}
+
+/// Whether a shell command line runs `git commit`, e.g.
+/// `git add -A && git -C repo commit -m "..."`
+pub fn runs_git_commit(command: &str) -> bool {
+    command
+        .split([';', '&', '|', '\n'])
+        .any(|part| {
+            let mut words = part.split_whitespace().skip_while(|w| w.contains('='));
+            if words.next() != Some("git") {
+                return false;
+            }
+            while let Some(word) = words.next() {
+                match word {
+                    "-C" | "-c" => {
+                        words.next();
+                    }
+                    w if w.starts_with('-') => {}
+                    w => return w == "commit",
+                }
+            }
+            false
+        })
 }
 
 impl SessionData {
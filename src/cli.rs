@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "ail", about = "AI Log — AI development activity intelligence", version)]
@@ -9,6 +9,14 @@ pub struct Cli {
     /// Output as JSON
     #[arg(long, global = true)]
     pub json: bool,
+
+    /// Don't mask secrets and PII (API keys, tokens, emails, IPs) in output
+    #[arg(long, global = true)]
+    pub no_redact: bool,
+
+    /// Read the shared team index (see `ail team`) instead of your own
+    #[arg(long, global = true)]
+    pub team: bool,
 }
 
 #[derive(Subcommand)]
@@ -33,6 +41,26 @@ pub enum Commands {
         /// Fuzzy search query
         #[arg(short, long)]
         query: Option<String>,
+
+        /// Filter by author (team index)
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Filter by outcome (success, failed, abandoned, in-progress)
+        #[arg(long)]
+        outcome: Option<String>,
+
+        /// Sessions per page
+        #[arg(long, default_value = "200")]
+        limit: usize,
+
+        /// Page number (1-based)
+        #[arg(long)]
+        page: Option<usize>,
+
+        /// Continue after a cursor printed by a previous page
+        #[arg(long, conflicts_with = "page")]
+        after: Option<String>,
     },
 
     /// Resume a session
@@ -80,6 +108,38 @@ pub enum Commands {
         /// Search by file path
         #[arg(long)]
         file: Option<String>,
+
+        /// Filter by author (team index)
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Matches per page
+        #[arg(long, default_value = "50")]
+        limit: usize,
+
+        /// Page number (1-based)
+        #[arg(long)]
+        page: Option<usize>,
+
+        /// Continue after a cursor printed by a previous page
+        #[arg(long, conflicts_with = "page")]
+        after: Option<String>,
+    },
+
+    /// Manage saved searches
+    Search {
+        #[command(subcommand)]
+        action: SearchAction,
+    },
+
+    /// Find sessions similar to a given session
+    Related {
+        /// Session ID
+        session_id: String,
+
+        /// Max results
+        #[arg(long, default_value = "10")]
+        limit: usize,
     },
 
     /// Show full session conversation
@@ -121,56 +181,65 @@ pub enum Commands {
     },
 
     /// Generate work reports
+    #[command(args_conflicts_with_subcommands = true)]
     Report {
-        /// Daily report
-        #[arg(long)]
-        day: bool,
+        #[command(subcommand)]
+        action: Option<ReportAction>,
+
+        #[command(flatten)]
+        args: ReportArgs,
+    },
 
-        /// Specific date for daily report (YYYY-MM-DD)
+    /// Yesterday / Today / Blockers summary for a standup
+    Standup {
+        /// Day of the standup (YYYY-MM-DD, default today)
         #[arg(long)]
         date: Option<String>,
 
-        /// Weekly report
-        #[arg(long)]
-        week: bool,
+        /// Filter by project
+        #[arg(short, long)]
+        project: Option<String>,
 
-        /// Monthly report
+        /// Filter by author (team index)
         #[arg(long)]
-        month: bool,
+        author: Option<String>,
 
-        /// Quarterly report (Q1, Q2, Q3, Q4)
-        #[arg(long)]
-        quarter: Option<String>,
+        /// Output format (markdown, slack, json, html, csv, wiki)
+        #[arg(long, default_value = "markdown")]
+        format: String,
 
-        /// Custom range start
+        /// Use LLM to generate concise session summaries
         #[arg(long)]
-        from: Option<String>,
+        summarize: bool,
 
-        /// Custom range end
+        /// Copy to clipboard
         #[arg(long)]
-        to: Option<String>,
+        clipboard: bool,
+    },
 
-        /// Filter by project
-        #[arg(short, long)]
-        project: Option<String>,
+    /// Weekly trend of sessions and active time per agent and project
+    Stats {
+        /// Span of the weekly series (e.g. 12w, 6m)
+        #[arg(long, default_value = "12w")]
+        trend: String,
 
-        /// Output to file
+        /// Filter by agent
         #[arg(short, long)]
-        output: Option<String>,
+        agent: Option<String>,
 
-        /// Output format (markdown, slack, json)
-        #[arg(long, default_value = "markdown")]
-        format: String,
+        /// Filter by project
+        #[arg(short, long)]
+        project: Option<String>,
 
-        /// Use LLM to generate concise session summaries
+        /// Filter by author (team index)
         #[arg(long)]
-        summarize: bool,
+        author: Option<String>,
     },
 
     /// Export session context
     Export {
-        /// Session ID
-        session_id: String,
+        /// Session ID(s); several IDs are merged into one bundle, narrowed by --project/--since/--tag
+        session_ids: Vec<String>,
 
         /// Copy to clipboard
         #[arg(long)]
@@ -180,19 +249,159 @@ pub enum Commands {
         #[arg(long)]
         stdout: bool,
 
-        /// Detail level (full, summary, minimal)
-        #[arg(long, default_value = "summary")]
-        detail: String,
+        /// Detail level (full, summary, minimal) [default: summary]
+        #[arg(long)]
+        detail: Option<String>,
+
+        /// Template name: default, handoff, pr, postmortem, or a file in ~/.config/ail/templates/
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Pack the most useful content into about this many tokens
+        #[arg(long, conflicts_with_all = ["template", "detail"])]
+        max_tokens: Option<usize>,
+
+        /// Output format: markdown, or html for a self-contained page of one session
+        #[arg(long, default_value = "markdown", conflicts_with_all = ["template", "max_tokens"])]
+        format: String,
+
+        /// Bundle every session for this project (path or name)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Bundle sessions started within this period (e.g. 3d, 2w) or since a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Bundle sessions with this tag
+        #[arg(long)]
+        tag: Option<String>,
     },
 
-    /// Inject context into CLAUDE.md
+    /// Inject context into agent instruction files (CLAUDE.md, AGENTS.md, ...)
     Inject {
-        /// Session ID
-        session_id: Option<String>,
+        /// Session ID(s); several IDs are merged into one bundle, narrowed by --project/--since/--tag
+        session_ids: Vec<String>,
 
         /// Auto-inject latest context for current project
         #[arg(long)]
         auto: bool,
+
+        /// Target agent file(s): claude, codex, cursor, gemini, all (comma-separated)
+        #[arg(long, default_value = "claude")]
+        target: String,
+
+        /// Remove the injected ail block instead of writing one
+        #[arg(long, conflicts_with = "auto")]
+        remove: bool,
+
+        /// Bundle every session for this project (path or name)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Bundle sessions started within this period (e.g. 3d, 2w) or since a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Bundle sessions with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// List sessions that contain likely secrets (API keys, tokens, private keys, ...)
+    ScanSecrets {
+        /// Filter by agent (claude-code, codex, cursor)
+        #[arg(short, long)]
+        agent: Option<String>,
+
+        /// Filter by project path
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+
+    /// Convert a session into another agent's native format so it can be resumed there
+    Convert {
+        /// Session ID to convert
+        session_id: String,
+
+        /// Target agent (codex, claude-code)
+        #[arg(long)]
+        to: String,
+    },
+
+    /// Write sessions to a portable archive (.ail.tar.zst) for another machine
+    Archive {
+        /// Output file (default: ail-archive-<date>.ail.tar.zst)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Only sessions started within this period (e.g. 30d, 2w) or since a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only sessions for this project (path or name)
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions from this agent (claude-code, codex, cursor)
+        #[arg(short, long)]
+        agent: Option<String>,
+
+        /// Only sessions with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Redact secrets and PII in the archived content
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// Merge an archive written by `ail archive` into the index
+    Import {
+        /// Archive file
+        path: String,
+
+        /// Rewrite project and file paths, e.g. /Users/x=/home/x (repeatable)
+        #[arg(long = "map-path")]
+        map_path: Vec<String>,
+
+        /// Overwrite sessions that are already indexed (default: skip them)
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Share sessions with your team through a merged, read-only index
+    Team {
+        #[command(subcommand)]
+        action: TeamAction,
+    },
+
+    /// Generate a static HTML site of sessions
+    Site {
+        #[command(subcommand)]
+        action: SiteAction,
+    },
+
+    /// Encrypt the index at rest (first run), or lock it again by forgetting the cached key
+    Lock {
+        /// Derive the key from this file instead of a passphrase
+        #[arg(long)]
+        keyfile: Option<String>,
+    },
+
+    /// Unlock an encrypted index and cache the key for this login session
+    Unlock {
+        /// Derive the key from this file instead of a passphrase
+        #[arg(long)]
+        keyfile: Option<String>,
+
+        /// How long the cached key stays valid (e.g. 8h, 1d)
+        #[arg(long = "for", default_value = "8h")]
+        duration: String,
+
+        /// Decrypt the index for good and turn encryption off
+        #[arg(long)]
+        decrypt: bool,
     },
 
     /// Rebuild or update the index
@@ -220,3 +429,174 @@ pub enum Commands {
         edit: bool,
     },
 }
+
+/// Period, filters and output of `ail report`
+#[derive(Args)]
+pub struct ReportArgs {
+    /// Daily report
+    #[arg(long)]
+    pub day: bool,
+
+    /// Specific date for daily report (YYYY-MM-DD)
+    #[arg(long)]
+    pub date: Option<String>,
+
+    /// Weekly report
+    #[arg(long)]
+    pub week: bool,
+
+    /// Monthly report
+    #[arg(long)]
+    pub month: bool,
+
+    /// Quarterly report (Q1, Q2, Q3, Q4)
+    #[arg(long)]
+    pub quarter: Option<String>,
+
+    /// Custom range start
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// Custom range end
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Filter by project
+    #[arg(short, long)]
+    pub project: Option<String>,
+
+    /// Filter by author (team index)
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Output to file
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Output format (markdown, slack, json, html, csv, wiki)
+    #[arg(long, default_value = "markdown")]
+    pub format: String,
+
+    /// Use LLM to generate concise session summaries
+    #[arg(long)]
+    pub summarize: bool,
+
+    /// Use LLM to double-check each session's outcome
+    #[arg(long)]
+    pub refine_outcomes: bool,
+
+    /// Show changes against another period (previous)
+    #[arg(long)]
+    pub compare: Option<String>,
+
+    /// Section sessions by project, agent, branch, ticket or tag
+    #[arg(long, default_value = "project")]
+    pub group_by: String,
+}
+
+#[derive(Subcommand)]
+pub enum ReportAction {
+    /// Deliver the [report.schedules] reports whose last period hasn't been sent yet
+    RunScheduled {
+        /// Only this schedule
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Deliver again even if the period was already delivered
+        #[arg(long)]
+        force: bool,
+
+        /// Show what is due without delivering anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TeamAction {
+    /// Write your shareable sessions to an archive, honouring [team] exclusions
+    Export {
+        /// Output file (default: ail-team-<author>-<date>.ail.tar.zst)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Only sessions started within this period (e.g. 30d, 2w) or since a date
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Fold team members' archives into the shared index
+    Merge {
+        /// Archives written by `ail team export`
+        #[arg(required = true)]
+        archives: Vec<String>,
+
+        /// Index to merge into (default: [team] db_path)
+        #[arg(long)]
+        into: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SiteAction {
+    /// Build a multi-page site with client-side search that opens straight from disk
+    Build {
+        /// Output directory
+        out: String,
+
+        /// Only sessions for this project (path or name)
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions started within this period (e.g. 3d, 2w) or since a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only sessions with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only sessions from this agent (claude-code, codex, cursor)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SearchAction {
+    /// Save a named search (e.g. "tag:bug after:-7d")
+    Save {
+        /// Name of the saved search
+        name: String,
+
+        /// Query: keywords plus agent:, project:, tag:, file:, after:, before:
+        query: String,
+    },
+
+    /// List saved searches with current match counts
+    List,
+
+    /// Run a saved search by name
+    Run {
+        /// Name of the saved search
+        name: String,
+
+        /// Max results
+        #[arg(long, default_value = "200")]
+        limit: usize,
+
+        /// Page number (1-based)
+        #[arg(long)]
+        page: Option<usize>,
+
+        /// Continue after a cursor printed by a previous page
+        #[arg(long, conflicts_with = "page")]
+        after: Option<String>,
+    },
+
+    /// Delete a saved search
+    Delete {
+        /// Name of the saved search
+        name: String,
+    },
+}
@@ -1,7 +1,10 @@
+use crate::core::clock::Calendar;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AilConfig {
@@ -17,13 +20,28 @@ pub struct AilConfig {
     pub tui: TuiConfig,
     #[serde(default)]
     pub mcp: McpConfig,
+    #[serde(default)]
+    pub redact: RedactConfig,
+    #[serde(default)]
+    pub team: TeamConfig,
+    #[serde(default)]
+    pub time: TimeConfig,
+    #[serde(default)]
+    pub tickets: TicketsConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeneralConfig {
     pub db_path: String,
     pub auto_index: bool,
     pub index_interval: u64,
+    /// IANA timezone for days and weeks (default: the system timezone)
+    pub timezone: String,
+    /// First day of the week: monday or sunday
+    pub week_start: String,
+    /// When a work day begins, e.g. "04:00" to count late nights toward the day before
+    pub day_start: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,18 +67,46 @@ pub struct ExportConfig {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ReportConfig {
     pub default_format: String,
     pub include_file_changes: bool,
     #[serde(default)]
     pub summarize: SummarizeConfig,
+    /// Have the summarize model double-check rule-based session outcomes
+    pub refine_outcomes: bool,
+    /// Reports delivered by `ail report run-scheduled`, by name
+    #[serde(default)]
+    pub schedules: BTreeMap<String, ReportSchedule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSchedule {
+    /// day, week, month or quarter; each run delivers the last one that has ended
+    pub period: String,
+    pub format: String,
+    pub project: Option<String>,
+    pub group_by: String,
+    pub compare: bool,
+    /// File path pattern (`~/reports/{year}-W{week}.md`) or http(s) webhook URL
+    pub destination: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SummarizeConfig {
     pub enabled: bool,
+    /// anthropic, openai (any OpenAI-compatible server), llamacpp or ollama
+    pub provider: String,
+    /// Endpoint override, e.g. `http://localhost:8080/v1`
+    pub base_url: Option<String>,
     pub api_key: Option<String>,
-    pub model: String,
+    /// Environment variable holding the key (default: ANTHROPIC_API_KEY /
+    /// OPENAI_API_KEY, only for the provider's own endpoint)
+    pub api_key_env: Option<String>,
+    /// Default depends on the provider; required for a custom OpenAI-compatible endpoint
+    pub model: Option<String>,
     pub max_input_chars: usize,
 }
 
@@ -76,6 +122,60 @@ pub struct McpConfig {
     pub transport: String,
 }
 
+/// Secret/PII redaction applied to exports, reports, `show` and MCP output
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactConfig {
+    pub enabled: bool,
+    /// Built-in detectors to turn off (e.g. "email", "ipv4")
+    pub disabled: Vec<String>,
+    /// Exact values never redacted
+    pub allow: Vec<String>,
+    /// Extra patterns, e.g. internal hostnames
+    pub rules: Vec<RedactRule>,
+}
+
+/// Sharing sessions with a team index (`ail team export` / `ail team merge`)
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TeamConfig {
+    /// Name recorded on your sessions (default: $USER)
+    pub author: String,
+    /// Machine name recorded on your sessions (default: hostname)
+    pub machine: String,
+    /// Shared index opened by `--team` (default: team.db in the data directory)
+    pub db_path: String,
+    /// Projects (name or path, `~` allowed) never included in `ail team export`
+    pub exclude_projects: Vec<String>,
+    /// Sessions with any of these tags are never included in `ail team export`
+    pub exclude_tags: Vec<String>,
+}
+
+/// Active-time estimation shown by `ail list`, reports and the TUI
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeConfig {
+    /// Gaps between messages longer than this count as breaks, not work
+    pub idle_minutes: u64,
+}
+
+/// Issue keys picked out of prompts, branch names and commit messages
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TicketsConfig {
+    /// Regexes for ticket keys; if one has a capture group, group 1 is the key
+    pub patterns: Vec<String>,
+    /// Key prefixes that look like tickets but aren't (`UTF-8`, `SHA-256`)
+    pub ignore: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedactRule {
+    pub name: String,
+    /// Regex; if it has a `secret` group only that part is replaced
+    pub pattern: String,
+}
+
 impl Default for AilConfig {
     fn default() -> Self {
         Self {
@@ -85,6 +185,10 @@ impl Default for AilConfig {
             report: ReportConfig::default(),
             tui: TuiConfig::default(),
             mcp: McpConfig::default(),
+            redact: RedactConfig::default(),
+            team: TeamConfig::default(),
+            time: TimeConfig::default(),
+            tickets: TicketsConfig::default(),
         }
     }
 }
@@ -96,6 +200,9 @@ impl Default for GeneralConfig {
             db_path: db_path.to_string_lossy().to_string(),
             auto_index: true,
             index_interval: 300,
+            timezone: String::new(),
+            week_start: "monday".to_string(),
+            day_start: "00:00".to_string(),
         }
     }
 }
@@ -137,6 +244,21 @@ impl Default for ReportConfig {
             default_format: "markdown".to_string(),
             include_file_changes: true,
             summarize: SummarizeConfig::default(),
+            refine_outcomes: false,
+            schedules: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for ReportSchedule {
+    fn default() -> Self {
+        Self {
+            period: "week".to_string(),
+            format: "markdown".to_string(),
+            project: None,
+            group_by: "project".to_string(),
+            compare: false,
+            destination: String::new(),
         }
     }
 }
@@ -145,8 +267,11 @@ impl Default for SummarizeConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            provider: "anthropic".to_string(),
+            base_url: None,
             api_key: None,
-            model: "claude-haiku-4-5-20251001".to_string(),
+            api_key_env: None,
+            model: None,
             max_input_chars: 4000,
         }
     }
@@ -162,6 +287,39 @@ impl Default for TuiConfig {
     }
 }
 
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled: Vec::new(),
+            allow: vec![
+                "127.0.0.1".to_string(),
+                "0.0.0.0".to_string(),
+                "git@github.com".to_string(),
+                "git@gitlab.com".to_string(),
+            ],
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            idle_minutes: crate::core::activity::DEFAULT_IDLE_MINUTES,
+        }
+    }
+}
+
+impl Default for TicketsConfig {
+    fn default() -> Self {
+        Self {
+            patterns: crate::core::refs::DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            ignore: crate::core::refs::DEFAULT_IGNORE.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
 impl Default for McpConfig {
     fn default() -> Self {
         Self {
@@ -186,10 +344,61 @@ pub fn data_dir() -> PathBuf {
         .join("ail")
 }
 
+/// Set by the global `--team` flag: commands read the shared team index
+static TEAM_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_team_mode() {
+    TEAM_MODE.store(true, Ordering::Relaxed);
+}
+
+pub fn team_mode() -> bool {
+    TEAM_MODE.load(Ordering::Relaxed)
+}
+
+/// The index commands use: the local one, or the team index under `--team`
 pub fn db_path() -> PathBuf {
+    if team_mode() {
+        return team_db_path();
+    }
     data_dir().join("index.db")
 }
 
+pub fn team_db_path() -> PathBuf {
+    let configured = load_config().map(|c| c.team.db_path).unwrap_or_default();
+    match configured.as_str() {
+        "" => data_dir().join("team.db"),
+        p => expand_home(p),
+    }
+}
+
+/// `~/x` under the home directory; other paths as given
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// `[time] idle_minutes`, falling back to the default on a missing or bad config
+pub fn idle_minutes() -> u64 {
+    load_config().map(|c| c.time.idle_minutes).unwrap_or_else(|_| TimeConfig::default().idle_minutes)
+}
+
+/// The calendar from `[general]`, warning and falling back to the defaults on a bad
+/// timezone, week start or day start
+pub fn calendar() -> Calendar {
+    let general = load_config().map(|c| c.general).unwrap_or_default();
+    Calendar::new(&general.timezone, &general.week_start, &general.day_start).unwrap_or_else(|e| {
+        eprintln!("Warning: ignoring [general] day settings: {}", e);
+        Calendar::default()
+    })
+}
+
+/// `[tickets]`, falling back to the defaults on a missing or bad config
+pub fn tickets() -> TicketsConfig {
+    load_config().map(|c| c.tickets).unwrap_or_default()
+}
+
 pub fn load_config() -> Result<AilConfig> {
     let path = config_path();
     if path.exists() {
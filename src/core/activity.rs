@@ -0,0 +1,56 @@
+use crate::core::clock;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+/// Idle gap used when the config doesn't set one
+pub const DEFAULT_IDLE_MINUTES: u64 = 15;
+
+/// Time spent actively working: the sum of gaps between consecutive events
+/// (messages, tool calls) that are no longer than `idle`. Longer gaps are
+/// breaks — a session left open overnight only counts the time around it.
+pub fn active_seconds(timestamps: &[DateTime<Utc>], idle: Duration) -> i64 {
+    active_spans(timestamps, idle).iter().map(|(_, secs)| secs).sum()
+}
+
+/// `active_seconds` split by the work day each stretch of work started on
+/// (see `core::clock`).
+pub fn active_seconds_by_day(
+    timestamps: &[DateTime<Utc>],
+    idle: Duration,
+) -> BTreeMap<NaiveDate, i64> {
+    let calendar = clock::calendar();
+    let mut days = BTreeMap::new();
+    for (start, secs) in active_spans(timestamps, idle) {
+        *days.entry(calendar.day_of(start)).or_insert(0) += secs;
+    }
+    days
+}
+
+/// Active gaps as (start, seconds)
+fn active_spans(timestamps: &[DateTime<Utc>], idle: Duration) -> Vec<(DateTime<Utc>, i64)> {
+    let mut sorted = timestamps.to_vec();
+    sorted.sort();
+    sorted
+        .windows(2)
+        .map(|w| (w[0], w[1] - w[0]))
+        .filter(|(_, gap)| *gap <= idle)
+        .map(|(start, gap)| (start, gap.num_seconds()))
+        .collect()
+}
+
+/// "2h 05m", "12m", or "<1m"
+pub fn format_active(secs: i64) -> String {
+    let minutes = secs / 60;
+    if minutes == 0 {
+        "<1m".to_string()
+    } else if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Hours with one decimal, for timesheet columns
+pub fn hours(secs: i64) -> f64 {
+    (secs as f64 / 360.0).round() / 10.0
+}
@@ -0,0 +1,371 @@
+use crate::adapters::traits::{AgentType, MessageData, Role, SessionData, ToolCallData};
+use crate::core::db::{Database, SessionRow};
+use crate::core::redact::Redactor;
+use crate::core::team::Identity;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Archive layout (documented in the README): a zstd-compressed tar holding
+/// `manifest.json` plus `sessions.jsonl`, `messages.jsonl` and `tool_calls.jsonl`.
+/// Bump `VERSION` on incompatible changes; readers reject newer versions.
+pub const FORMAT: &str = "ail-archive";
+pub const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const SESSIONS: &str = "sessions.jsonl";
+const MESSAGES: &str = "messages.jsonl";
+const TOOL_CALLS: &str = "tool_calls.jsonl";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub ail_version: String,
+    pub redacted: bool,
+    pub sessions: usize,
+    pub messages: usize,
+    pub tool_calls: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionRecord {
+    id: String,
+    conversation_id: Option<String>,
+    agent: String,
+    project_path: Option<String>,
+    project_name: Option<String>,
+    summary: Option<String>,
+    work_summary: Option<String>,
+    llm_summary: Option<String>,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    machine: Option<String>,
+    #[serde(default)]
+    git_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MessageRecord {
+    session_id: String,
+    role: String,
+    content: String,
+    timestamp: Option<String>,
+    #[serde(default)]
+    files_changed: Vec<String>,
+    uuid: Option<String>,
+    parent_uuid: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ToolCallRecord {
+    session_id: String,
+    tool_name: String,
+    file_path: Option<String>,
+    timestamp: Option<String>,
+    message_uuid: Option<String>,
+    #[serde(default)]
+    is_error: bool,
+    #[serde(default)]
+    is_commit: bool,
+}
+
+/// How `import_archive` treats sessions whose id is already indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnConflict {
+    Skip,
+    Replace,
+}
+
+/// A `--map-path FROM=TO` rewrite of project and file paths.
+#[derive(Debug, Clone)]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+impl PathMapping {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((from, to)) if !from.is_empty() => Ok(Self {
+                from: from.trim_end_matches('/').to_string(),
+                to: to.trim_end_matches('/').to_string(),
+            }),
+            _ => bail!("Invalid path mapping: {} (use FROM=TO, e.g. /Users/x=/home/x)", s),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportResult {
+    pub manifest: Manifest,
+    pub imported: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+}
+
+/// Write `sessions` to a new archive at `path`. Messages and summaries are
+/// passed through `redactor` (use `Redactor::disabled()` to keep them as is).
+/// Sessions without an author are attributed to `identity`.
+pub fn write_archive(
+    db: &Database,
+    sessions: &[SessionRow],
+    path: &Path,
+    redactor: &Redactor,
+    redacted: bool,
+    identity: &Identity,
+) -> Result<Manifest> {
+    if sessions.is_empty() {
+        bail!("No sessions matched");
+    }
+
+    let mut session_lines = Vec::new();
+    let mut message_lines = Vec::new();
+    let mut tool_call_lines = Vec::new();
+    let mut message_count = 0;
+    let mut tool_call_count = 0;
+
+    for s in sessions {
+        let redact = |v: &Option<String>| v.as_deref().map(|t| redactor.redact(t));
+        push_line(
+            &mut session_lines,
+            &SessionRecord {
+                id: s.id.clone(),
+                conversation_id: s.conversation_id.clone(),
+                agent: s.agent.clone(),
+                project_path: s.project_path.clone(),
+                project_name: s.project_name.clone(),
+                summary: redact(&s.summary),
+                work_summary: redact(&s.work_summary),
+                llm_summary: redact(&s.llm_summary),
+                started_at: s.started_at.clone(),
+                ended_at: s.ended_at.clone(),
+                tags: s.tags.split(',').filter(|t| !t.is_empty()).map(String::from).collect(),
+                author: Some(s.author.clone().unwrap_or_else(|| identity.author.clone())),
+                machine: Some(s.machine.clone().unwrap_or_else(|| identity.machine.clone())),
+                git_branch: s.git_branch.clone(),
+            },
+        )?;
+
+        for m in db.get_messages(&s.id)? {
+            push_line(
+                &mut message_lines,
+                &MessageRecord {
+                    session_id: s.id.clone(),
+                    role: m.role,
+                    content: redactor.redact(&m.content),
+                    timestamp: m.timestamp,
+                    files_changed: serde_json::from_str(&m.files_changed).unwrap_or_default(),
+                    uuid: m.uuid,
+                    parent_uuid: m.parent_uuid,
+                },
+            )?;
+            message_count += 1;
+        }
+
+        for tc in db.get_tool_calls(&s.id)? {
+            push_line(
+                &mut tool_call_lines,
+                &ToolCallRecord {
+                    session_id: s.id.clone(),
+                    tool_name: tc.tool_name,
+                    file_path: tc.file_path,
+                    timestamp: tc.timestamp,
+                    message_uuid: tc.message_uuid,
+                    is_error: tc.is_error,
+                    is_commit: tc.is_commit,
+                },
+            )?;
+            tool_call_count += 1;
+        }
+    }
+
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        ail_version: env!("CARGO_PKG_VERSION").to_string(),
+        redacted,
+        sessions: sessions.len(),
+        messages: message_count,
+        tool_calls: tool_call_count,
+    };
+
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let encoder = zstd::Encoder::new(file, 0)?.auto_finish();
+    let mut tar = tar::Builder::new(encoder);
+    append_entry(&mut tar, MANIFEST, &serde_json::to_vec_pretty(&manifest)?)?;
+    append_entry(&mut tar, SESSIONS, &session_lines)?;
+    append_entry(&mut tar, MESSAGES, &message_lines)?;
+    append_entry(&mut tar, TOOL_CALLS, &tool_call_lines)?;
+    tar.into_inner()?;
+
+    Ok(manifest)
+}
+
+/// Merge an archive into the index. Paths are rewritten with `mappings`
+/// (longest matching prefix wins) before insertion.
+pub fn import_archive(db: &Database, path: &Path, mappings: &[PathMapping], on_conflict: OnConflict) -> Result<ImportResult> {
+    let entries = read_entries(path)?;
+    let manifest = parse_manifest(&entries)?;
+
+    let mut messages: HashMap<String, Vec<MessageData>> = HashMap::new();
+    for line in jsonl::<MessageRecord>(&entries, MESSAGES)? {
+        messages.entry(line.session_id).or_default().push(MessageData {
+            role: Role::from_str(&line.role),
+            content: line.content,
+            timestamp: parse_time(line.timestamp.as_deref()),
+            files_changed: line.files_changed.iter().map(|f| map_path(f, mappings)).collect(),
+            uuid: line.uuid,
+            parent_uuid: line.parent_uuid,
+        });
+    }
+    let mut tool_calls: HashMap<String, Vec<ToolCallData>> = HashMap::new();
+    for line in jsonl::<ToolCallRecord>(&entries, TOOL_CALLS)? {
+        tool_calls.entry(line.session_id).or_default().push(ToolCallData {
+            tool_name: line.tool_name,
+            file_path: line.file_path.map(|p| map_path(&p, mappings)),
+            timestamp: parse_time(line.timestamp.as_deref()),
+            message_uuid: line.message_uuid,
+            is_error: line.is_error,
+            is_commit: line.is_commit,
+        });
+    }
+
+    let mut result = ImportResult {
+        manifest,
+        imported: 0,
+        replaced: 0,
+        skipped: 0,
+    };
+    // All or nothing: a bad record leaves the index as it was
+    let tx = db.transaction()?;
+    for record in jsonl::<SessionRecord>(&entries, SESSIONS)? {
+        let Some(agent) = AgentType::from_str(&record.agent) else {
+            bail!("Unknown agent '{}' for session {}", record.agent, record.id);
+        };
+        let exists = db.session_exists(&record.id)?;
+        if exists && on_conflict == OnConflict::Skip {
+            result.skipped += 1;
+            continue;
+        }
+
+        let data = SessionData {
+            messages: messages.remove(&record.id).unwrap_or_default(),
+            tool_calls: tool_calls.remove(&record.id).unwrap_or_default(),
+            id: record.id,
+            conversation_id: record.conversation_id,
+            agent,
+            project_path: record.project_path.map(|p| map_path(&p, mappings).into()),
+            project_name: record.project_name,
+            summary: record.summary,
+            work_summary: record.work_summary,
+            started_at: parse_time(record.started_at.as_deref()),
+            ended_at: parse_time(record.ended_at.as_deref()),
+            tags: record.tags,
+            git_branch: record.git_branch,
+        };
+        if exists {
+            db.delete_session(&data.id)?;
+            result.replaced += 1;
+        } else {
+            result.imported += 1;
+        }
+        db.insert_session(&data)?;
+        if let Some(ref llm_summary) = record.llm_summary {
+            db.update_llm_summary(&data.id, llm_summary)?;
+        }
+        if record.author.is_some() || record.machine.is_some() {
+            db.set_session_origin(&data.id, record.author.as_deref(), record.machine.as_deref())?;
+        }
+    }
+    tx.commit()?;
+    Ok(result)
+}
+
+fn push_line<T: Serialize>(buf: &mut Vec<u8>, record: &T) -> Result<()> {
+    serde_json::to_writer(&mut *buf, record)?;
+    buf.push(b'\n');
+    Ok(())
+}
+
+fn append_entry<W: std::io::Write>(tar: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// Every entry of the archive by name. Unknown entries are kept but ignored,
+/// so later versions can add files without breaking older readers.
+fn read_entries(path: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let decoder = zstd::Decoder::new(file)?;
+    let mut tar = tar::Archive::new(decoder);
+    let mut entries = HashMap::new();
+    for entry in tar.entries().context("Not an ail archive (expected .tar.zst)")? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        entries.insert(name, data);
+    }
+    Ok(entries)
+}
+
+fn parse_manifest(entries: &HashMap<String, Vec<u8>>) -> Result<Manifest> {
+    let raw = entries
+        .get(MANIFEST)
+        .context("Not an ail archive: manifest.json is missing")?;
+    let manifest: Manifest = serde_json::from_slice(raw).context("Corrupt archive manifest")?;
+    if manifest.format != FORMAT {
+        bail!("Not an ail archive (format '{}')", manifest.format);
+    }
+    if manifest.version > VERSION {
+        bail!(
+            "Archive version {} is newer than this ail supports ({}); upgrade ail to import it",
+            manifest.version,
+            VERSION
+        );
+    }
+    Ok(manifest)
+}
+
+fn jsonl<T: for<'de> Deserialize<'de>>(entries: &HashMap<String, Vec<u8>>, name: &str) -> Result<Vec<T>> {
+    let Some(raw) = entries.get(name) else {
+        bail!("Corrupt archive: {} is missing", name);
+    };
+    let text = std::str::from_utf8(raw).with_context(|| format!("Corrupt archive: {}", name))?;
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .enumerate()
+        .map(|(i, l)| serde_json::from_str(l).with_context(|| format!("Corrupt archive: {} line {}", name, i + 1)))
+        .collect()
+}
+
+fn parse_time(ts: Option<&str>) -> Option<DateTime<Utc>> {
+    ts.and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Apply the longest mapping whose `from` is `path` or a directory prefix of it.
+fn map_path(path: &str, mappings: &[PathMapping]) -> String {
+    mappings
+        .iter()
+        .filter(|m| path == m.from || path.strip_prefix(&m.from).is_some_and(|rest| rest.starts_with('/')))
+        .max_by_key(|m| m.from.len())
+        .map(|m| format!("{}{}", m.to, &path[m.from.len()..]))
+        .unwrap_or_else(|| path.to_string())
+}
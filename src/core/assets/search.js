@@ -0,0 +1,22 @@
+// Client-side search over search-index.js; works from file:// without a server.
+(function () {
+  var text = {};
+  (window.AIL_SEARCH_INDEX || []).forEach(function (entry) {
+    text[entry.id] = entry.text.toLowerCase();
+  });
+  var input = document.getElementById('q');
+  var empty = document.getElementById('no-results');
+  var rows = Array.prototype.slice.call(document.querySelectorAll('tr[data-id]'));
+
+  input.addEventListener('input', function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    var shown = 0;
+    rows.forEach(function (row) {
+      var haystack = text[row.getAttribute('data-id')] || '';
+      var match = terms.every(function (t) { return haystack.indexOf(t) !== -1; });
+      row.hidden = !match;
+      if (match) shown++;
+    });
+    empty.hidden = shown > 0;
+  });
+})();
@@ -0,0 +1,67 @@
+/// Rough token estimate without a tokenizer: ~4 ASCII characters per token,
+/// one token per non-ASCII character (CJK, Hangul, emoji tokenize densely).
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text.chars().fold((0usize, 0usize), |(a, o), c| {
+        if c.is_ascii() {
+            (a + 1, o)
+        } else {
+            (a, o + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// Cut `text` so its estimate stays within `max_tokens`, marking the cut with "…".
+pub fn fit_tokens(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+    // Leave room for the ellipsis
+    let limit = max_tokens.saturating_sub(1) * 4;
+    let mut cost = 0;
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        cost += if c.is_ascii() { 1 } else { 4 };
+        if cost > limit {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    format!("{}…", text[..end].trim_end())
+}
+
+/// Tracks how much of a token budget is left while sections are packed.
+pub struct Budget {
+    total: usize,
+    used: usize,
+}
+
+impl Budget {
+    pub fn new(total: usize) -> Self {
+        Self { total, used: 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.total.saturating_sub(self.used)
+    }
+
+    /// `percent` of the total budget, capped by what is left
+    pub fn share(&self, percent: usize) -> usize {
+        (self.total * percent / 100).min(self.remaining())
+    }
+
+    /// Record `text` as used when it fits; returns whether it did.
+    pub fn take(&mut self, text: &str) -> bool {
+        let cost = estimate_tokens(text);
+        if cost <= self.remaining() {
+            self.used += cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+}
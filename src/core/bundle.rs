@@ -0,0 +1,208 @@
+use crate::core::context::DetailLevel;
+use crate::core::db::{Database, SessionFilter, SessionRow, ToolCallRow};
+use crate::core::display::{agent_display_name, change_type, format_datetime, truncate};
+use crate::core::thread;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+/// Which sessions go into a bundle: the explicit ids, narrowed by the
+/// filters when both are given, or else everything matching the filters.
+#[derive(Debug, Clone, Default)]
+pub struct BundleSelection {
+    pub session_ids: Vec<String>,
+    pub project: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub tag: Option<String>,
+}
+
+impl BundleSelection {
+    fn has_filters(&self) -> bool {
+        self.project.is_some() || self.since.is_some() || self.tag.is_some()
+    }
+}
+
+/// Resolve a selection to sessions, oldest first, without duplicates.
+pub fn select_sessions(db: &Database, selection: &BundleSelection) -> Result<Vec<SessionRow>> {
+    let mut sessions = Vec::new();
+    for id in &selection.session_ids {
+        match db.get_session(id)? {
+            Some(s) => sessions.push(s),
+            None => bail!("Session not found: {}", id),
+        }
+    }
+
+    if selection.has_filters() {
+        let filter = SessionFilter {
+            project: selection.project.clone(),
+            tag: selection.tag.clone(),
+            from: selection.since,
+            ..Default::default()
+        };
+        let matching = db.query_all_sessions(&filter)?;
+        if selection.session_ids.is_empty() {
+            sessions = matching;
+        } else {
+            let matching: HashSet<String> = matching.into_iter().map(|s| s.id).collect();
+            sessions.retain(|s| matching.contains(&s.id));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    sessions.retain(|s| seen.insert(s.id.clone()));
+    sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    Ok(sessions)
+}
+
+/// Merge several sessions into one chronological context document with a
+/// combined changed-files table, de-duplicated requests and a per-session timeline.
+pub fn export_bundle(db: &Database, sessions: &[SessionRow], detail: DetailLevel) -> Result<String> {
+    if sessions.is_empty() {
+        bail!("No sessions matched");
+    }
+
+    let mut out = String::new();
+
+    // Header
+    writeln!(out, "# Context Bundle")?;
+    writeln!(out, "- **Sessions**: {}", sessions.len())?;
+    let mut agents: BTreeMap<&str, usize> = BTreeMap::new();
+    for s in sessions {
+        *agents.entry(agent_display_name(&s.agent)).or_default() += 1;
+    }
+    let agent_list: Vec<String> = agents.iter().map(|(a, n)| format!("{} ({})", a, n)).collect();
+    writeln!(out, "- **Agents**: {}", agent_list.join(", "))?;
+    let projects: BTreeSet<&str> = sessions.iter().filter_map(|s| s.project_path.as_deref()).collect();
+    if !projects.is_empty() {
+        let projects: Vec<&str> = projects.into_iter().collect();
+        writeln!(out, "- **Projects**: {}", projects.join(", "))?;
+    }
+    let first = sessions.first().and_then(|s| s.started_at.as_deref());
+    let last = sessions.last().and_then(|s| s.ended_at.as_deref().or(s.started_at.as_deref()));
+    if let (Some(f), Some(l)) = (first, last) {
+        writeln!(out, "- **Period**: {} – {}", format_datetime(f), format_datetime(l))?;
+    }
+    writeln!(out)?;
+
+    // De-duplicated requests
+    let mut requests: Vec<(String, Vec<&str>)> = Vec::new();
+    for s in sessions {
+        let Some(ref summary) = s.summary else { continue };
+        let key = normalize_request(summary);
+        match requests.iter_mut().find(|(r, _)| normalize_request(r) == key) {
+            Some((_, ids)) => ids.push(&s.id),
+            None => requests.push((summary.clone(), vec![&s.id])),
+        }
+    }
+    if !requests.is_empty() {
+        writeln!(out, "## Requests")?;
+        for (request, ids) in &requests {
+            let ids: Vec<String> = ids.iter().map(|id| format!("`{}`", short_id(id))).collect();
+            writeln!(out, "- {} ({})", request, ids.join(", "))?;
+        }
+        writeln!(out)?;
+    }
+
+    // Combined changed files, keyed by repo-relative path so checkouts line up
+    let mut all_tool_calls: Vec<(&SessionRow, Vec<ToolCallRow>)> = Vec::new();
+    for s in sessions {
+        all_tool_calls.push((s, db.get_tool_calls(&s.id)?));
+    }
+    let mut files: BTreeMap<String, (Vec<&'static str>, Vec<String>)> = BTreeMap::new();
+    for (s, tool_calls) in &all_tool_calls {
+        for tc in tool_calls {
+            let Some(change) = change_type(&tc.tool_name) else { continue };
+            let Some(path) = tc.rel_path.clone().or_else(|| tc.file_path.clone()) else { continue };
+            let entry = files.entry(path).or_default();
+            if !entry.0.contains(&change) {
+                entry.0.push(change);
+            }
+            let sid = short_id(&s.id).to_string();
+            if !entry.1.contains(&sid) {
+                entry.1.push(sid);
+            }
+        }
+    }
+    if !files.is_empty() {
+        writeln!(out, "## Changed Files")?;
+        writeln!(out, "| File | Change | Sessions |")?;
+        writeln!(out, "|------|--------|----------|")?;
+        for (path, (changes, ids)) in &files {
+            writeln!(out, "| `{}` | {} | {} |", path, changes.join(", "), ids.join(", "))?;
+        }
+        writeln!(out)?;
+    }
+
+    // Per-session timeline
+    writeln!(out, "## Timeline")?;
+    writeln!(out)?;
+    for (s, tool_calls) in &all_tool_calls {
+        let when = s.started_at.as_deref().map(format_datetime).unwrap_or_else(|| "?".to_string());
+        writeln!(
+            out,
+            "### {} · {} · `{}`",
+            when,
+            agent_display_name(&s.agent),
+            short_id(&s.id)
+        )?;
+        if let Some(ref p) = s.project_name {
+            writeln!(out, "- **Project**: {}", p)?;
+        }
+        if let Some(ref summary) = s.summary {
+            writeln!(out, "- **Request**: {}", summary)?;
+        }
+        if let Some(result) = s.llm_summary.as_ref().or(s.work_summary.as_ref()) {
+            writeln!(out, "- **Result**: {}", result)?;
+        }
+        let mut session_files: Vec<&str> = tool_calls
+            .iter()
+            .filter(|tc| change_type(&tc.tool_name).is_some())
+            .filter_map(|tc| tc.rel_path.as_deref().or(tc.file_path.as_deref()))
+            .collect();
+        session_files.sort();
+        session_files.dedup();
+        if !session_files.is_empty() {
+            writeln!(out, "- **Files**: {}", session_files.join(", "))?;
+        }
+
+        let take = match detail {
+            DetailLevel::Minimal => 0,
+            DetailLevel::Summary => 2,
+            DetailLevel::Full => usize::MAX,
+        };
+        if take > 0 {
+            let messages = db.get_messages(&s.id)?;
+            let live: Vec<_> = thread::live_branch(&messages)
+                .into_iter()
+                .filter(|m| m.role == "user" || m.role == "assistant")
+                .collect();
+            writeln!(out)?;
+            for m in &live[live.len().saturating_sub(take)..] {
+                let label = if m.role == "user" { "You" } else { "AI" };
+                let content = match detail {
+                    DetailLevel::Full => m.content.clone(),
+                    _ => truncate(&m.content, 300),
+                };
+                writeln!(out, "**{}**: {}", label, content)?;
+                writeln!(out)?;
+            }
+        } else {
+            writeln!(out)?;
+        }
+    }
+
+    Ok(out)
+}
+
+fn normalize_request(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '!', '?'])
+        .to_lowercase()
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
@@ -0,0 +1,145 @@
+use crate::core::db::{parse_datetime, parse_duration};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+/// How days and weeks are cut for reports, filters and displayed times:
+/// the timezone, the first day of the week, and the time a work day rolls
+/// over (with `04:00`, a session at 01:30 still counts toward the day before).
+#[derive(Debug, Clone, Copy)]
+pub struct Calendar {
+    /// `None` for the system timezone
+    zone: Option<Tz>,
+    week_start: Weekday,
+    day_start: NaiveTime,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            zone: None,
+            week_start: Weekday::Mon,
+            day_start: NaiveTime::MIN,
+        }
+    }
+}
+
+/// Set once at startup from `[general]`, see `set`
+static CALENDAR: OnceLock<Calendar> = OnceLock::new();
+
+pub fn set(calendar: Calendar) {
+    let _ = CALENDAR.set(calendar);
+}
+
+/// The configured calendar, or the system timezone with Monday weeks
+pub fn calendar() -> &'static Calendar {
+    CALENDAR.get_or_init(Calendar::default)
+}
+
+impl Calendar {
+    /// `timezone` is an IANA name (`Asia/Seoul`) or empty for the system one;
+    /// `week_start` a weekday name; `day_start` a `HH:MM` time.
+    pub fn new(timezone: &str, week_start: &str, day_start: &str) -> Result<Self> {
+        let zone = match timezone.trim() {
+            "" | "local" => None,
+            name => Some(
+                name.parse::<Tz>()
+                    .map_err(|_| anyhow!("Unknown timezone '{}' (use an IANA name like Asia/Seoul)", name))?,
+            ),
+        };
+        let week_start = match week_start.trim().parse::<Weekday>() {
+            Ok(day @ (Weekday::Mon | Weekday::Sun)) => day,
+            _ => bail!("Unknown week_start '{}' (expected monday or sunday)", week_start),
+        };
+        let day_start = NaiveTime::parse_from_str(day_start.trim(), "%H:%M")
+            .map_err(|_| anyhow!("Invalid day_start '{}' (expected HH:MM, e.g. 04:00)", day_start))?;
+        Ok(Self {
+            zone,
+            week_start,
+            day_start,
+        })
+    }
+
+    /// Wall-clock time of `t` in the calendar's timezone
+    pub fn local(&self, t: DateTime<Utc>) -> NaiveDateTime {
+        match self.zone {
+            Some(tz) => t.with_timezone(&tz).naive_local(),
+            None => t.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// The instant a wall-clock time happens. On a DST overlap the earlier
+    /// one; a time skipped by DST moves past the gap.
+    pub fn instant(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.zone {
+            Some(tz) => resolve(&tz, local),
+            None => resolve(&Local, local),
+        }
+    }
+
+    /// The work day `t` belongs to
+    pub fn day_of(&self, t: DateTime<Utc>) -> NaiveDate {
+        (self.local(t) - (self.day_start - NaiveTime::MIN)).date()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.day_of(Utc::now())
+    }
+
+    /// When work day `date` begins
+    pub fn day_start(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.instant(date.and_time(self.day_start))
+    }
+
+    /// From the start of `first` to the last second of `last`
+    pub fn days(&self, first: NaiveDate, last: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            self.day_start(first),
+            self.day_start(last + Duration::days(1)) - Duration::seconds(1),
+        )
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+
+    /// First day of the week `date` is in
+    pub fn week_of(&self, date: NaiveDate) -> NaiveDate {
+        let offset = (7 + date.weekday().num_days_from_monday() - self.week_start.num_days_from_monday()) % 7;
+        date - Duration::days(offset as i64)
+    }
+
+    /// Start of a `--last` span. Hours roll back from now; days, weeks and
+    /// months are whole work days counting today, so `1d` is "today".
+    pub fn since(&self, spec: &str) -> Option<DateTime<Utc>> {
+        let span = parse_duration(spec)?;
+        if spec.trim().ends_with('h') {
+            return Some(Utc::now() - span);
+        }
+        let days = span.num_days().max(1) - 1;
+        Some(self.day_start(self.today() - Duration::days(days)))
+    }
+}
+
+/// A `--since` value: a duration (`3d`, `2w`) back from now, or a date
+pub fn since_ago(spec: &str) -> Result<DateTime<Utc>> {
+    parse_duration(spec)
+        .map(|span| Utc::now() - span)
+        .or_else(|| parse_datetime(spec))
+        .ok_or_else(|| anyhow!("Invalid since: {} (use e.g. 3d, 2w, 1m or a date)", spec))
+}
+
+/// An RFC 3339 timestamp as wall-clock time in the configured timezone
+pub fn format_local(ts: &str, fmt: &str) -> Option<String> {
+    let t = DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&Utc);
+    Some(calendar().local(t).format(fmt).to_string())
+}
+
+fn resolve<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> DateTime<Utc> {
+    let at = |t: NaiveDateTime| zone.from_local_datetime(&t).earliest();
+    at(local)
+        .or_else(|| at(local + Duration::hours(1)))
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| DateTime::<Utc>::from_naive_utc_and_offset(local, Utc))
+}
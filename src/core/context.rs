@@ -1,7 +1,14 @@
+use crate::config;
 use crate::core::db::{Database, MessageRow, SessionRow, ToolCallRow};
-use anyhow::{bail, Result};
-use std::fmt::Write;
+use crate::core::budget::{self, fit_tokens, Budget};
+use crate::core::display::{self, agent_display_name};
+use crate::core::{clock, paths, template, thread};
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::path::Path;
+use std::process::Command;
 
 #[derive(Debug, Clone, Copy)]
 pub enum DetailLevel {
@@ -18,13 +25,52 @@ impl DetailLevel {
             _ => DetailLevel::Summary,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetailLevel::Full => "full",
+            DetailLevel::Summary => "summary",
+            DetailLevel::Minimal => "minimal",
+        }
+    }
 }
 
+/// Export a session using the template configured in `[export] template`.
 pub fn export_context(
     db: &Database,
     session_id: &str,
     detail: DetailLevel,
 ) -> Result<String> {
+    let template = config::load_config()
+        .map(|c| c.export.template)
+        .unwrap_or_else(|_| "default".to_string());
+    export_context_with_template(db, session_id, detail, &template)
+}
+
+pub fn export_context_with_template(
+    db: &Database,
+    session_id: &str,
+    detail: DetailLevel,
+    template_name: &str,
+) -> Result<String> {
+    let session = db
+        .get_session(session_id)?
+        .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+    let messages = db.get_messages(session_id)?;
+    let tool_calls = db.get_tool_calls(session_id)?;
+
+    let ctx = build_template_context(&session, &messages, &tool_calls, detail);
+    template::render(template_name, &ctx)
+}
+
+/// Export a session packed into roughly `max_tokens` tokens.
+///
+/// Sections are filled greedily in priority order: the original request, the
+/// final state, changed files, errors hit along the way and the last
+/// exchanges. Whatever budget is left goes to a compressed one-line-per-turn
+/// outline of the middle of the session.
+pub fn export_context_budgeted(db: &Database, session_id: &str, max_tokens: usize) -> Result<String> {
     let session = db
         .get_session(session_id)?
         .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
@@ -32,201 +78,378 @@ pub fn export_context(
     let messages = db.get_messages(session_id)?;
     let tool_calls = db.get_tool_calls(session_id)?;
 
-    generate_context_markdown(&session, &messages, &tool_calls, detail)
+    Ok(generate_budgeted_markdown(&session, &messages, &tool_calls, max_tokens))
 }
 
-fn generate_context_markdown(
+fn generate_budgeted_markdown(
     session: &SessionRow,
     messages: &[MessageRow],
     tool_calls: &[ToolCallRow],
-    detail: DetailLevel,
-) -> Result<String> {
-    let mut out = String::new();
+    max_tokens: usize,
+) -> String {
+    let mut budget = Budget::new(max_tokens);
 
-    // Header
-    writeln!(out, "# Session Context")?;
-    writeln!(out, "- **Agent**: {}", agent_display_name(&session.agent))?;
+    let mut header = String::from("# Session Context\n");
+    header.push_str(&format!("- **Agent**: {}\n", agent_display_name(&session.agent)));
     if let Some(ref p) = session.project_path {
-        writeln!(out, "- **Project**: {}", p)?;
+        header.push_str(&format!("- **Project**: {}\n", p));
     }
     if let Some(ref t) = session.started_at {
-        writeln!(out, "- **Date**: {}", format_date(t))?;
+        header.push_str(&format!("- **Date**: {}\n", format_date(t)));
     }
-    writeln!(out, "- **Session ID**: {}", session.id)?;
-    writeln!(out)?;
+    header.push_str(&format!("- **Session ID**: {}\n\n", session.id));
+    budget.take(&header);
+
+    let live: Vec<&MessageRow> = thread::live_branch(messages)
+        .into_iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .collect();
+    let first_prompt = live.iter().position(|m| m.role == "user");
+    let last_reply = live.iter().rposition(|m| m.role == "assistant");
 
-    // Work summary
-    writeln!(out, "## Work Summary")?;
-    if let Some(ref s) = session.summary {
-        writeln!(out, "**Request**: {}", s)?;
+    // 1. Original request
+    let request = match first_prompt {
+        Some(i) => section("Original Request", &fit_tokens(&live[i].content, budget.share(20))),
+        None => String::new(),
+    };
+    budget.take(&request);
+
+    // 2. Final state
+    let mut state = String::new();
+    // The rule-based work summary is usually an excerpt of the last reply
+    let result = session
+        .llm_summary
+        .as_ref()
+        .or(session.work_summary.as_ref())
+        .filter(|r| {
+            let head: String = r.chars().take(40).collect();
+            last_reply.is_none_or(|i| !live[i].content.starts_with(&head))
+        });
+    if let Some(r) = result {
+        state.push_str(&format!("**Result**: {}\n", fit_tokens(r, budget.share(5))));
     }
-    if let Some(ref ws) = session.work_summary {
-        writeln!(out, "**Result**: {}", ws)?;
+    if let Some(i) = last_reply {
+        state.push_str(&format!(
+            "**Last reply**: {}\n",
+            fit_tokens(&live[i].content, budget.share(10))
+        ));
     }
-    writeln!(out)?;
-
-    // Changed files
-    let file_changes = extract_file_changes(tool_calls);
-    if !file_changes.is_empty() {
-        writeln!(out, "## Changed Files")?;
-        for (path, change_type) in &file_changes {
-            writeln!(out, "- `{}` ({})", path, change_type)?;
+    let state = if state.is_empty() { state } else { section("Current State", state.trim_end()) };
+    budget.take(&state);
+
+    // 3. Changed files
+    let mut files = String::new();
+    let files_budget = budget.share(10);
+    let changes = extract_file_changes(tool_calls);
+    for (i, (path, change)) in changes.iter().enumerate() {
+        let line = format!("- `{}` ({})\n", path, change);
+        if budget::estimate_tokens(&files) + budget::estimate_tokens(&line) > files_budget {
+            files.push_str(&format!("- … {} more\n", changes.len() - i));
+            break;
         }
-        writeln!(out)?;
+        files.push_str(&line);
     }
+    let files = if files.is_empty() { files } else { section("Changed Files", files.trim_end()) };
+    budget.take(&files);
 
-    match detail {
-        DetailLevel::Minimal => {
-            // Already done
+    // 4. Errors encountered
+    let mut errors = String::new();
+    let errors_budget = budget.share(10);
+    for line in error_lines(messages) {
+        let line = format!("- {}\n", fit_tokens(&line, 60));
+        if budget::estimate_tokens(&errors) + budget::estimate_tokens(&line) > errors_budget {
+            break;
         }
-        DetailLevel::Summary => {
-            // Add last few exchanges
-            let last_messages: Vec<&MessageRow> = messages
-                .iter()
-                .filter(|m| m.role == "user" || m.role == "assistant")
-                .rev()
-                .take(6)
-                .collect::<Vec<_>>()
-                .into_iter()
-                .rev()
-                .collect();
-
-            if !last_messages.is_empty() {
-                writeln!(out, "## Recent Conversation")?;
-                for msg in last_messages {
-                    let role_label = if msg.role == "user" { "You" } else { "AI" };
-                    let content = truncate_content(&msg.content, 500);
-                    writeln!(out, "**{}**: {}", role_label, content)?;
-                    writeln!(out)?;
-                }
-            }
+        errors.push_str(&line);
+    }
+    let errors = if errors.is_empty() { errors } else { section("Errors Encountered", errors.trim_end()) };
+    budget.take(&errors);
+
+    // 5. Last exchanges, newest first, keeping some budget back for the outline
+    let reserve = budget.share(15);
+    let mut recent: Vec<String> = Vec::new();
+    let mut recent_start = live.len();
+    let mut recent_budget = budget.remaining().saturating_sub(reserve);
+    for i in (0..live.len()).rev() {
+        if Some(i) == first_prompt || Some(i) == last_reply {
+            continue;
         }
-        DetailLevel::Full => {
-            // Full conversation
-            writeln!(out, "## Full Conversation")?;
-            for msg in messages {
-                if msg.role == "tool" {
-                    continue;
-                }
-                let role_label = if msg.role == "user" { "You" } else { "AI" };
-                let ts = msg
-                    .timestamp
-                    .as_ref()
-                    .map(|t| format_time(t))
-                    .unwrap_or_default();
-                writeln!(out, "### {} {}", role_label, ts)?;
-                writeln!(out, "{}", msg.content)?;
-                writeln!(out)?;
-            }
+        let label = if live[i].role == "user" { "You" } else { "AI" };
+        let entry = format!("**{}**: {}\n\n", label, fit_tokens(&live[i].content, 300));
+        let cost = budget::estimate_tokens(&entry);
+        if cost > recent_budget {
+            break;
+        }
+        recent_budget -= cost;
+        recent.push(entry);
+        recent_start = i;
+    }
+    recent.reverse();
+    let recent = if recent.is_empty() {
+        String::new()
+    } else {
+        section("Recent Conversation", recent.concat().trim_end())
+    };
+    budget.take(&recent);
+
+    // 6. Everything between the request and the recent window, one line per turn
+    let covered: HashSet<i64> = live[recent_start.min(live.len())..].iter().map(|m| m.id).collect();
+    let turns = thread::build_turns(messages, tool_calls);
+    let middle: Vec<&thread::Turn> = turns
+        .iter()
+        .filter(|t| !t.abandoned)
+        .filter(|t| t.prompt.is_some_and(|p| Some(p.id) != first_prompt.map(|i| live[i].id)))
+        .filter(|t| t.messages().all(|m| !covered.contains(&m.id)))
+        .collect();
+    let mut outline = String::new();
+    for (i, turn) in middle.iter().enumerate() {
+        let Some(prompt) = turn.prompt else { continue };
+        let time = prompt.timestamp.as_deref().map(format_time).unwrap_or_default();
+        let mut line = format!("- {} {}", time, fit_tokens(&prompt.content.replace('\n', " "), 30));
+        if !turn.tool_calls.is_empty() {
+            line.push_str(&format!(" ({})", thread::tool_call_summary(&turn.tool_calls)));
+        }
+        line.push('\n');
+        if budget::estimate_tokens(&outline) + budget::estimate_tokens(&line) + 10 > budget.remaining() {
+            outline.push_str(&format!("- … {} more turns omitted\n", middle.len() - i));
+            break;
         }
+        outline.push_str(&line);
     }
+    let outline = if outline.is_empty() { outline } else { section("Earlier in the Session", outline.trim_end()) };
+    budget.take(&outline);
 
-    Ok(out)
+    let mut out = header;
+    out.push_str(&format!("_Packed to ~{} of {} tokens._\n\n", budget.used(), max_tokens));
+    for part in [request, state, files, errors, outline, recent] {
+        out.push_str(&part);
+    }
+    out
 }
 
-fn extract_file_changes(tool_calls: &[ToolCallRow]) -> Vec<(String, &'static str)> {
-    let mut files = Vec::new();
-    let mut seen = std::collections::HashSet::new();
+fn section(title: &str, body: &str) -> String {
+    format!("## {}\n{}\n\n", title, body)
+}
 
-    for tc in tool_calls {
-        if let Some(ref fp) = tc.file_path {
-            if seen.insert(fp.clone()) {
-                let change_type = match tc.tool_name.as_str() {
-                    "Write" | "create_file" => "created",
-                    "Edit" | "edit_file" => "modified",
-                    "delete_file" => "deleted",
-                    _ => "modified",
-                };
-                files.push((fp.clone(), change_type));
-            }
-        }
-    }
-    files
+/// Distinct lines that look like errors, in the order they appeared
+fn error_lines(messages: &[MessageRow]) -> Vec<String> {
+    let re = Regex::new(r"(?i)\b(error|failed|failure|panicked|exception|traceback)\b").unwrap();
+    let mut seen = HashSet::new();
+    messages
+        .iter()
+        .filter(|m| m.role != "user")
+        .flat_map(|m| m.content.lines())
+        .map(|l| l.trim())
+        .filter(|l| re.is_match(l))
+        .filter(|l| seen.insert(l.to_string()))
+        .map(|l| l.to_string())
+        .collect()
 }
 
-pub fn inject_context(
-    db: &Database,
-    session_id: &str,
-    project_path: &Path,
-) -> Result<()> {
-    let context = export_context(db, session_id, DetailLevel::Summary)?;
-    let claude_md = project_path.join("CLAUDE.md");
+/// Everything a template can see. See README "Export Templates" for the fields.
+#[derive(Serialize)]
+struct TemplateContext {
+    detail: &'static str,
+    session: SessionInfo,
+    messages: Vec<MessageInfo>,
+    /// Last few exchanges on the live branch (what `--detail summary` shows)
+    recent: Vec<MessageInfo>,
+    turns: Vec<TurnInfo>,
+    tool_calls: Vec<ToolCallInfo>,
+    files: Vec<FileInfo>,
+    git: Option<GitInfo>,
+}
 
-    let mut content = if claude_md.exists() {
-        std::fs::read_to_string(&claude_md)?
-    } else {
-        String::new()
-    };
+#[derive(Serialize)]
+struct SessionInfo {
+    id: String,
+    agent: String,
+    agent_name: String,
+    project_path: Option<String>,
+    project_name: Option<String>,
+    summary: Option<String>,
+    work_summary: Option<String>,
+    llm_summary: Option<String>,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+    date: Option<String>,
+    message_count: i64,
+    tags: Vec<String>,
+}
 
-    // Remove existing ail context block if present
-    let start_marker = "<!-- ail:context:start -->";
-    let end_marker = "<!-- ail:context:end -->";
-    if let Some(start) = content.find(start_marker) {
-        if let Some(end) = content.find(end_marker) {
-            content = format!(
-                "{}{}",
-                &content[..start],
-                &content[end + end_marker.len()..]
-            );
-        }
-    }
+#[derive(Serialize, Clone)]
+struct MessageInfo {
+    role: String,
+    /// "You" / "AI"
+    label: &'static str,
+    content: String,
+    timestamp: Option<String>,
+    time: Option<String>,
+    abandoned: bool,
+}
 
-    // Append new context
-    let inject_block = format!(
-        "\n{}\n{}\n{}\n",
-        start_marker, context, end_marker
-    );
-    content.push_str(&inject_block);
+#[derive(Serialize)]
+struct TurnInfo {
+    prompt: Option<MessageInfo>,
+    replies: Vec<MessageInfo>,
+    messages: Vec<MessageInfo>,
+    tool_calls: Vec<ToolCallInfo>,
+    tool_summary: String,
+    depth: usize,
+    abandoned: bool,
+    branch_start: bool,
+}
 
-    std::fs::write(&claude_md, content)?;
-    Ok(())
+#[derive(Serialize, Clone)]
+struct ToolCallInfo {
+    tool: String,
+    file_path: Option<String>,
+    rel_path: Option<String>,
+    timestamp: Option<String>,
 }
 
-pub fn auto_inject(db: &Database) -> Result<String> {
-    let cwd = std::env::current_dir()?;
-    let cwd_str = cwd.to_string_lossy().to_string();
+#[derive(Serialize)]
+struct FileInfo {
+    path: String,
+    /// Repo-relative path, when known
+    rel_path: Option<String>,
+    change: &'static str,
+}
 
-    // Find the most recent session for the current project
-    let sessions = db.list_sessions(None, Some(&cwd_str), None, None, 1)?;
+/// Repository state of the project directory at export time
+#[derive(Serialize)]
+struct GitInfo {
+    root: String,
+    branch: Option<String>,
+    commit: Option<String>,
+    remote: Option<String>,
+}
 
-    if let Some(session) = sessions.first() {
-        inject_context(db, &session.id, &cwd)?;
-        Ok(session.id.clone())
-    } else {
-        bail!("No sessions found for current project: {}", cwd_str)
+fn build_template_context(
+    session: &SessionRow,
+    messages: &[MessageRow],
+    tool_calls: &[ToolCallRow],
+    detail: DetailLevel,
+) -> TemplateContext {
+    let live: HashSet<i64> = thread::live_branch(messages).iter().map(|m| m.id).collect();
+    let message_info = |m: &MessageRow| MessageInfo {
+        role: m.role.clone(),
+        label: if m.role == "user" { "You" } else { "AI" },
+        content: m.content.clone(),
+        timestamp: m.timestamp.clone(),
+        time: m.timestamp.as_deref().map(format_time),
+        abandoned: !live.contains(&m.id),
+    };
+    let tool_call_info = |tc: &ToolCallRow| ToolCallInfo {
+        tool: tc.tool_name.clone(),
+        file_path: tc.file_path.clone(),
+        rel_path: tc.rel_path.clone(),
+        timestamp: tc.timestamp.clone(),
+    };
+
+    let conversation: Vec<&MessageRow> = messages.iter().filter(|m| m.role != "tool").collect();
+    let recent: Vec<MessageInfo> = {
+        let live_msgs: Vec<&MessageRow> = thread::live_branch(messages)
+            .into_iter()
+            .filter(|m| m.role == "user" || m.role == "assistant")
+            .collect();
+        live_msgs[live_msgs.len().saturating_sub(6)..]
+            .iter()
+            .map(|m| message_info(m))
+            .collect()
+    };
+
+    let turns = thread::build_turns(messages, tool_calls)
+        .iter()
+        .map(|t| TurnInfo {
+            prompt: t.prompt.map(message_info),
+            replies: t.replies.iter().filter(|m| m.role != "tool").map(|m| message_info(m)).collect(),
+            messages: t.messages().filter(|m| m.role != "tool").map(message_info).collect(),
+            tool_calls: t.tool_calls.iter().map(|tc| tool_call_info(tc)).collect(),
+            tool_summary: thread::tool_call_summary(&t.tool_calls),
+            depth: t.depth,
+            abandoned: t.abandoned,
+            branch_start: t.branch_start,
+        })
+        .collect();
+
+    TemplateContext {
+        detail: detail.as_str(),
+        session: SessionInfo {
+            id: session.id.clone(),
+            agent: session.agent.clone(),
+            agent_name: agent_display_name(&session.agent).to_string(),
+            project_path: session.project_path.clone(),
+            project_name: session.project_name.clone(),
+            summary: session.summary.clone(),
+            work_summary: session.work_summary.clone(),
+            llm_summary: session.llm_summary.clone(),
+            started_at: session.started_at.clone(),
+            ended_at: session.ended_at.clone(),
+            date: session.started_at.as_deref().map(format_date),
+            message_count: session.message_count,
+            tags: session
+                .tags
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .collect(),
+        },
+        messages: conversation.iter().map(|m| message_info(m)).collect(),
+        recent,
+        turns,
+        tool_calls: tool_calls.iter().map(tool_call_info).collect(),
+        files: extract_file_changes(tool_calls)
+            .into_iter()
+            .map(|(path, change)| FileInfo {
+                rel_path: tool_calls
+                    .iter()
+                    .find(|tc| tc.file_path.as_deref() == Some(path.as_str()))
+                    .and_then(|tc| tc.rel_path.clone()),
+                path,
+                change,
+            })
+            .collect(),
+        git: session.project_path.as_deref().and_then(git_info),
     }
 }
 
-fn agent_display_name(agent: &str) -> &str {
-    match agent {
-        "claude-code" => "Claude Code",
-        "codex" => "Codex",
-        "cursor" => "Cursor",
-        _ => agent,
-    }
+fn git_info(project_path: &str) -> Option<GitInfo> {
+    let root = paths::find_git_root(Path::new(project_path))?;
+    let git = |args: &[&str]| -> Option<String> {
+        let out = Command::new("git").arg("-C").arg(&root).args(args).output().ok()?;
+        if !out.status.success() {
+            return None;
+        }
+        let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    };
+    Some(GitInfo {
+        root: root.to_string_lossy().to_string(),
+        branch: git(&["rev-parse", "--abbrev-ref", "HEAD"]),
+        commit: git(&["rev-parse", "--short", "HEAD"]),
+        remote: git(&["remote", "get-url", "origin"]),
+    })
 }
 
-fn format_date(ts: &str) -> String {
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
-        dt.format("%Y-%m-%d").to_string()
-    } else {
-        ts.to_string()
+fn extract_file_changes(tool_calls: &[ToolCallRow]) -> Vec<(String, &'static str)> {
+    let mut files = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for tc in tool_calls {
+        if let Some(ref fp) = tc.file_path {
+            if seen.insert(fp.clone()) {
+                files.push((fp.clone(), display::change_type(&tc.tool_name).unwrap_or("modified")));
+            }
+        }
     }
+    files
 }
 
-fn format_time(ts: &str) -> String {
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
-        dt.format("%H:%M").to_string()
-    } else {
-        ts.to_string()
-    }
+fn format_date(ts: &str) -> String {
+    clock::format_local(ts, "%Y-%m-%d").unwrap_or_else(|| ts.to_string())
 }
 
-fn truncate_content(s: &str, max_chars: usize) -> String {
-    if s.chars().count() <= max_chars {
-        s.to_string()
-    } else {
-        let end = s.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(s.len());
-        format!("{}...", &s[..end])
-    }
+fn format_time(ts: &str) -> String {
+    clock::format_local(ts, "%H:%M").unwrap_or_else(|| ts.to_string())
 }
@@ -0,0 +1,406 @@
+use crate::adapters::claude_code::ClaudeCodeAdapter;
+use crate::adapters::codex::CodexAdapter;
+use crate::adapters::traits::{AgentAdapter, AgentType};
+use crate::core::db::{Database, MessageRow, SessionRow, ToolCallRow};
+use crate::core::team::Identity;
+use crate::core::thread;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Placeholder output for replayed tool calls; ail only indexes the call itself
+const TOOL_OUTPUT_NOTE: &str = "(output not recorded; session converted by ail)";
+
+/// A session written in another agent's native format.
+pub struct Converted {
+    /// ail session id of the new session (already indexed)
+    pub session_id: String,
+    pub path: PathBuf,
+    pub resume_command: String,
+}
+
+/// Replay the live branch of an indexed session as a new session of the
+/// `target` agent, written where that agent looks for resumable sessions.
+pub fn convert_session(db: &Database, session_id: &str, target: AgentType) -> Result<Converted> {
+    let adapter: Box<dyn AgentAdapter> = match target {
+        AgentType::ClaudeCode => Box::new(ClaudeCodeAdapter::new()),
+        AgentType::Codex => Box::new(CodexAdapter::new()),
+        AgentType::Cursor => {
+            bail!("Converting to Cursor is not supported: Cursor keeps sessions in its own database")
+        }
+    };
+    convert_with(db, session_id, adapter.as_ref())
+}
+
+/// `convert_session` into `adapter`'s data directory
+fn convert_with(db: &Database, session_id: &str, adapter: &dyn AgentAdapter) -> Result<Converted> {
+    let target = adapter.agent_type();
+    let session = db
+        .get_session(session_id)?
+        .with_context(|| format!("Session not found: {}", session_id))?;
+    if session.agent == target.as_str() {
+        bail!("Session {} is already a {} session", session_id, target.display_name());
+    }
+
+    let messages = db.get_messages(&session.id)?;
+    let tool_calls = db.get_tool_calls(&session.id)?;
+    let live: Vec<&MessageRow> = thread::live_branch(&messages)
+        .into_iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .collect();
+    if live.is_empty() {
+        bail!("Session {} has no messages to convert", session_id);
+    }
+    let turns = attach_tool_calls(&live, &tool_calls);
+
+    let cwd = match session.project_path {
+        Some(ref p) => PathBuf::from(p),
+        None => std::env::current_dir()?,
+    };
+    let new_id = Uuid::new_v4().to_string();
+
+    let (path, lines) = match target {
+        AgentType::ClaudeCode => {
+            let path = adapter
+                .data_dir()
+                .join("projects")
+                .join(encode_project_dir(&cwd))
+                .join(format!("{}.jsonl", new_id));
+            (path, claude_code_lines(&session, &turns, &new_id, &cwd))
+        }
+        AgentType::Codex => {
+            let now = Utc::now();
+            let path = adapter
+                .data_dir()
+                .join("sessions")
+                .join(now.format("%Y/%m/%d").to_string())
+                .join(format!("rollout-{}-{}.jsonl", now.format("%Y-%m-%dT%H-%M-%S"), new_id));
+            (path, codex_lines(&session, &turns, &new_id, &cwd))
+        }
+        AgentType::Cursor => {
+            bail!("Converting to Cursor is not supported: Cursor keeps sessions in its own database")
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for line in &lines {
+        content.push_str(&serde_json::to_string(line)?);
+        content.push('\n');
+    }
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // Index right away so the new session shows up in ail without a rescan
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let data = adapter
+        .get_session(&stem)?
+        .with_context(|| format!("Written session could not be read back: {}", path.display()))?;
+    db.insert_session(&data)?;
+    let me = Identity::load();
+    db.set_session_origin(&data.id, Some(&me.author), Some(&me.machine))?;
+
+    let resume_id = data.conversation_id.as_deref().unwrap_or(&data.id);
+    let resume_command = adapter.resume_command(resume_id, Some(&cwd.to_string_lossy()));
+    Ok(Converted {
+        session_id: data.id,
+        path,
+        resume_command,
+    })
+}
+
+/// A live-branch message with the tool calls it made.
+struct ReplayTurn<'a> {
+    message: &'a MessageRow,
+    tool_calls: Vec<&'a ToolCallRow>,
+}
+
+/// Pair each tool call with its message: by `message_uuid` when the source
+/// recorded one, otherwise the last message at or before its time.
+fn attach_tool_calls<'a>(live: &[&'a MessageRow], tool_calls: &'a [ToolCallRow]) -> Vec<ReplayTurn<'a>> {
+    let mut turns: Vec<ReplayTurn> = live
+        .iter()
+        .map(|m| ReplayTurn {
+            message: m,
+            tool_calls: Vec::new(),
+        })
+        .collect();
+
+    for tc in tool_calls {
+        let by_uuid = tc.message_uuid.as_ref().and_then(|u| {
+            turns
+                .iter()
+                .position(|t| t.message.uuid.as_ref() == Some(u))
+        });
+        let owner = match (by_uuid, &tc.message_uuid, &tc.timestamp) {
+            (Some(i), _, _) => Some(i),
+            // Recorded on a message that is not on the live branch
+            (None, Some(_), _) => None,
+            (None, None, Some(ts)) => turns
+                .iter()
+                .rposition(|t| t.message.timestamp.as_deref().is_some_and(|mt| mt <= ts.as_str())),
+            (None, None, None) => None,
+        };
+        if let Some(i) = owner {
+            turns[i].tool_calls.push(tc);
+        }
+    }
+    turns
+}
+
+/// `/Users/me/my.app` → `-Users-me-my-app`, as Claude Code names project directories
+fn encode_project_dir(path: &Path) -> String {
+    path.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Message timestamps, falling back to the previous one (or the session start)
+/// so every written line carries a time.
+fn timestamps(session: &SessionRow, turns: &[ReplayTurn]) -> Vec<String> {
+    let mut last = session
+        .started_at
+        .clone()
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+    turns
+        .iter()
+        .map(|t| {
+            if let Some(ts) = t.message.timestamp.as_deref().and_then(normalize_timestamp) {
+                last = ts;
+            }
+            last.clone()
+        })
+        .collect()
+}
+
+fn normalize_timestamp(ts: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.with_timezone(&Utc).to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+fn tool_input(tc: &ToolCallRow) -> Value {
+    match tc.file_path {
+        Some(ref p) => json!({ "file_path": p }),
+        None => json!({}),
+    }
+}
+
+fn claude_code_lines(session: &SessionRow, turns: &[ReplayTurn], new_id: &str, cwd: &Path) -> Vec<Value> {
+    let cwd = cwd.to_string_lossy();
+    let times = timestamps(session, turns);
+    let mut lines = Vec::new();
+    let mut parent: Option<String> = None;
+
+    let mut push = |lines: &mut Vec<Value>, kind: &str, ts: &str, message: Value| {
+        let uuid = Uuid::new_v4().to_string();
+        lines.push(json!({
+            "parentUuid": parent,
+            "isSidechain": false,
+            "userType": "external",
+            "cwd": cwd,
+            "sessionId": new_id,
+            "version": env!("CARGO_PKG_VERSION"),
+            "type": kind,
+            "message": message,
+            "uuid": uuid,
+            "timestamp": ts,
+        }));
+        parent = Some(uuid);
+    };
+
+    for (turn, ts) in turns.iter().zip(&times) {
+        let mut content = Vec::new();
+        if turn.message.role == "user" {
+            push(&mut lines, "user", ts, json!({ "role": "user", "content": turn.message.content }));
+            // Calls made before any reply text become a tool-only assistant line
+            if turn.tool_calls.is_empty() {
+                continue;
+            }
+        } else {
+            content.push(json!({ "type": "text", "text": turn.message.content }));
+        }
+
+        let mut results = Vec::new();
+        for tc in &turn.tool_calls {
+            let id = format!("toolu_{}", Uuid::new_v4().simple());
+            content.push(json!({ "type": "tool_use", "id": id, "name": tc.tool_name, "input": tool_input(tc) }));
+            results.push(json!({ "type": "tool_result", "tool_use_id": id, "content": TOOL_OUTPUT_NOTE }));
+        }
+        push(
+            &mut lines,
+            "assistant",
+            ts,
+            json!({ "role": "assistant", "type": "message", "content": content }),
+        );
+        // Every tool_use needs a matching tool_result before the next turn
+        if !results.is_empty() {
+            push(&mut lines, "user", ts, json!({ "role": "user", "content": results }));
+        }
+    }
+    lines
+}
+
+fn codex_lines(session: &SessionRow, turns: &[ReplayTurn], new_id: &str, cwd: &Path) -> Vec<Value> {
+    let times = timestamps(session, turns);
+    let start = times.first().cloned().unwrap_or_else(|| Utc::now().to_rfc3339());
+    let mut lines = vec![json!({
+        "timestamp": start,
+        "type": "session_meta",
+        "payload": {
+            "id": new_id,
+            "timestamp": start,
+            "cwd": cwd.to_string_lossy(),
+            "originator": "ail",
+            "cli_version": env!("CARGO_PKG_VERSION"),
+            "instructions": null,
+        },
+    })];
+    let item = |ts: &str, payload: Value| json!({ "timestamp": ts, "type": "response_item", "payload": payload });
+
+    for (turn, ts) in turns.iter().zip(&times) {
+        let (role, part) = if turn.message.role == "user" {
+            ("user", "input_text")
+        } else {
+            ("assistant", "output_text")
+        };
+        lines.push(item(
+            ts,
+            json!({
+                "type": "message",
+                "role": role,
+                "content": [{ "type": part, "text": turn.message.content }],
+            }),
+        ));
+        for tc in &turn.tool_calls {
+            let call_id = format!("call_{}", Uuid::new_v4().simple());
+            lines.push(item(
+                ts,
+                json!({
+                    "type": "function_call",
+                    "name": tc.tool_name,
+                    "arguments": tool_input(tc).to_string(),
+                    "call_id": call_id,
+                }),
+            ));
+            lines.push(item(
+                ts,
+                json!({ "type": "function_call_output", "call_id": call_id, "output": TOOL_OUTPUT_NOTE }),
+            ));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::traits::SessionData;
+
+    /// A scratch directory, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("ail-convert-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Copy a fixture from tests/fixtures/convert to `dest`
+    fn install_fixture(name: &str, dest: &Path) {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/convert").join(name);
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        std::fs::copy(src, dest).unwrap();
+    }
+
+    /// The conversation as (role, text) and the tool calls as (tool, file)
+    #[allow(clippy::type_complexity)]
+    fn transcript(session: &SessionData) -> (Vec<(String, String)>, Vec<(String, Option<String>)>) {
+        let messages = session
+            .messages
+            .iter()
+            .map(|m| (m.role.as_str().to_string(), m.content.clone()))
+            .collect();
+        let tool_calls = session
+            .tool_calls
+            .iter()
+            .map(|tc| (tc.tool_name.clone(), tc.file_path.clone()))
+            .collect();
+        (messages, tool_calls)
+    }
+
+    /// Convert `original` to the other agent and back, checking that each
+    /// written session parses to the same messages and tool calls.
+    fn assert_round_trip(original: SessionData, from: &dyn AgentAdapter, to: &dyn AgentAdapter, db: &Database) {
+        let expected = transcript(&original);
+        assert!(!expected.0.is_empty() && !expected.1.is_empty(), "fixture parsed to nothing");
+        db.insert_session(&original).unwrap();
+
+        let there = convert_with(db, &original.id, to).unwrap();
+        let parsed = to.get_session(&there.session_id).unwrap().expect("converted session");
+        assert_eq!(transcript(&parsed), expected);
+        assert_eq!(parsed.project_path, original.project_path);
+
+        let back = convert_with(db, &there.session_id, from).unwrap();
+        let parsed = from.get_session(&back.session_id).unwrap().expect("re-converted session");
+        assert_eq!(transcript(&parsed), expected);
+        assert_eq!(parsed.project_path, original.project_path);
+    }
+
+    #[test]
+    fn claude_code_round_trips_through_codex() {
+        let scratch = Scratch::new();
+        let claude = ClaudeCodeAdapter::with_data_dir(scratch.0.join("claude"));
+        let codex = CodexAdapter::with_data_dir(scratch.0.join("codex"));
+        install_fixture(
+            "claude-code.jsonl",
+            &scratch.0.join("claude/projects/-tmp-ail-fixture-app/cc-fixture.jsonl"),
+        );
+        let db = Database::open(&scratch.0.join("index.db")).unwrap();
+
+        let original = claude.get_session("cc-fixture").unwrap().expect("fixture session");
+        assert_round_trip(original, &claude, &codex, &db);
+    }
+
+    #[test]
+    fn codex_round_trips_through_claude_code() {
+        let scratch = Scratch::new();
+        let claude = ClaudeCodeAdapter::with_data_dir(scratch.0.join("claude"));
+        let codex = CodexAdapter::with_data_dir(scratch.0.join("codex"));
+        install_fixture(
+            "codex.jsonl",
+            &scratch.0.join("codex/sessions/2026/10/13/rollout-2026-10-13T14-00-00-fixture.jsonl"),
+        );
+        let db = Database::open(&scratch.0.join("index.db")).unwrap();
+
+        let original = codex
+            .get_session("rollout-2026-10-13T14-00-00-fixture")
+            .unwrap()
+            .expect("fixture session");
+        assert_round_trip(original, &codex, &claude, &db);
+    }
+
+    #[test]
+    fn codex_resume_uses_the_subcommand() {
+        let codex = CodexAdapter::with_data_dir(PathBuf::from("/nonexistent"));
+        assert_eq!(
+            codex.resume_command("0199a1b2", Some("/work/api")),
+            "cd /work/api && codex resume 0199a1b2"
+        );
+    }
+}
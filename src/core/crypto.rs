@@ -0,0 +1,155 @@
+use crate::config;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{bail, Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use std::path::{Path, PathBuf};
+
+/// Prefix of an encrypted column value: `enc1:<base64(nonce || ciphertext)>`
+const PREFIX: &str = "enc1:";
+const NONCE_LEN: usize = 12;
+
+/// Environment variable read instead of prompting for the passphrase
+pub const PASSPHRASE_ENV: &str = "AIL_PASSPHRASE";
+
+/// 256-bit key for the index, derived from a passphrase or keyfile with Argon2id.
+#[derive(Clone)]
+pub struct IndexKey([u8; 32]);
+
+impl IndexKey {
+    pub fn derive(secret: &[u8], salt: &[u8]) -> Result<Self> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(secret, salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+        Ok(Self(key))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let cipher = Aes256Gcm::new(&self.0.into());
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+        let mut raw = nonce.to_vec();
+        raw.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", PREFIX, BASE64.encode(raw)))
+    }
+
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(encoded) = value.strip_prefix(PREFIX) else {
+            bail!("Value is not encrypted");
+        };
+        let raw = BASE64.decode(encoded).context("Corrupt encrypted value")?;
+        if raw.len() < NONCE_LEN {
+            bail!("Corrupt encrypted value");
+        }
+        let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(&self.0.into())
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow::anyhow!("Decryption failed (wrong key?)"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+}
+
+impl Drop for IndexKey {
+    fn drop(&mut self) {
+        self.0 = [0u8; 32];
+    }
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+pub fn new_salt() -> String {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    BASE64.encode(salt)
+}
+
+pub fn decode_salt(salt: &str) -> Result<Vec<u8>> {
+    BASE64.decode(salt).context("Corrupt encryption salt")
+}
+
+/// The passphrase or keyfile contents: `--keyfile`, then `$AIL_PASSPHRASE`,
+/// then an interactive prompt (asked twice when `confirm` is set).
+pub fn read_secret(keyfile: Option<&Path>, confirm: bool) -> Result<Vec<u8>> {
+    if let Some(path) = keyfile {
+        let secret = std::fs::read(path)
+            .with_context(|| format!("Failed to read keyfile: {}", path.display()))?;
+        if secret.is_empty() {
+            bail!("Keyfile is empty: {}", path.display());
+        }
+        return Ok(secret);
+    }
+    if let Ok(pass) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(pass.into_bytes());
+    }
+
+    let pass = rpassword::prompt_password("Index passphrase: ")?;
+    if pass.is_empty() {
+        bail!("Passphrase must not be empty");
+    }
+    if confirm && rpassword::prompt_password("Repeat passphrase: ")? != pass {
+        bail!("Passphrases do not match");
+    }
+    Ok(pass.into_bytes())
+}
+
+/// Where the unlocked key is cached: the per-login runtime dir when there is one.
+fn cached_key_path() -> PathBuf {
+    dirs::runtime_dir()
+        .map(|d| d.join("ail"))
+        .unwrap_or_else(config::data_dir)
+        .join("session.key")
+}
+
+/// Cache `key` until `ttl` from now, readable only by the current user.
+pub fn cache_key(key: &IndexKey, ttl: Duration) -> Result<PathBuf> {
+    let path = cached_key_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = format!("{}\n{}\n", (Utc::now() + ttl).to_rfc3339(), BASE64.encode(key.0));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    use std::io::Write;
+    options.open(&path)?.write_all(content.as_bytes())?;
+    Ok(path)
+}
+
+/// The cached key, unless it is missing or expired (expired keys are removed).
+pub fn load_cached_key() -> Option<IndexKey> {
+    let path = cached_key_path();
+    let content = std::fs::read_to_string(&path).ok()?;
+    let mut lines = content.lines();
+    let expires = lines.next().and_then(|l| DateTime::parse_from_rfc3339(l).ok())?;
+    if expires < Utc::now() {
+        let _ = std::fs::remove_file(&path);
+        return None;
+    }
+    let raw = BASE64.decode(lines.next()?).ok()?;
+    Some(IndexKey(raw.try_into().ok()?))
+}
+
+/// Forget the cached key. Returns whether there was one.
+pub fn clear_cached_key() -> Result<bool> {
+    let path = cached_key_path();
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
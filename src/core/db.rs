@@ -1,13 +1,36 @@
 use crate::adapters::traits::*;
-use anyhow::{Context, Result};
+use crate::config;
+use crate::core::activity;
+use crate::core::clock;
+use crate::core::crypto::{self, IndexKey};
+use crate::core::outcome::{self, Evidence, Outcome};
+use crate::core::paths;
+use crate::core::refs;
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use std::path::Path;
 
 pub struct Database {
     conn: Connection,
+    /// Whether `messages.content` is stored encrypted
+    encrypted: bool,
+    /// Present when the index is encrypted and unlocked
+    key: Option<IndexKey>,
+    /// Gap between events that counts as a break when computing active time
+    idle: chrono::Duration,
+    /// Ticket key patterns for `session_refs`
+    tickets: refs::RefMatcher,
+    /// Opened with `open_read_only`; lazy upkeep is skipped
+    read_only: bool,
 }
 
+/// Plaintext encrypted into `meta` to check a derived key
+const KEY_CHECK: &str = "ail-index-key";
+
+/// Shown in place of encrypted summaries while the index is locked
+const LOCKED_SUMMARY: &str = "[locked]";
+
 #[derive(Debug, Clone)]
 pub struct SessionRow {
     pub id: String,
@@ -25,6 +48,17 @@ pub struct SessionRow {
     pub files_modified: i64,
     pub files_deleted: i64,
     pub tags: String,
+    /// Who ran the session, for indexes shared by a team
+    pub author: Option<String>,
+    /// Machine the session was indexed on
+    pub machine: Option<String>,
+    /// Active working time (idle gaps removed), see `core::activity`
+    pub active_secs: Option<i64>,
+    pub git_branch: Option<String>,
+    /// `success`, `failed`, `abandoned` or `in-progress`, see `core::outcome`
+    pub outcome: Option<String>,
+    /// Why the session got its outcome, e.g. `user said "never mind"`
+    pub outcome_reason: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +69,10 @@ pub struct MessageRow {
     pub content: String,
     pub timestamp: Option<String>,
     pub files_changed: String,
+    /// Agent-assigned message id, when the agent records one
+    pub uuid: Option<String>,
+    /// `uuid` of the message this one replied to (conversation tree edge)
+    pub parent_uuid: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +86,7 @@ pub struct SearchResult {
     pub timestamp: Option<String>,
     pub summary: Option<String>,
     pub started_at: Option<String>,
+    pub author: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -56,7 +95,85 @@ pub struct ToolCallRow {
     pub session_id: String,
     pub tool_name: String,
     pub file_path: Option<String>,
+    /// `file_path` relative to the git root / project, when it could be determined
+    pub rel_path: Option<String>,
     pub timestamp: Option<String>,
+    /// `uuid` of the message that issued this call
+    pub message_uuid: Option<String>,
+    pub is_error: bool,
+    pub is_commit: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedSearchRow {
+    pub name: String,
+    pub query: String,
+    pub created_at: Option<String>,
+}
+
+/// Structured session filter, produced by `search::parse_query`.
+/// Every field is optional; unset fields don't constrain the result.
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    pub keyword: Option<String>,
+    pub agent: Option<String>,
+    pub project: Option<String>,
+    pub tag: Option<String>,
+    pub file: Option<String>,
+    /// Other names `file` has had (git renames), matched like it
+    pub file_aliases: Vec<String>,
+    pub author: Option<String>,
+    pub outcome: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Opaque keyset pagination cursor.
+///
+/// Session listings page on `(started_at, id)`; FTS searches page on
+/// `(rank, rowid)`. Encoded as hex so it survives shells and JSON untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cursor {
+    Session { started_at: String, id: String },
+    Search { rank: f64, rowid: i64 },
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let raw = match self {
+            Cursor::Session { started_at, id } => format!("s\u{1f}{}\u{1f}{}", started_at, id),
+            Cursor::Search { rank, rowid } => format!("r\u{1f}{}\u{1f}{}", rank, rowid),
+        };
+        raw.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn decode(s: &str) -> Result<Self> {
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| s.get(i..i + 2).and_then(|h| u8::from_str_radix(h, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| anyhow::anyhow!("Invalid cursor: {}", s))?;
+        let raw = String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("Invalid cursor: {}", s))?;
+
+        let parts: Vec<&str> = raw.splitn(3, '\u{1f}').collect();
+        match parts.as_slice() {
+            ["s", started_at, id] => Ok(Cursor::Session {
+                started_at: started_at.to_string(),
+                id: id.to_string(),
+            }),
+            ["r", rank, rowid] => Ok(Cursor::Search {
+                rank: rank.parse().map_err(|_| anyhow::anyhow!("Invalid cursor: {}", s))?,
+                rowid: rowid.parse().map_err(|_| anyhow::anyhow!("Invalid cursor: {}", s))?,
+            }),
+            _ => bail!("Invalid cursor: {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -64,6 +181,12 @@ pub struct Stats {
     pub total_sessions: i64,
     pub sessions_by_agent: Vec<(String, i64)>,
     pub sessions_by_project: Vec<(String, i64)>,
+    /// Empty unless sessions carry authors (a team index)
+    pub sessions_by_author: Vec<(String, i64)>,
+    /// Active working time in seconds, overall and per agent/project
+    pub total_active_secs: i64,
+    pub active_secs_by_agent: Vec<(String, i64)>,
+    pub active_secs_by_project: Vec<(String, i64)>,
     pub total_files_created: i64,
     pub total_files_modified: i64,
     pub total_files_deleted: i64,
@@ -80,15 +203,85 @@ impl Database {
 
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")?;
 
-        let db = Self { conn };
+        let mut db = Self {
+            conn,
+            encrypted: false,
+            key: None,
+            idle: default_idle(),
+            tickets: refs::RefMatcher::default(),
+            read_only: false,
+        };
         db.init_schema()?;
         db.migrate()?;
+        db.load_encryption()?;
+        db.load_idle()?;
+        db.load_tickets()?;
+        Ok(db)
+    }
+
+    /// Open an existing index without writing to it (a shared team index).
+    /// Schema migrations are skipped, so the file must come from this version.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            bail!("No index at {}", path.display());
+        }
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open database at {}", path.display()))?;
+
+        let mut db = Self {
+            conn,
+            encrypted: false,
+            key: None,
+            idle: default_idle(),
+            tickets: refs::RefMatcher::default(),
+            read_only: true,
+        };
+        db.load_encryption()?;
+        db.load_idle()?;
+        db.load_tickets()?;
         Ok(db)
     }
 
+    /// Whether the index was opened with `open_read_only`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Start a transaction; everything written through this connection until
+    /// it is committed is rolled back if it's dropped
+    pub fn transaction(&self) -> Result<rusqlite::Transaction<'_>> {
+        Ok(self.conn.unchecked_transaction()?)
+    }
+
+    /// Bring a freshly opened index in line with the config: the idle
+    /// threshold and ticket patterns.
+    pub fn apply_config(&mut self) -> Result<()> {
+        self.set_idle_minutes(config::idle_minutes())?;
+        let tickets = config::tickets();
+        self.set_ticket_patterns(&tickets.patterns, &tickets.ignore)?;
+        Ok(())
+    }
+
+    /// Fold the write-ahead log into the main file and switch to rollback
+    /// journaling, so the index is one self-contained file others can read.
+    pub fn make_shareable(&self) -> Result<()> {
+        self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); PRAGMA journal_mode=DELETE;")?;
+        Ok(())
+    }
+
     pub fn open_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            encrypted: false,
+            key: None,
+            idle: default_idle(),
+            tickets: refs::RefMatcher::default(),
+            read_only: false,
+        };
         db.init_schema()?;
         db.migrate()?;
         Ok(db)
@@ -146,6 +339,17 @@ impl Database {
                 tokenize='unicode61'
             );
 
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS saved_searches (
+                name TEXT PRIMARY KEY,
+                query TEXT NOT NULL,
+                created_at TEXT
+            );
+
             CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id);
             CREATE INDEX IF NOT EXISTS idx_tool_calls_session ON tool_calls(session_id);
             CREATE INDEX IF NOT EXISTS idx_sessions_agent ON sessions(agent);
@@ -165,21 +369,162 @@ impl Database {
         self.conn
             .execute("ALTER TABLE sessions ADD COLUMN conversation_id TEXT", [])
             .ok();
+        let rel_path_added = self
+            .conn
+            .execute("ALTER TABLE tool_calls ADD COLUMN rel_path TEXT", [])
+            .is_ok();
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tool_calls_rel_path ON tool_calls(rel_path)",
+            [],
+        )?;
+        if rel_path_added {
+            self.backfill_rel_paths()?;
+        }
+        self.conn
+            .execute("ALTER TABLE messages ADD COLUMN uuid TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE messages ADD COLUMN parent_uuid TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE tool_calls ADD COLUMN message_uuid TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN author TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN machine TEXT", [])
+            .ok();
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sessions_author ON sessions(author)",
+            [],
+        )?;
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN active_secs INTEGER", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN git_branch TEXT", [])
+            .ok();
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS session_refs (
+                session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                ref TEXT NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (session_id, ref)
+            );
+            CREATE INDEX IF NOT EXISTS idx_session_refs_ref ON session_refs(ref);",
+        )?;
+        self.conn
+            .execute("ALTER TABLE tool_calls ADD COLUMN is_error INTEGER NOT NULL DEFAULT 0", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE tool_calls ADD COLUMN is_commit INTEGER NOT NULL DEFAULT 0", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN outcome TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN outcome_reason TEXT", [])
+            .ok();
+        self.conn
+            .execute("ALTER TABLE sessions ADD COLUMN outcome_source TEXT", [])
+            .ok();
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sessions_outcome ON sessions(outcome)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Fill `tool_calls.rel_path` for rows indexed before the column existed.
+    fn backfill_rel_paths(&self) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tc.id, tc.file_path, s.project_path
+             FROM tool_calls tc JOIN sessions s ON s.id = tc.session_id
+             WHERE tc.file_path IS NOT NULL",
+        )?;
+        let rows: Vec<(i64, String, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        for (id, file_path, project_path) in rows {
+            if let Some(rel) = paths::normalize_file_path(&file_path, project_path.as_deref()) {
+                self.conn.execute(
+                    "UPDATE tool_calls SET rel_path = ?1 WHERE id = ?2",
+                    params![rel, id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_tool_calls(&self, session: &SessionData) -> Result<()> {
+        let project_path = session
+            .project_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string());
+        for tc in &session.tool_calls {
+            let rel_path = tc
+                .file_path
+                .as_deref()
+                .and_then(|fp| paths::normalize_file_path(fp, project_path.as_deref()));
+            self.conn.execute(
+                "INSERT INTO tool_calls (session_id, tool_name, file_path, rel_path, timestamp, message_uuid, is_error, is_commit)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session.id,
+                    tc.tool_name,
+                    tc.file_path,
+                    rel_path,
+                    tc.timestamp.map(|t| t.to_rfc3339()),
+                    tc.message_uuid,
+                    tc.is_error,
+                    tc.is_commit,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn insert_messages(&self, session: &SessionData) -> Result<()> {
+        for msg in &session.messages {
+            self.conn.execute(
+                "INSERT INTO messages (session_id, role, content, timestamp, files_changed, uuid, parent_uuid)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    session.id,
+                    msg.role.as_str(),
+                    self.seal(&msg.content)?,
+                    msg.timestamp.map(|t| t.to_rfc3339()),
+                    serde_json::to_string(&msg.files_changed).unwrap_or_default(),
+                    msg.uuid,
+                    msg.parent_uuid,
+                ],
+            )?;
+
+            // Insert into messages FTS (the in-memory copy when encrypted)
+            self.conn.execute(
+                "INSERT INTO messages_fts (session_id, role, content)
+                 VALUES (?1, ?2, ?3)",
+                params![session.id, msg.role.as_str(), msg.content],
+            )?;
+        }
         Ok(())
     }
 
     pub fn insert_session(&self, session: &SessionData) -> Result<()> {
         self.conn.execute(
-            "INSERT OR REPLACE INTO sessions (id, conversation_id, agent, project_path, project_name, summary, work_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            "INSERT OR REPLACE INTO sessions (id, conversation_id, agent, project_path, project_name, summary, work_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags, git_branch)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 session.id,
                 session.conversation_id,
                 session.agent.as_str(),
                 session.project_path.as_ref().map(|p| p.to_string_lossy().to_string()),
                 session.project_name,
-                session.summary,
-                session.work_summary,
+                self.seal_summary(session.summary.as_deref())?,
+                self.seal_summary(session.work_summary.as_deref())?,
                 session.started_at.map(|t| t.to_rfc3339()),
                 session.ended_at.map(|t| t.to_rfc3339()),
                 session.message_count() as i64,
@@ -187,6 +532,7 @@ impl Database {
                 session.files_modified() as i64,
                 session.files_deleted() as i64,
                 session.tags.join(","),
+                session.git_branch,
             ],
         )?;
 
@@ -196,48 +542,22 @@ impl Database {
              VALUES (?1, ?2, ?3, ?4, ?5)",
             params![
                 session.id,
-                session.summary.as_deref().unwrap_or(""),
-                session.work_summary.as_deref().unwrap_or(""),
+                self.fts_summary(session.summary.as_deref()),
+                self.fts_summary(session.work_summary.as_deref()),
                 session.project_name.as_deref().unwrap_or(""),
                 session.tags.join(" "),
             ],
         )?;
 
         // Insert messages
-        for msg in &session.messages {
-            let _msg_id = self.conn.execute(
-                "INSERT INTO messages (session_id, role, content, timestamp, files_changed)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![
-                    session.id,
-                    msg.role.as_str(),
-                    msg.content,
-                    msg.timestamp.map(|t| t.to_rfc3339()),
-                    serde_json::to_string(&msg.files_changed).unwrap_or_default(),
-                ],
-            )?;
-
-            // Insert into messages FTS (use last_insert_rowid for the rowid)
-            self.conn.execute(
-                "INSERT INTO messages_fts (session_id, role, content)
-                 VALUES (?1, ?2, ?3)",
-                params![session.id, msg.role.as_str(), msg.content],
-            )?;
-        }
+        self.insert_messages(session)?;
 
         // Insert tool calls
-        for tc in &session.tool_calls {
-            self.conn.execute(
-                "INSERT INTO tool_calls (session_id, tool_name, file_path, timestamp)
-                 VALUES (?1, ?2, ?3, ?4)",
-                params![
-                    session.id,
-                    tc.tool_name,
-                    tc.file_path,
-                    tc.timestamp.map(|t| t.to_rfc3339()),
-                ],
-            )?;
-        }
+        self.insert_tool_calls(session)?;
+
+        self.set_active_secs(&session.id, &session_timestamps(session))?;
+        self.index_refs(session)?;
+        self.classify_outcome(session)?;
 
         Ok(())
     }
@@ -252,6 +572,10 @@ impl Database {
             "DELETE FROM sessions_fts WHERE session_id = ?1",
             params![session_id],
         )?;
+        self.conn.execute(
+            "DELETE FROM session_refs WHERE session_id = ?1",
+            params![session_id],
+        )?;
         // Delete from main tables (CASCADE handles messages and tool_calls)
         self.conn.execute(
             "DELETE FROM tool_calls WHERE session_id = ?1",
@@ -269,9 +593,14 @@ impl Database {
     }
 
     pub fn clear_all(&self) -> Result<()> {
+        // While an encrypted index is unlocked, a TEMP copy shadows the on-disk one
+        if self.key.is_some() {
+            self.conn.execute_batch("DELETE FROM temp.messages_fts;")?;
+        }
         self.conn.execute_batch(
-            "DELETE FROM messages_fts;
+            "DELETE FROM main.messages_fts;
              DELETE FROM sessions_fts;
+             DELETE FROM session_refs;
              DELETE FROM tool_calls;
              DELETE FROM messages;
              DELETE FROM sessions;",
@@ -282,12 +611,13 @@ impl Database {
     pub fn get_session(&self, session_id: &str) -> Result<Option<SessionRow>> {
         self.conn
             .query_row(
-                "SELECT id, conversation_id, agent, project_path, project_name, summary, work_summary, llm_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags
+                "SELECT id, conversation_id, agent, project_path, project_name, summary, work_summary, llm_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags, author, machine, active_secs, git_branch, outcome, outcome_reason
                  FROM sessions WHERE id = ?1",
                 params![session_id],
                 |row| Self::row_to_session(row),
             )
             .optional()
+            .map(|row| row.map(|s| self.open_session(s)))
             .map_err(Into::into)
     }
 
@@ -308,6 +638,12 @@ impl Database {
             files_modified: row.get(12)?,
             files_deleted: row.get(13)?,
             tags: row.get::<_, String>(14)?,
+            author: row.get(15)?,
+            machine: row.get(16)?,
+            active_secs: row.get(17)?,
+            git_branch: row.get(18)?,
+            outcome: row.get(19)?,
+            outcome_reason: row.get(20)?,
         })
     }
 
@@ -319,50 +655,278 @@ impl Database {
         to: Option<DateTime<Utc>>,
         limit: usize,
     ) -> Result<Vec<SessionRow>> {
-        let mut sql = String::from(
-            "SELECT id, conversation_id, agent, project_path, project_name, summary, work_summary, llm_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags
-             FROM sessions WHERE 1=1",
-        );
+        Ok(self
+            .list_sessions_page(agent, project, None, from, to, None, limit)?
+            .items)
+    }
+
+    /// One page of sessions, most recent first, starting after `after`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn list_sessions_page(
+        &self,
+        agent: Option<&str>,
+        project: Option<&str>,
+        author: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        after: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Page<SessionRow>> {
+        let mut where_clause = String::from("WHERE 1=1");
         let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
         if let Some(a) = agent {
-            sql.push_str(" AND agent = ?");
+            where_clause.push_str(" AND agent = ?");
             param_values.push(Box::new(a.to_string()));
         }
         if let Some(p) = project {
             let abs_project = std::fs::canonicalize(p)
                 .unwrap_or_else(|_| std::path::PathBuf::from(p));
-            sql.push_str(" AND project_path = ?");
+            where_clause.push_str(" AND project_path = ?");
             param_values.push(Box::new(abs_project.to_string_lossy().to_string()));
         }
+        if let Some(a) = author {
+            where_clause.push_str(" AND author = ?");
+            param_values.push(Box::new(a.to_string()));
+        }
         if let Some(f) = from {
-            sql.push_str(" AND started_at >= ?");
+            where_clause.push_str(" AND started_at >= ?");
             param_values.push(Box::new(f.to_rfc3339()));
         }
         if let Some(t) = to {
-            sql.push_str(" AND started_at <= ?");
+            where_clause.push_str(" AND started_at <= ?");
             param_values.push(Box::new(t.to_rfc3339()));
         }
 
-        sql.push_str(" ORDER BY started_at DESC");
-        sql.push_str(&format!(" LIMIT {}", limit));
+        self.session_page(where_clause, param_values, after, limit)
+    }
+
+    /// Every session in a time range, fetched page by page (no upper bound).
+    pub fn list_all_sessions(
+        &self,
+        agent: Option<&str>,
+        project: Option<&str>,
+        author: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<SessionRow>> {
+        let mut sessions = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let page = self.list_sessions_page(agent, project, author, from, to, cursor.as_ref(), 500)?;
+            sessions.extend(page.items);
+            match page.next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+        Ok(sessions)
+    }
+
+    /// Every session active in a time range: started in it, or started
+    /// earlier and ended in it.
+    pub fn list_sessions_active_between(
+        &self,
+        project: Option<&str>,
+        author: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SessionRow>> {
+        let mut where_clause =
+            String::from("WHERE started_at <= ? AND COALESCE(ended_at, started_at) >= ?");
+        let mut values = vec![to.to_rfc3339(), from.to_rfc3339()];
+        if let Some(p) = project {
+            let abs_project = std::fs::canonicalize(p)
+                .unwrap_or_else(|_| std::path::PathBuf::from(p));
+            where_clause.push_str(" AND project_path = ?");
+            values.push(abs_project.to_string_lossy().to_string());
+        }
+        if let Some(a) = author {
+            where_clause.push_str(" AND author = ?");
+            values.push(a.to_string());
+        }
+
+        let mut sessions = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let param_values: Vec<Box<dyn rusqlite::types::ToSql>> = values
+                .iter()
+                .map(|v| Box::new(v.clone()) as Box<dyn rusqlite::types::ToSql>)
+                .collect();
+            let page = self.session_page(where_clause.clone(), param_values, cursor.as_ref(), 500)?;
+            sessions.extend(page.items);
+            match page.next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+        Ok(sessions)
+    }
+
+    /// List sessions matching a structured filter (saved searches, collections).
+    pub fn query_sessions(&self, filter: &SessionFilter, limit: usize) -> Result<Vec<SessionRow>> {
+        Ok(self.query_sessions_page(filter, None, limit)?.items)
+    }
+
+    /// Every session matching a filter, fetched page by page.
+    pub fn query_all_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionRow>> {
+        let mut sessions = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let page = self.query_sessions_page(filter, cursor.as_ref(), 500)?;
+            sessions.extend(page.items);
+            match page.next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+        Ok(sessions)
+    }
+
+    pub fn query_sessions_page(
+        &self,
+        filter: &SessionFilter,
+        after: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Page<SessionRow>> {
+        let (where_clause, param_values) = Self::session_filter_clause(filter);
+        self.session_page(where_clause, param_values, after, limit)
+    }
+
+    /// Keyset pagination over `(started_at, id)` descending.
+    /// Fetches one extra row to learn whether another page exists.
+    fn session_page(
+        &self,
+        mut where_clause: String,
+        mut param_values: Vec<Box<dyn rusqlite::types::ToSql>>,
+        after: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Page<SessionRow>> {
+        match after {
+            Some(Cursor::Session { started_at, id }) => {
+                where_clause.push_str(
+                    " AND (COALESCE(started_at, '') < ? OR (COALESCE(started_at, '') = ? AND id < ?))",
+                );
+                param_values.push(Box::new(started_at.clone()));
+                param_values.push(Box::new(started_at.clone()));
+                param_values.push(Box::new(id.clone()));
+            }
+            Some(_) => bail!("Cursor is not a session list cursor"),
+            None => {}
+        }
+
+        let sql = format!(
+            "SELECT id, conversation_id, agent, project_path, project_name, summary, work_summary, llm_summary, started_at, ended_at, message_count, files_created, files_modified, files_deleted, tags, author, machine, active_secs, git_branch, outcome, outcome_reason
+             FROM sessions {}
+             ORDER BY COALESCE(started_at, '') DESC, id DESC
+             LIMIT {}",
+            where_clause,
+            limit + 1
+        );
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             param_values.iter().map(|p| p.as_ref()).collect();
 
         let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map(params_refs.as_slice(), |row| Self::row_to_session(row))?;
+        let rows = stmt.query_map(params_refs.as_slice(), Self::row_to_session)?;
 
         let mut sessions = Vec::new();
         for row in rows {
-            sessions.push(row?);
+            sessions.push(self.open_session(row?));
         }
-        Ok(sessions)
+
+        let next_cursor = if sessions.len() > limit {
+            sessions.truncate(limit);
+            sessions.last().map(|s| Cursor::Session {
+                started_at: s.started_at.clone().unwrap_or_default(),
+                id: s.id.clone(),
+            })
+        } else {
+            None
+        };
+
+        Ok(Page {
+            items: sessions,
+            next_cursor,
+        })
+    }
+
+    pub fn count_sessions(&self, filter: &SessionFilter) -> Result<i64> {
+        let (where_clause, param_values) = Self::session_filter_clause(filter);
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            param_values.iter().map(|p| p.as_ref()).collect();
+
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM sessions {}", where_clause),
+            params_refs.as_slice(),
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    fn session_filter_clause(
+        filter: &SessionFilter,
+    ) -> (String, Vec<Box<dyn rusqlite::types::ToSql>>) {
+        let mut where_clause = String::from("WHERE 1=1");
+        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+        if let Some(ref k) = filter.keyword {
+            where_clause.push_str(
+                " AND id IN (SELECT session_id FROM messages_fts WHERE messages_fts MATCH ?)",
+            );
+            param_values.push(Box::new(k.clone()));
+        }
+        if let Some(ref a) = filter.agent {
+            where_clause.push_str(" AND agent = ?");
+            param_values.push(Box::new(a.clone()));
+        }
+        if let Some(ref p) = filter.project {
+            // Accept either a path or a bare project name
+            let abs_project = std::fs::canonicalize(p)
+                .unwrap_or_else(|_| std::path::PathBuf::from(p));
+            where_clause.push_str(" AND (project_path = ? OR project_name = ?)");
+            param_values.push(Box::new(abs_project.to_string_lossy().to_string()));
+            param_values.push(Box::new(p.clone()));
+        }
+        if let Some(ref t) = filter.tag {
+            where_clause.push_str(" AND (',' || tags || ',') LIKE ?");
+            param_values.push(Box::new(format!("%,{},%", t)));
+        }
+        if let Some(ref a) = filter.author {
+            where_clause.push_str(" AND author = ?");
+            param_values.push(Box::new(a.clone()));
+        }
+        if let Some(ref o) = filter.outcome {
+            where_clause.push_str(" AND outcome = ?");
+            param_values.push(Box::new(o.clone()));
+        }
+        if let Some(ref f) = filter.file {
+            let names: Vec<&String> = std::iter::once(f).chain(&filter.file_aliases).collect();
+            let conditions = vec!["rel_path = ? OR file_path LIKE ?"; names.len()];
+            where_clause.push_str(&format!(
+                " AND id IN (SELECT session_id FROM tool_calls WHERE {})",
+                conditions.join(" OR ")
+            ));
+            for name in names {
+                param_values.push(Box::new(name.clone()));
+                param_values.push(Box::new(format!("%{}%", name)));
+            }
+        }
+        if let Some(f) = filter.from {
+            where_clause.push_str(" AND started_at >= ?");
+            param_values.push(Box::new(f.to_rfc3339()));
+        }
+        if let Some(t) = filter.to {
+            where_clause.push_str(" AND started_at <= ?");
+            param_values.push(Box::new(t.to_rfc3339()));
+        }
+
+        (where_clause, param_values)
     }
 
     pub fn get_messages(&self, session_id: &str) -> Result<Vec<MessageRow>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, session_id, role, content, timestamp, files_changed
+            "SELECT id, session_id, role, content, timestamp, files_changed, uuid, parent_uuid
              FROM messages WHERE session_id = ?1 ORDER BY id ASC",
         )?;
 
@@ -374,19 +938,23 @@ impl Database {
                 content: row.get(3)?,
                 timestamp: row.get(4)?,
                 files_changed: row.get::<_, String>(5)?,
+                uuid: row.get(6)?,
+                parent_uuid: row.get(7)?,
             })
         })?;
 
         let mut messages = Vec::new();
         for row in rows {
-            messages.push(row?);
+            let mut m = row?;
+            m.content = self.unseal(m.content)?;
+            messages.push(m);
         }
         Ok(messages)
     }
 
     pub fn get_tool_calls(&self, session_id: &str) -> Result<Vec<ToolCallRow>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, session_id, tool_name, file_path, timestamp
+            "SELECT id, session_id, tool_name, file_path, rel_path, timestamp, message_uuid, is_error, is_commit
              FROM tool_calls WHERE session_id = ?1 ORDER BY id ASC",
         )?;
 
@@ -396,7 +964,11 @@ impl Database {
                 session_id: row.get(1)?,
                 tool_name: row.get(2)?,
                 file_path: row.get(3)?,
-                timestamp: row.get(4)?,
+                rel_path: row.get(4)?,
+                timestamp: row.get(5)?,
+                message_uuid: row.get(6)?,
+                is_error: row.get(7)?,
+                is_commit: row.get(8)?,
             })
         })?;
 
@@ -407,17 +979,96 @@ impl Database {
         Ok(tool_calls)
     }
 
-    pub fn search_messages(
+    /// Sessions (other than `session_id`) that touched any of the same files,
+    /// as `(other_session_id, file_path)` pairs. Files match on their
+    /// repo-relative path, so other checkouts and worktrees count too; the
+    /// path is the repo-relative one where known.
+    pub fn sessions_sharing_files(&self, session_id: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT other.session_id, COALESCE(other.rel_path, other.file_path)
+             FROM tool_calls own
+             JOIN tool_calls other ON other.rel_path = own.rel_path OR other.file_path = own.file_path
+             WHERE own.session_id = ?1 AND other.session_id != ?1 AND own.file_path IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut pairs = Vec::new();
+        for row in rows {
+            pairs.push(row?);
+        }
+        Ok(pairs)
+    }
+
+    /// Sessions whose user messages match an FTS query, best matches first.
+    pub fn sessions_matching_user_text(
         &self,
-        keyword: &str,
-        agent: Option<&str>,
+        fts_query: &str,
+        exclude_session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.ensure_unlocked()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT session_id FROM messages_fts
+             WHERE messages_fts MATCH ?1 AND role = 'user' AND session_id != ?2
+             GROUP BY session_id
+             ORDER BY MIN(rank)
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![fts_query, exclude_session_id, limit as i64], |row| {
+            row.get::<_, String>(0)
+        })?;
+
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row?);
+        }
+        Ok(ids)
+    }
+
+    pub fn first_user_message(&self, session_id: &str) -> Result<Option<String>> {
+        let content: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT content FROM messages WHERE session_id = ?1 AND role = 'user' ORDER BY id ASC LIMIT 1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        content.map(|c| self.unseal(c)).transpose()
+    }
+
+    pub fn search_messages(
+        &self,
+        keyword: &str,
+        agent: Option<&str>,
         project: Option<&str>,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
         limit: usize,
     ) -> Result<Vec<SearchResult>> {
+        Ok(self
+            .search_messages_page(keyword, agent, project, None, from, to, None, limit)?
+            .items)
+    }
+
+    /// One page of FTS matches, best rank first, starting after `after`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_messages_page(
+        &self,
+        keyword: &str,
+        agent: Option<&str>,
+        project: Option<&str>,
+        author: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        after: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Page<SearchResult>> {
+        self.ensure_unlocked()?;
         let mut sql = String::from(
-            "SELECT mf.session_id, s.agent, s.project_name, s.project_path, mf.role, mf.content, s.started_at, s.summary, s.started_at
+            "SELECT mf.session_id, s.agent, s.project_name, s.project_path, mf.role, mf.content, s.started_at, s.summary, s.started_at, mf.rank, mf.rowid, s.author
              FROM messages_fts mf
              JOIN sessions s ON s.id = mf.session_id
              WHERE messages_fts MATCH ?1",
@@ -435,6 +1086,10 @@ impl Database {
             sql.push_str(" AND s.project_path = ?");
             param_values.push(Box::new(abs_project.to_string_lossy().to_string()));
         }
+        if let Some(a) = author {
+            sql.push_str(" AND s.author = ?");
+            param_values.push(Box::new(a.to_string()));
+        }
         if let Some(f) = from {
             sql.push_str(" AND s.started_at >= ?");
             param_values.push(Box::new(f.to_rfc3339()));
@@ -443,56 +1098,62 @@ impl Database {
             sql.push_str(" AND s.started_at <= ?");
             param_values.push(Box::new(t.to_rfc3339()));
         }
+        match after {
+            Some(Cursor::Search { rank, rowid }) => {
+                sql.push_str(" AND (mf.rank > ? OR (mf.rank = ? AND mf.rowid > ?))");
+                param_values.push(Box::new(*rank));
+                param_values.push(Box::new(*rank));
+                param_values.push(Box::new(*rowid));
+            }
+            Some(_) => bail!("Cursor is not a search cursor"),
+            None => {}
+        }
 
-        sql.push_str(&format!(" LIMIT {}", limit));
+        sql.push_str(&format!(" ORDER BY mf.rank, mf.rowid LIMIT {}", limit + 1));
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             param_values.iter().map(|p| p.as_ref()).collect();
 
         let mut stmt = self.conn.prepare(&sql)?;
         let rows = stmt.query_map(params_refs.as_slice(), |row| {
-            Ok(SearchResult {
-                session_id: row.get(0)?,
-                agent: row.get(1)?,
-                project_name: row.get(2)?,
-                project_path: row.get(3)?,
-                role: row.get(4)?,
-                content: row.get(5)?,
-                timestamp: row.get(6)?,
-                summary: row.get(7)?,
-                started_at: row.get(8)?,
-            })
+            Ok((
+                SearchResult {
+                    session_id: row.get(0)?,
+                    agent: row.get(1)?,
+                    project_name: row.get(2)?,
+                    project_path: row.get(3)?,
+                    role: row.get(4)?,
+                    content: row.get(5)?,
+                    timestamp: row.get(6)?,
+                    summary: row.get(7)?,
+                    started_at: row.get(8)?,
+                    author: row.get(11)?,
+                },
+                row.get::<_, f64>(9)?,
+                row.get::<_, i64>(10)?,
+            ))
         })?;
 
         let mut results = Vec::new();
         for row in rows {
-            results.push(row?);
+            let (mut result, rank, rowid) = row?;
+            result.summary = self.unseal_summary(result.summary);
+            results.push((result, rank, rowid));
         }
-        Ok(results)
-    }
-
-    pub fn search_by_file(
-        &self,
-        file_path: &str,
-        limit: usize,
-    ) -> Result<Vec<SessionRow>> {
-        let pattern = format!("%{}%", file_path);
-        let mut stmt = self.conn.prepare(
-            "SELECT DISTINCT s.id, s.conversation_id, s.agent, s.project_path, s.project_name, s.summary, s.work_summary, s.llm_summary, s.started_at, s.ended_at, s.message_count, s.files_created, s.files_modified, s.files_deleted, s.tags
-             FROM sessions s
-             JOIN tool_calls tc ON tc.session_id = s.id
-             WHERE tc.file_path LIKE ?1
-             ORDER BY s.started_at DESC
-             LIMIT ?2",
-        )?;
-
-        let rows = stmt.query_map(params![pattern, limit as i64], |row| Self::row_to_session(row))?;
 
-        let mut sessions = Vec::new();
-        for row in rows {
-            sessions.push(row?);
-        }
-        Ok(sessions)
+        let next_cursor = if results.len() > limit {
+            results.truncate(limit);
+            results
+                .last()
+                .map(|(_, rank, rowid)| Cursor::Search { rank: *rank, rowid: *rowid })
+        } else {
+            None
+        };
+
+        Ok(Page {
+            items: results.into_iter().map(|(r, _, _)| r).collect(),
+            next_cursor,
+        })
     }
 
     pub fn update_tags(&self, session_id: &str, tags: &[String]) -> Result<()> {
@@ -526,11 +1187,64 @@ impl Database {
             .collect())
     }
 
+    pub fn save_search(&self, name: &str, query: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO saved_searches (name, query, created_at) VALUES (?1, ?2, ?3)",
+            params![name, query, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearchRow>> {
+        self.conn
+            .query_row(
+                "SELECT name, query, created_at FROM saved_searches WHERE name = ?1",
+                params![name],
+                |row| {
+                    Ok(SavedSearchRow {
+                        name: row.get(0)?,
+                        query: row.get(1)?,
+                        created_at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    pub fn list_saved_searches(&self) -> Result<Vec<SavedSearchRow>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, query, created_at FROM saved_searches ORDER BY name ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SavedSearchRow {
+                name: row.get(0)?,
+                query: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+
+        let mut searches = Vec::new();
+        for row in rows {
+            searches.push(row?);
+        }
+        Ok(searches)
+    }
+
+    pub fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        let count = self
+            .conn
+            .execute("DELETE FROM saved_searches WHERE name = ?1", params![name])?;
+        Ok(count > 0)
+    }
+
     pub fn get_stats(
         &self,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
+        agent: Option<&str>,
         project: Option<&str>,
+        author: Option<&str>,
     ) -> Result<Stats> {
         let mut where_clause = String::from("WHERE 1=1");
         let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
@@ -543,12 +1257,20 @@ impl Database {
             where_clause.push_str(" AND started_at <= ?");
             param_values.push(Box::new(t.to_rfc3339()));
         }
+        if let Some(a) = agent {
+            where_clause.push_str(" AND agent = ?");
+            param_values.push(Box::new(a.to_string()));
+        }
         if let Some(p) = project {
             let abs_project = std::fs::canonicalize(p)
                 .unwrap_or_else(|_| std::path::PathBuf::from(p));
             where_clause.push_str(" AND project_path = ?");
             param_values.push(Box::new(abs_project.to_string_lossy().to_string()));
         }
+        if let Some(a) = author {
+            where_clause.push_str(" AND author = ?");
+            param_values.push(Box::new(a.to_string()));
+        }
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             param_values.iter().map(|p| p.as_ref()).collect();
@@ -584,6 +1306,45 @@ impl Database {
             .filter_map(|r| r.ok())
             .collect();
 
+        // By author (only sessions that have one)
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT author, COUNT(*) FROM sessions {} AND author IS NOT NULL GROUP BY author ORDER BY COUNT(*) DESC",
+            where_clause
+        ))?;
+        let sessions_by_author: Vec<(String, i64)> = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // Active time
+        let total_active_secs: i64 = self.conn.query_row(
+            &format!("SELECT COALESCE(SUM(active_secs),0) FROM sessions {}", where_clause),
+            params_refs.as_slice(),
+            |row| row.get(0),
+        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT agent, COALESCE(SUM(active_secs),0) AS secs FROM sessions {} GROUP BY agent ORDER BY secs DESC",
+            where_clause
+        ))?;
+        let active_secs_by_agent: Vec<(String, i64)> = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT COALESCE(project_name, 'unknown'), COALESCE(SUM(active_secs),0) AS secs FROM sessions {} GROUP BY project_name ORDER BY secs DESC",
+            where_clause
+        ))?;
+        let active_secs_by_project: Vec<(String, i64)> = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
         // File stats
         let file_stats: (i64, i64, i64) = self.conn.query_row(
             &format!(
@@ -605,6 +1366,20 @@ impl Database {
             file_where.push_str(" AND tc.timestamp <= ?");
             file_params.push(Box::new(t.to_rfc3339()));
         }
+        if let Some(a) = agent {
+            file_where.push_str(" AND tc.session_id IN (SELECT id FROM sessions WHERE agent = ?)");
+            file_params.push(Box::new(a.to_string()));
+        }
+        if let Some(p) = project {
+            let abs_project = std::fs::canonicalize(p)
+                .unwrap_or_else(|_| std::path::PathBuf::from(p));
+            file_where.push_str(" AND tc.session_id IN (SELECT id FROM sessions WHERE project_path = ?)");
+            file_params.push(Box::new(abs_project.to_string_lossy().to_string()));
+        }
+        if let Some(a) = author {
+            file_where.push_str(" AND tc.session_id IN (SELECT id FROM sessions WHERE author = ?)");
+            file_params.push(Box::new(a.to_string()));
+        }
         let file_params_refs: Vec<&dyn rusqlite::types::ToSql> =
             file_params.iter().map(|p| p.as_ref()).collect();
 
@@ -623,6 +1398,10 @@ impl Database {
             total_sessions,
             sessions_by_agent,
             sessions_by_project,
+            sessions_by_author,
+            total_active_secs,
+            active_secs_by_agent,
+            active_secs_by_project,
             total_files_created: file_stats.0,
             total_files_modified: file_stats.1,
             total_files_deleted: file_stats.2,
@@ -630,10 +1409,33 @@ impl Database {
         })
     }
 
+    /// Record who ran a session and where. `update_session` keeps them;
+    /// `insert_session` starts the row over, so set them again after it.
+    pub fn set_session_origin(&self, session_id: &str, author: Option<&str>, machine: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sessions SET author = ?1, machine = ?2 WHERE id = ?3",
+            params![author, machine, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Attribute the sessions in `session_ids` that have no author yet to
+    /// this user and machine.
+    pub fn stamp_local_origin(&self, session_ids: &[String], author: &str, machine: &str) -> Result<usize> {
+        let mut stamped = 0;
+        for id in session_ids {
+            stamped += self.conn.execute(
+                "UPDATE sessions SET author = ?1, machine = ?2 WHERE id = ?3 AND author IS NULL",
+                params![author, machine, id],
+            )?;
+        }
+        Ok(stamped)
+    }
+
     pub fn update_llm_summary(&self, session_id: &str, llm_summary: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE sessions SET llm_summary = ?1 WHERE id = ?2",
-            params![llm_summary, session_id],
+            params![self.seal(llm_summary)?, session_id],
         )?;
         Ok(())
     }
@@ -662,17 +1464,18 @@ impl Database {
     pub fn update_session(&self, session: &SessionData) -> Result<()> {
         // Update session metadata
         self.conn.execute(
-            "UPDATE sessions SET conversation_id = ?1, summary = ?2, work_summary = ?3, ended_at = ?4, message_count = ?5, files_created = ?6, files_modified = ?7, files_deleted = ?8
-             WHERE id = ?9",
+            "UPDATE sessions SET conversation_id = ?1, summary = ?2, work_summary = ?3, ended_at = ?4, message_count = ?5, files_created = ?6, files_modified = ?7, files_deleted = ?8, git_branch = ?9
+             WHERE id = ?10",
             params![
                 session.conversation_id,
-                session.summary,
-                session.work_summary,
+                self.seal_summary(session.summary.as_deref())?,
+                self.seal_summary(session.work_summary.as_deref())?,
                 session.ended_at.map(|t| t.to_rfc3339()),
                 session.message_count() as i64,
                 session.files_created() as i64,
                 session.files_modified() as i64,
                 session.files_deleted() as i64,
+                session.git_branch,
                 session.id,
             ],
         )?;
@@ -680,38 +1483,22 @@ impl Database {
         // Replace messages: delete old, insert new
         self.conn.execute("DELETE FROM messages_fts WHERE session_id = ?1", params![session.id])?;
         self.conn.execute("DELETE FROM messages WHERE session_id = ?1", params![session.id])?;
-        for msg in &session.messages {
-            self.conn.execute(
-                "INSERT INTO messages (session_id, role, content, timestamp, files_changed) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![
-                    session.id,
-                    msg.role.as_str(),
-                    msg.content,
-                    msg.timestamp.map(|t| t.to_rfc3339()),
-                    serde_json::to_string(&msg.files_changed).unwrap_or_default(),
-                ],
-            )?;
-            self.conn.execute(
-                "INSERT INTO messages_fts (session_id, role, content) VALUES (?1, ?2, ?3)",
-                params![session.id, msg.role.as_str(), msg.content],
-            )?;
-        }
+        self.insert_messages(session)?;
 
         // Replace tool calls
         self.conn.execute("DELETE FROM tool_calls WHERE session_id = ?1", params![session.id])?;
-        for tc in &session.tool_calls {
-            self.conn.execute(
-                "INSERT INTO tool_calls (session_id, tool_name, file_path, timestamp) VALUES (?1, ?2, ?3, ?4)",
-                params![session.id, tc.tool_name, tc.file_path, tc.timestamp.map(|t| t.to_rfc3339())],
-            )?;
-        }
+        self.insert_tool_calls(session)?;
+
+        self.set_active_secs(&session.id, &session_timestamps(session))?;
+        self.index_refs(session)?;
+        self.classify_outcome(session)?;
 
         // Update sessions FTS
         self.conn.execute(
             "UPDATE sessions_fts SET summary = ?1, work_summary = ?2 WHERE session_id = ?3",
             params![
-                session.summary.as_deref().unwrap_or(""),
-                session.work_summary.as_deref().unwrap_or(""),
+                self.fts_summary(session.summary.as_deref()),
+                self.fts_summary(session.work_summary.as_deref()),
                 session.id,
             ],
         )?;
@@ -758,19 +1545,572 @@ impl Database {
     }
 }
 
+fn default_idle() -> chrono::Duration {
+    chrono::Duration::minutes(activity::DEFAULT_IDLE_MINUTES as i64)
+}
+
+/// Every message and tool call time in a session, unsorted
+fn session_timestamps(session: &SessionData) -> Vec<DateTime<Utc>> {
+    session
+        .messages
+        .iter()
+        .filter_map(|m| m.timestamp)
+        .chain(session.tool_calls.iter().filter_map(|tc| tc.timestamp))
+        .collect()
+}
+
+// ── Active time ──
+//
+// `sessions.active_secs` is computed with the idle threshold in effect when the
+// session was indexed. The threshold is kept in `meta`, and changing it
+// recomputes every session from the stored message and tool call times.
+impl Database {
+    fn load_idle(&mut self) -> Result<()> {
+        if let Some(minutes) = self.meta("activity.idle_minutes")?.and_then(|m| m.parse().ok()) {
+            self.idle = chrono::Duration::minutes(minutes);
+        }
+        Ok(())
+    }
+
+    /// Use `minutes` as the idle threshold, recomputing active time for
+    /// sessions that were measured with another threshold (or not at all).
+    /// Returns how many sessions were recomputed.
+    pub fn set_idle_minutes(&mut self, minutes: u64) -> Result<usize> {
+        let idle = chrono::Duration::minutes(minutes as i64);
+        let changed = self.meta("activity.idle_minutes")?.as_deref() != Some(minutes.to_string().as_str());
+        if changed {
+            self.set_meta("activity.idle_minutes", &minutes.to_string())?;
+        }
+        self.idle = idle;
+
+        let sql = if changed {
+            "SELECT id FROM sessions"
+        } else {
+            "SELECT id FROM sessions WHERE active_secs IS NULL"
+        };
+        let ids: Vec<String> = self
+            .conn
+            .prepare(sql)?
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        for id in &ids {
+            let timestamps = self.activity_timestamps(id)?;
+            self.set_active_secs(id, &timestamps)?;
+        }
+        Ok(ids.len())
+    }
+
+    pub fn idle_threshold(&self) -> chrono::Duration {
+        self.idle
+    }
+
+    /// Times of a session's messages and tool calls, oldest first
+    pub fn activity_timestamps(&self, session_id: &str) -> Result<Vec<DateTime<Utc>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp FROM messages WHERE session_id = ?1 AND timestamp IS NOT NULL
+             UNION ALL
+             SELECT timestamp FROM tool_calls WHERE session_id = ?1 AND timestamp IS NOT NULL",
+        )?;
+        let mut timestamps: Vec<DateTime<Utc>> = stmt
+            .query_map(params![session_id], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .filter_map(|t| parse_datetime(&t))
+            .collect();
+        timestamps.sort();
+        Ok(timestamps)
+    }
+
+    /// Times of every indexed message, optionally for one agent's sessions
+    pub fn message_times(&self, agent: Option<&str>) -> Result<Vec<DateTime<Utc>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT m.timestamp FROM messages m JOIN sessions s ON s.id = m.session_id
+             WHERE m.timestamp IS NOT NULL AND (?1 IS NULL OR s.agent = ?1)",
+        )?;
+        let times = stmt
+            .query_map(params![agent], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .filter_map(|t| parse_datetime(&t))
+            .collect();
+        Ok(times)
+    }
+
+    fn set_active_secs(&self, session_id: &str, timestamps: &[DateTime<Utc>]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sessions SET active_secs = ?1 WHERE id = ?2",
+            params![activity::active_seconds(timestamps, self.idle), session_id],
+        )?;
+        Ok(())
+    }
+}
+
+// ── Ticket refs ──
+//
+// `session_refs` holds the ticket keys found in each session's branch, prompts
+// and commit messages. Like the idle threshold, the patterns are kept in
+// `meta`; changing them re-extracts every session's refs.
+impl Database {
+    fn load_tickets(&mut self) -> Result<()> {
+        if let Some(stored) = self.meta("tickets.config")? {
+            if let Ok((patterns, ignore)) = serde_json::from_str::<(Vec<String>, Vec<String>)>(&stored) {
+                if let Ok(matcher) = refs::RefMatcher::new(&patterns, &ignore) {
+                    self.tickets = matcher;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Use these ticket patterns, re-extracting refs for every session if
+    /// they differ from the ones the index was built with. Invalid patterns
+    /// are skipped with a warning. Returns how many sessions were
+    /// re-extracted (none while the index is locked).
+    pub fn set_ticket_patterns(&mut self, patterns: &[String], ignore: &[String]) -> Result<usize> {
+        let patterns: Vec<String> = patterns
+            .iter()
+            .filter(|p| match regex::Regex::new(p) {
+                Ok(_) => true,
+                Err(e) => {
+                    eprintln!("Warning: ignoring invalid [tickets] pattern '{}': {}", p, e);
+                    false
+                }
+            })
+            .cloned()
+            .collect();
+        let patterns = patterns.as_slice();
+        self.tickets = refs::RefMatcher::new(patterns, ignore)?;
+        let config = serde_json::to_string(&(patterns, ignore))?;
+        if self.meta("tickets.config")?.as_deref() == Some(config.as_str()) || self.is_locked() {
+            return Ok(0);
+        }
+
+        let ids: Vec<String> = self
+            .conn
+            .prepare("SELECT id FROM sessions")?
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        for id in &ids {
+            let branch: Option<String> = self.conn.query_row(
+                "SELECT git_branch FROM sessions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )?;
+            let messages = self.get_messages(id)?;
+            let found = self.tickets.session_refs(
+                branch.as_deref(),
+                messages.iter().map(|m| (m.role.as_str(), m.content.as_str())),
+            );
+            self.set_session_refs(id, &found)?;
+        }
+        self.set_meta("tickets.config", &config)?;
+        Ok(ids.len())
+    }
+
+    /// Ticket keys a session references, in the order they were found
+    pub fn session_refs(&self, session_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT ref FROM session_refs WHERE session_id = ?1 ORDER BY rowid")?;
+        let refs = stmt
+            .query_map(params![session_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(refs)
+    }
+
+    fn index_refs(&self, session: &SessionData) -> Result<()> {
+        let found = self.tickets.session_refs(
+            session.git_branch.as_deref(),
+            session.messages.iter().map(|m| (m.role.as_str(), m.content.as_str())),
+        );
+        self.set_session_refs(&session.id, &found)
+    }
+
+    fn set_session_refs(&self, session_id: &str, found: &[(String, &str)]) -> Result<()> {
+        self.conn.execute("DELETE FROM session_refs WHERE session_id = ?1", params![session_id])?;
+        for (key, source) in found {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO session_refs (session_id, ref, source) VALUES (?1, ?2, ?3)",
+                params![session_id, key, source],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// ── Outcomes ──
+//
+// Sessions are labelled by `core::outcome`'s rules when indexed. An
+// in-progress label goes stale once the session has sat idle, so
+// `refresh_outcomes` re-runs the rules for those; `ail index` and the
+// commands that read outcomes call it, not every open. A label refined by an LLM
+// (source "llm") stands until the session changes and is indexed again.
+impl Database {
+    fn classify_outcome(&self, session: &SessionData) -> Result<()> {
+        let evidence = Evidence {
+            messages: session
+                .messages
+                .iter()
+                .map(|m| (m.role.as_str(), m.content.as_str()))
+                .collect(),
+            tool_errors: session.tool_calls.iter().map(|tc| tc.is_error).collect(),
+            committed: session.tool_calls.iter().any(|tc| tc.is_commit),
+            files_changed: (session.files_created() + session.files_modified() + session.files_deleted()) as i64,
+            last_activity: session_timestamps(session).into_iter().max(),
+        };
+        let (label, reason) = outcome::classify(&evidence, Utc::now(), self.idle);
+        self.set_outcome(&session.id, label, &reason, "rules")
+    }
+
+    /// Classify sessions indexed before outcomes existed, and re-check
+    /// in-progress ones that have since gone idle. Returns how many were
+    /// classified (none while the index is locked or opened read-only).
+    pub fn refresh_outcomes(&self) -> Result<usize> {
+        if self.is_locked() || self.read_only {
+            return Ok(0);
+        }
+        let stale = (Utc::now() - self.idle).to_rfc3339();
+        let ids: Vec<String> = self
+            .conn
+            .prepare(
+                "SELECT id FROM sessions
+                 WHERE outcome IS NULL OR (outcome = 'in-progress' AND COALESCE(ended_at, '') < ?1)",
+            )?
+            .query_map(params![stale], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        for id in &ids {
+            let messages = self.get_messages(id)?;
+            let tool_calls = self.get_tool_calls(id)?;
+            let files_changed: i64 = self.conn.query_row(
+                "SELECT files_created + files_modified + files_deleted FROM sessions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )?;
+            let evidence = Evidence {
+                messages: messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect(),
+                tool_errors: tool_calls.iter().map(|tc| tc.is_error).collect(),
+                committed: tool_calls.iter().any(|tc| tc.is_commit),
+                files_changed,
+                last_activity: self.activity_timestamps(id)?.into_iter().max(),
+            };
+            let (label, reason) = outcome::classify(&evidence, Utc::now(), self.idle);
+            self.set_outcome(id, label, &reason, "rules")?;
+        }
+        Ok(ids.len())
+    }
+
+    /// Whether any of a session's tool calls ran `git commit`
+    pub fn session_committed(&self, session_id: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM tool_calls WHERE session_id = ?1 AND is_commit = 1)",
+            params![session_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Record a session's outcome; `source` is "rules" or "llm"
+    pub fn set_outcome(&self, session_id: &str, label: Outcome, reason: &str, source: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sessions SET outcome = ?1, outcome_reason = ?2, outcome_source = ?3 WHERE id = ?4",
+            params![label.as_str(), reason, source, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a session's outcome was already refined by an LLM
+    pub fn outcome_refined(&self, session_id: &str) -> Result<bool> {
+        let source: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT outcome_source FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(source.as_deref() == Some("llm"))
+    }
+}
+
 pub fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
     // Try RFC3339 first
     if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
         return Some(dt.with_timezone(&Utc));
     }
-    // Try YYYY-MM-DD
+    // Try YYYY-MM-DD: the start of that day in the configured timezone
     if let Ok(nd) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        let ndt = nd.and_hms_opt(0, 0, 0)?;
-        return Some(DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc));
+        return Some(clock::calendar().day_start(nd));
     }
     None
 }
 
+// ── Encryption ──
+//
+// When enabled, `messages.content` and the sessions' `summary`, `work_summary`
+// and `llm_summary` hold `enc1:` ciphertext, the on-disk `messages_fts` stays
+// empty and `sessions_fts` keeps no summaries. Unlocking builds a TEMP
+// `messages_fts` from the decrypted messages; it shadows the main table, so
+// search queries are unchanged. The rest of the session metadata stays in
+// plaintext so listings work while the index is locked: project paths and
+// names, git branches, tags, times and counts, author and machine, outcome
+// labels and reasons, ticket keys, and the tool names and file paths of tool
+// calls.
+impl Database {
+    fn meta(&self, key: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+            .map_err(Into::into)
+    }
+
+    fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Pick up encryption state, unlocking with the cached session key if there is one.
+    fn load_encryption(&mut self) -> Result<()> {
+        self.encrypted = self.meta("encryption.salt")?.is_some();
+        if self.encrypted {
+            if let Some(key) = crypto::load_cached_key() {
+                if self.verify_key(&key)? {
+                    self.attach_key(key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.encrypted && self.key.is_none()
+    }
+
+    fn verify_key(&self, key: &IndexKey) -> Result<bool> {
+        let check = self.meta("encryption.check")?.unwrap_or_default();
+        Ok(key.decrypt(&check).is_ok_and(|v| v == KEY_CHECK))
+    }
+
+    /// Derive the key from a passphrase or keyfile and unlock. Returns the key for caching.
+    pub fn unlock(&mut self, secret: &[u8]) -> Result<IndexKey> {
+        let Some(salt) = self.meta("encryption.salt")? else {
+            bail!("The index is not encrypted (run `ail lock` to encrypt it)");
+        };
+        let key = IndexKey::derive(secret, &crypto::decode_salt(&salt)?)?;
+        if !self.verify_key(&key)? {
+            bail!("Wrong passphrase or keyfile");
+        }
+        self.attach_key(key.clone())?;
+        Ok(key)
+    }
+
+    /// Keep `key` and build the in-memory search index from decrypted messages.
+    fn attach_key(&mut self, key: IndexKey) -> Result<()> {
+        self.conn.execute_batch(
+            "DROP TABLE IF EXISTS temp.messages_fts;
+             CREATE VIRTUAL TABLE temp.messages_fts USING fts5(
+                 session_id UNINDEXED,
+                 role UNINDEXED,
+                 content,
+                 tokenize='unicode61'
+             );",
+        )?;
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, role, content FROM messages ORDER BY id")?;
+        let rows: Vec<(i64, String, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let tx = self.conn.unchecked_transaction()?;
+        for (id, session_id, role, content) in rows {
+            let content = if crypto::is_encrypted(&content) {
+                key.decrypt(&content)?
+            } else {
+                content
+            };
+            tx.execute(
+                "INSERT INTO temp.messages_fts (rowid, session_id, role, content) VALUES (?1, ?2, ?3, ?4)",
+                params![id, session_id, role, content],
+            )?;
+        }
+        tx.commit()?;
+
+        self.key = Some(key);
+        Ok(())
+    }
+
+    /// Encrypt every stored message and drop the plaintext search index.
+    /// The index stays unlocked for this connection; returns the key for caching.
+    pub fn enable_encryption(&mut self, secret: &[u8]) -> Result<IndexKey> {
+        if self.encrypted {
+            bail!("The index is already encrypted");
+        }
+        let salt = crypto::new_salt();
+        let key = IndexKey::derive(secret, &crypto::decode_salt(&salt)?)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let rows: Vec<(i64, String)> = tx
+            .prepare("SELECT id, content FROM messages")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        for (id, content) in rows {
+            tx.execute(
+                "UPDATE messages SET content = ?1 WHERE id = ?2",
+                params![key.encrypt(&content)?, id],
+            )?;
+        }
+        for (id, summaries) in Self::session_summaries(&tx)? {
+            let sealed = summaries
+                .iter()
+                .map(|s| s.as_deref().map(|s| key.encrypt(s)).transpose())
+                .collect::<Result<Vec<_>>>()?;
+            tx.execute(
+                "UPDATE sessions SET summary = ?1, work_summary = ?2, llm_summary = ?3 WHERE id = ?4",
+                params![sealed[0], sealed[1], sealed[2], id],
+            )?;
+        }
+        tx.execute("DELETE FROM main.messages_fts", [])?;
+        tx.execute("UPDATE sessions_fts SET summary = '', work_summary = ''", [])?;
+        self.set_meta("encryption.salt", &salt)?;
+        self.set_meta("encryption.check", &key.encrypt(KEY_CHECK)?)?;
+        tx.commit()?;
+
+        // Don't leave plaintext behind in the WAL or in free pages
+        self.conn
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+
+        self.encrypted = true;
+        self.attach_key(key.clone())?;
+        Ok(key)
+    }
+
+    /// Decrypt every message back to plaintext and turn encryption off. Requires the key.
+    pub fn disable_encryption(&mut self) -> Result<()> {
+        let Some(key) = self.key.take() else {
+            bail!("The index is locked. Run `ail unlock` first.");
+        };
+
+        self.conn.execute_batch("DROP TABLE IF EXISTS temp.messages_fts;")?;
+        let tx = self.conn.unchecked_transaction()?;
+        let rows: Vec<(i64, String)> = tx
+            .prepare("SELECT id, content FROM messages")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        for (id, content) in rows {
+            if crypto::is_encrypted(&content) {
+                tx.execute(
+                    "UPDATE messages SET content = ?1 WHERE id = ?2",
+                    params![key.decrypt(&content)?, id],
+                )?;
+            }
+        }
+        for (id, summaries) in Self::session_summaries(&tx)? {
+            let opened = summaries
+                .into_iter()
+                .map(|s| match s {
+                    Some(s) if crypto::is_encrypted(&s) => key.decrypt(&s).map(Some),
+                    other => Ok(other),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            tx.execute(
+                "UPDATE sessions SET summary = ?1, work_summary = ?2, llm_summary = ?3 WHERE id = ?4",
+                params![opened[0], opened[1], opened[2], id],
+            )?;
+            tx.execute(
+                "UPDATE sessions_fts SET summary = ?1, work_summary = ?2 WHERE session_id = ?3",
+                params![opened[0].as_deref().unwrap_or(""), opened[1].as_deref().unwrap_or(""), id],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO main.messages_fts (session_id, role, content)
+             SELECT session_id, role, content FROM messages ORDER BY id",
+            [],
+        )?;
+        tx.execute("DELETE FROM meta WHERE key LIKE 'encryption.%'", [])?;
+        tx.commit()?;
+
+        self.encrypted = false;
+        Ok(())
+    }
+
+    fn ensure_unlocked(&self) -> Result<()> {
+        if self.is_locked() {
+            bail!("The index is locked. Run `ail unlock` first.");
+        }
+        Ok(())
+    }
+
+    /// Message content as stored: encrypted when encryption is on.
+    fn seal(&self, content: &str) -> Result<String> {
+        match (self.encrypted, &self.key) {
+            (false, _) => Ok(content.to_string()),
+            (true, Some(key)) => key.encrypt(content),
+            (true, None) => bail!("The index is locked. Run `ail unlock` first."),
+        }
+    }
+
+    /// Stored message content back to plaintext.
+    fn unseal(&self, stored: String) -> Result<String> {
+        if !crypto::is_encrypted(&stored) {
+            return Ok(stored);
+        }
+        self.ensure_unlocked()?;
+        match self.key {
+            Some(ref key) => key.decrypt(&stored),
+            None => bail!("Found encrypted content but the index is not encrypted"),
+        }
+    }
+
+    fn seal_summary(&self, summary: Option<&str>) -> Result<Option<String>> {
+        summary.map(|s| self.seal(s)).transpose()
+    }
+
+    /// A stored summary for display; `[locked]` while the index is locked
+    fn unseal_summary(&self, stored: Option<String>) -> Option<String> {
+        match (stored, &self.key) {
+            (Some(s), _) if !crypto::is_encrypted(&s) => Some(s),
+            (Some(s), Some(key)) => key.decrypt(&s).ok(),
+            (Some(_), None) => Some(LOCKED_SUMMARY.to_string()),
+            (None, _) => None,
+        }
+    }
+
+    fn open_session(&self, mut session: SessionRow) -> SessionRow {
+        session.summary = self.unseal_summary(session.summary.take());
+        session.work_summary = self.unseal_summary(session.work_summary.take());
+        session.llm_summary = self.unseal_summary(session.llm_summary.take());
+        session
+    }
+
+    /// Summary text for `sessions_fts`, which keeps none when encrypted
+    fn fts_summary<'a>(&self, summary: Option<&'a str>) -> &'a str {
+        if self.encrypted {
+            ""
+        } else {
+            summary.unwrap_or("")
+        }
+    }
+
+    /// Each session's `summary`, `work_summary` and `llm_summary` as stored
+    #[allow(clippy::type_complexity)]
+    fn session_summaries(conn: &Connection) -> Result<Vec<(String, [Option<String>; 3])>> {
+        let rows = conn
+            .prepare("SELECT id, summary, work_summary, llm_summary FROM sessions")?
+            .query_map([], |row| Ok((row.get(0)?, [row.get(1)?, row.get(2)?, row.get(3)?])))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(rows)
+    }
+}
+
 /// Parse a duration string like "7d", "2w", "1m" into a chrono::Duration
 pub fn parse_duration(s: &str) -> Option<chrono::Duration> {
     let s = s.trim();
@@ -789,3 +2129,53 @@ pub fn parse_duration(s: &str) -> Option<chrono::Duration> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::traits::{AgentType, MessageData, Role};
+
+    #[test]
+    fn refresh_outcomes_skips_read_only_index() {
+        let dir = std::env::temp_dir().join(format!("ail-db-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("team.db");
+        let long_ago = Utc::now() - chrono::Duration::days(1);
+        {
+            let db = Database::open(&path).unwrap();
+            db.insert_session(&SessionData {
+                id: "s1".to_string(),
+                conversation_id: None,
+                agent: AgentType::ClaudeCode,
+                project_path: None,
+                project_name: None,
+                summary: None,
+                work_summary: None,
+                started_at: Some(long_ago),
+                ended_at: Some(long_ago),
+                messages: vec![MessageData {
+                    role: Role::User,
+                    content: "fix it".to_string(),
+                    timestamp: Some(long_ago),
+                    files_changed: Vec::new(),
+                    uuid: None,
+                    parent_uuid: None,
+                }],
+                tool_calls: Vec::new(),
+                tags: Vec::new(),
+                git_branch: None,
+            })
+            .unwrap();
+            // Labelled while it was still going; stale by now
+            db.set_outcome("s1", Outcome::InProgress, "active 0m ago", "rules").unwrap();
+            db.make_shareable().unwrap();
+        }
+
+        let db = Database::open_read_only(&path).unwrap();
+        assert!(db.is_read_only());
+        assert_eq!(db.refresh_outcomes().unwrap(), 0);
+        let session = db.get_session("s1").unwrap().unwrap();
+        assert_eq!(session.outcome.as_deref(), Some("in-progress"));
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::adapters::AgentType;
+use crate::core::clock;
+
+/// "Claude Code" for `claude-code`; unknown agents as stored
+pub fn agent_display_name(agent: &str) -> &str {
+    AgentType::from_str(agent).map_or(agent, |a| a.display_name())
+}
+
+/// "created", "modified" or "deleted" for tools that change a file
+pub fn change_type(tool_name: &str) -> Option<&'static str> {
+    match tool_name {
+        "Write" | "create_file" => Some("created"),
+        "Edit" | "edit_file" => Some("modified"),
+        "delete_file" => Some("deleted"),
+        _ => None,
+    }
+}
+
+/// `2025-01-13 09:30` in the configured timezone
+pub fn format_datetime(ts: &str) -> String {
+    clock::format_local(ts, "%Y-%m-%d %H:%M").unwrap_or_else(|| ts.to_string())
+}
+
+/// Cut to `max_chars` characters, adding "..."
+pub fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        let end = s.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(s.len());
+        format!("{}...", &s[..end])
+    }
+}
@@ -0,0 +1,396 @@
+use crate::core::clock;
+use crate::core::db::{Database, SessionRow, ToolCallRow};
+use crate::core::display::{agent_display_name, change_type};
+use crate::core::redact::Redactor;
+use crate::core::thread;
+use anyhow::{bail, Context, Result};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::fmt::Write;
+use std::path::Path;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+const STYLE: &str = include_str!("assets/style.css");
+const SEARCH_JS: &str = include_str!("assets/search.js");
+
+/// Characters of conversation text per session kept in the site search index
+const SEARCH_TEXT_CHARS: usize = 5000;
+
+/// Renders sessions as standalone HTML pages. Holds the syntax definitions so
+/// a site build loads them once.
+pub struct HtmlRenderer<'r> {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    redactor: &'r Redactor,
+}
+
+impl<'r> HtmlRenderer<'r> {
+    pub fn new(redactor: &'r Redactor) -> Self {
+        let mut themes = ThemeSet::load_defaults();
+        let theme = themes
+            .themes
+            .remove("InspiredGitHub")
+            .expect("built-in syntect theme");
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme,
+            redactor,
+        }
+    }
+
+    /// One self-contained page: conversation turns, collapsible tool calls and
+    /// a changed-files sidebar. `back_link` adds a link to a site index.
+    pub fn session_page(&self, db: &Database, session: &SessionRow, back_link: Option<&str>) -> Result<String> {
+        let messages = db.get_messages(&session.id)?;
+        let tool_calls = db.get_tool_calls(&session.id)?;
+        let turns = thread::build_turns(&messages, &tool_calls);
+        let title = self.redactor.redact(session.summary.as_deref().unwrap_or("Untitled session"));
+
+        let mut out = String::new();
+        page_head(&mut out, &title)?;
+
+        writeln!(out, "<header class=\"top\">")?;
+        if let Some(link) = back_link {
+            writeln!(out, "<a class=\"back\" href=\"{}\">← All sessions</a>", escape(link))?;
+        }
+        writeln!(out, "<h1>{}</h1>", escape(&title))?;
+        let mut meta = vec![escape(agent_display_name(&session.agent))];
+        if let Some(p) = session.project_path.as_ref().or(session.project_name.as_ref()) {
+            meta.push(escape(p));
+        }
+        if let Some(ref started) = session.started_at {
+            meta.push(time_tag(started, "%Y-%m-%d %H:%M"));
+        }
+        meta.push(format!("{} messages", session.message_count));
+        meta.push(format!("<code>{}</code>", escape(&session.id)));
+        writeln!(out, "<div class=\"meta\">{}</div>", meta.join(" · "))?;
+        writeln!(out, "</header>")?;
+
+        writeln!(out, "<div class=\"layout\">")?;
+        self.files_sidebar(&mut out, &tool_calls)?;
+
+        writeln!(out, "<main>")?;
+        for turn in &turns {
+            let indent = turn.depth * 24;
+            if turn.branch_start {
+                writeln!(
+                    out,
+                    "<div class=\"branch-marker\" style=\"margin-left:{}px\">┆ Abandoned branch</div>",
+                    indent
+                )?;
+            }
+            let class = if turn.abandoned { "turn abandoned" } else { "turn" };
+            writeln!(out, "<section class=\"{}\" style=\"margin-left:{}px\">", class, indent)?;
+            for m in turn.messages().filter(|m| m.role != "tool") {
+                let (role_class, label) = if m.role == "user" { ("user", "You") } else { ("ai", "AI") };
+                let time = m
+                    .timestamp
+                    .as_deref()
+                    .map(|t| time_tag(t, "%H:%M"))
+                    .unwrap_or_default();
+                writeln!(out, "<article class=\"msg {}\">", role_class)?;
+                writeln!(out, "<div class=\"msg-head\"><span class=\"role\">{}</span>{}</div>", label, time)?;
+                writeln!(out, "<div class=\"body\">{}</div>", self.markdown(&self.redactor.redact(&m.content)))?;
+                writeln!(out, "</article>")?;
+            }
+            if !turn.tool_calls.is_empty() {
+                self.tool_calls(&mut out, &turn.tool_calls)?;
+            }
+            writeln!(out, "</section>")?;
+        }
+        writeln!(out, "</main>")?;
+        writeln!(out, "</div>")?;
+
+        page_foot(&mut out)?;
+        Ok(out)
+    }
+
+    fn files_sidebar(&self, out: &mut String, tool_calls: &[ToolCallRow]) -> Result<()> {
+        let mut files: Vec<(&str, &str)> = Vec::new();
+        for tc in tool_calls {
+            let Some(change) = change_type(&tc.tool_name) else { continue };
+            let Some(path) = tc.rel_path.as_deref().or(tc.file_path.as_deref()) else { continue };
+            match files.iter_mut().find(|(p, _)| *p == path) {
+                // A later delete or create wins over edits
+                Some(entry) if change != "modified" => entry.1 = change,
+                Some(_) => {}
+                None => files.push((path, change)),
+            }
+        }
+
+        writeln!(out, "<aside class=\"sidebar\">")?;
+        writeln!(out, "<h2>Changed files</h2>")?;
+        if files.is_empty() {
+            writeln!(out, "<p class=\"empty\">No file changes</p>")?;
+        } else {
+            writeln!(out, "<ul class=\"files\">")?;
+            for (path, change) in files {
+                writeln!(
+                    out,
+                    "<li><span class=\"badge {0}\">{0}</span> <code>{1}</code></li>",
+                    change,
+                    escape(path)
+                )?;
+            }
+            writeln!(out, "</ul>")?;
+        }
+        writeln!(out, "</aside>")?;
+        Ok(())
+    }
+
+    fn tool_calls(&self, out: &mut String, tool_calls: &[&ToolCallRow]) -> Result<()> {
+        let count = match tool_calls.len() {
+            1 => "1 tool call".to_string(),
+            n => format!("{} tool calls", n),
+        };
+        writeln!(out, "<details class=\"tools\">")?;
+        writeln!(
+            out,
+            "<summary>{} — {}</summary>",
+            count,
+            escape(&thread::tool_call_summary(tool_calls))
+        )?;
+        writeln!(out, "<ul>")?;
+        for tc in tool_calls {
+            write!(out, "<li><code>{}</code>", escape(&tc.tool_name))?;
+            if let Some(path) = tc.rel_path.as_deref().or(tc.file_path.as_deref()) {
+                write!(out, " {}", escape(path))?;
+            }
+            if let Some(ref ts) = tc.timestamp {
+                write!(out, " {}", time_tag(ts, "%H:%M:%S"))?;
+            }
+            writeln!(out, "</li>")?;
+        }
+        writeln!(out, "</ul>")?;
+        writeln!(out, "</details>")?;
+        Ok(())
+    }
+
+    /// Markdown to HTML. Raw HTML in messages is shown as text, and fenced
+    /// code blocks are syntax-highlighted.
+    fn markdown(&self, text: &str) -> String {
+        let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+        let mut events: Vec<Event> = Vec::new();
+        let mut code: Option<(String, String)> = None;
+
+        for event in Parser::new_ext(text, options) {
+            match event {
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    let lang = match kind {
+                        CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or("").to_string(),
+                        CodeBlockKind::Indented => String::new(),
+                    };
+                    code = Some((lang, String::new()));
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    if let Some((lang, source)) = code.take() {
+                        events.push(Event::Html(self.highlight(&source, &lang).into()));
+                    }
+                }
+                Event::Text(t) if code.is_some() => {
+                    if let Some((_, ref mut source)) = code {
+                        source.push_str(&t);
+                    }
+                }
+                Event::Html(h) | Event::InlineHtml(h) => events.push(Event::Text(h)),
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: safe_url(dest_url),
+                    title,
+                    id,
+                })),
+                Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => events.push(Event::Start(Tag::Image {
+                    link_type,
+                    dest_url: safe_url(dest_url),
+                    title,
+                    id,
+                })),
+                other => events.push(other),
+            }
+        }
+
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, events.into_iter());
+        html
+    }
+
+    fn highlight(&self, source: &str, lang: &str) -> String {
+        let syntax = self
+            .syntaxes
+            .find_syntax_by_token(lang)
+            .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text());
+        highlighted_html_for_string(source, &self.syntaxes, syntax, &self.theme)
+            .unwrap_or_else(|_| format!("<pre><code>{}</code></pre>", escape(source)))
+    }
+}
+
+/// A single session as one self-contained HTML file.
+pub fn export_session_html(db: &Database, session_id: &str, redactor: &Redactor) -> Result<String> {
+    let session = db
+        .get_session(session_id)?
+        .with_context(|| format!("Session not found: {}", session_id))?;
+    HtmlRenderer::new(redactor).session_page(db, &session, None)
+}
+
+/// Write a static site to `out_dir`: `index.html` with a client-side search
+/// box, one page per session under `sessions/`, and `search-index.js`.
+/// Everything opens straight from disk. Returns the number of pages written.
+pub fn build_site(db: &Database, sessions: &[SessionRow], out_dir: &Path, redactor: &Redactor) -> Result<usize> {
+    if sessions.is_empty() {
+        bail!("No sessions matched");
+    }
+    let pages_dir = out_dir.join("sessions");
+    std::fs::create_dir_all(&pages_dir)
+        .with_context(|| format!("Failed to create {}", pages_dir.display()))?;
+
+    let renderer = HtmlRenderer::new(redactor);
+    let mut search_index = Vec::new();
+    let mut rows = String::new();
+
+    for session in sessions {
+        let file_name = format!("{}.html", page_name(&session.id));
+        let page = renderer.session_page(db, session, Some("../index.html"))?;
+        std::fs::write(pages_dir.join(&file_name), page)?;
+
+        let title = redactor.redact(session.summary.as_deref().unwrap_or("Untitled session"));
+        let date = session
+            .started_at
+            .as_deref()
+            .map(|t| time_tag(t, "%Y-%m-%d %H:%M"))
+            .unwrap_or_default();
+        writeln!(
+            rows,
+            "<tr data-id=\"{}\"><td class=\"date\">{}</td><td>{}</td><td>{}</td><td><a href=\"sessions/{}\">{}</a></td><td class=\"num\">{}</td></tr>",
+            escape(&session.id),
+            date,
+            escape(agent_display_name(&session.agent)),
+            escape(session.project_name.as_deref().unwrap_or("-")),
+            escape(&file_name),
+            escape(&title),
+            session.message_count
+        )?;
+
+        let mut text = format!(
+            "{} {} {} {} {}",
+            title,
+            session.project_name.as_deref().unwrap_or(""),
+            session.agent,
+            session.tags,
+            session.work_summary.as_deref().unwrap_or("")
+        );
+        for m in db.get_messages(&session.id)? {
+            if text.len() >= SEARCH_TEXT_CHARS {
+                break;
+            }
+            text.push(' ');
+            text.push_str(&redactor.redact(&m.content));
+        }
+        let text: String = text.chars().take(SEARCH_TEXT_CHARS).collect();
+        search_index.push(serde_json::json!({ "id": session.id, "text": text }));
+    }
+
+    let mut index = String::new();
+    page_head(&mut index, "AI Sessions")?;
+    writeln!(index, "<header class=\"top\">")?;
+    writeln!(index, "<h1>AI Sessions</h1>")?;
+    writeln!(index, "<div class=\"meta\">{} sessions</div>", sessions.len())?;
+    writeln!(
+        index,
+        "<input id=\"q\" type=\"search\" placeholder=\"Search requests, projects and conversations…\" autofocus>"
+    )?;
+    writeln!(index, "</header>")?;
+    writeln!(index, "<main class=\"index\">")?;
+    writeln!(index, "<table class=\"sessions\">")?;
+    writeln!(
+        index,
+        "<thead><tr><th>Date</th><th>Agent</th><th>Project</th><th>Request</th><th>Msgs</th></tr></thead>"
+    )?;
+    writeln!(index, "<tbody>\n{}</tbody>", rows)?;
+    writeln!(index, "</table>")?;
+    writeln!(index, "<p id=\"no-results\" class=\"empty\" hidden>No matching sessions.</p>")?;
+    writeln!(index, "</main>")?;
+    writeln!(index, "<script src=\"search-index.js\"></script>")?;
+    writeln!(index, "<script>\n{}</script>", SEARCH_JS)?;
+    page_foot(&mut index)?;
+
+    std::fs::write(out_dir.join("index.html"), index)?;
+    std::fs::write(
+        out_dir.join("search-index.js"),
+        format!("window.AIL_SEARCH_INDEX = {};\n", serde_json::to_string(&search_index)?),
+    )?;
+
+    Ok(sessions.len())
+}
+
+fn page_head(out: &mut String, title: &str) -> Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">")?;
+    writeln!(out, "<title>{} · ail</title>", escape(title))?;
+    writeln!(out, "<style>\n{}</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    Ok(())
+}
+
+fn page_foot(out: &mut String) -> Result<()> {
+    writeln!(
+        out,
+        "<footer>Generated by ail on {}</footer>",
+        clock::calendar().local(chrono::Utc::now()).format("%Y-%m-%d %H:%M")
+    )?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(())
+}
+
+/// `<time>` with the full timestamp as its machine-readable value
+fn time_tag(ts: &str, format: &str) -> String {
+    let shown = clock::format_local(ts, format).unwrap_or_else(|| ts.to_string());
+    format!("<time datetime=\"{}\" title=\"{0}\">{}</time>", escape(ts), escape(&shown))
+}
+
+/// Drop script URLs from links and images in conversation text
+fn safe_url(url: CowStr) -> CowStr {
+    let scheme = url.trim_start().to_ascii_lowercase();
+    if scheme.starts_with("javascript:") || scheme.starts_with("vbscript:") || scheme.starts_with("data:text/html") {
+        CowStr::Borrowed("#")
+    } else {
+        url
+    }
+}
+
+/// Session ids made safe for file names
+fn page_name(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
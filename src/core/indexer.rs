@@ -1,5 +1,6 @@
 use crate::adapters::{self, traits::AgentAdapter};
 use crate::core::db::Database;
+use crate::core::team::Identity;
 use anyhow::Result;
 
 pub struct IndexResult {
@@ -43,6 +44,7 @@ fn index_adapter(db: &Database, adapter: &dyn AgentAdapter) -> Result<IndexResul
     let sessions_found = sessions.len();
     let mut sessions_new = 0;
     let mut sessions_updated = 0;
+    let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
 
     for session in sessions {
         if db.session_exists(&session.id)? {
@@ -58,6 +60,10 @@ fn index_adapter(db: &Database, adapter: &dyn AgentAdapter) -> Result<IndexResul
         }
     }
 
+    // Sessions found on this machine belong to its user
+    let me = Identity::load();
+    db.stamp_local_origin(&ids, &me.author, &me.machine)?;
+
     Ok(IndexResult {
         agent: agent_name,
         sessions_found,
@@ -0,0 +1,177 @@
+use crate::core::context::{export_context, DetailLevel};
+use crate::core::db::Database;
+use crate::core::redact::Redactor;
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+const START_MARKER: &str = "<!-- ail:context:start -->";
+const END_MARKER: &str = "<!-- ail:context:end -->";
+
+/// Frontmatter for the Cursor rule file, so the context is always attached
+const CURSOR_RULE_HEADER: &str = "---
+description: Context from previous AI coding sessions (generated by ail)
+globs:
+alwaysApply: true
+---
+";
+
+/// An agent's native instruction file that context can be injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectTarget {
+    Claude,
+    Codex,
+    Cursor,
+    Gemini,
+}
+
+impl InjectTarget {
+    pub const ALL: [InjectTarget; 4] = [
+        InjectTarget::Claude,
+        InjectTarget::Codex,
+        InjectTarget::Cursor,
+        InjectTarget::Gemini,
+    ];
+
+    /// Parse `claude`, `codex`, `cursor`, `gemini` or `all`.
+    pub fn parse_list(s: &str) -> Result<Vec<InjectTarget>> {
+        let mut targets = Vec::new();
+        for name in s.split(',').map(|n| n.trim().to_lowercase()) {
+            let parsed: &[InjectTarget] = match name.as_str() {
+                "claude" | "claude-code" => &[InjectTarget::Claude],
+                "codex" => &[InjectTarget::Codex],
+                "cursor" => &[InjectTarget::Cursor],
+                "gemini" => &[InjectTarget::Gemini],
+                "all" => &InjectTarget::ALL,
+                _ => bail!("Unknown inject target: {} (use claude, codex, cursor, gemini or all)", name),
+            };
+            for t in parsed {
+                if !targets.contains(t) {
+                    targets.push(*t);
+                }
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Instruction file relative to the project root
+    pub fn relative_path(&self) -> &'static str {
+        match self {
+            InjectTarget::Claude => "CLAUDE.md",
+            InjectTarget::Codex => "AGENTS.md",
+            InjectTarget::Cursor => ".cursor/rules/ail-context.mdc",
+            InjectTarget::Gemini => "GEMINI.md",
+        }
+    }
+
+    pub fn file_path(&self, project_path: &Path) -> PathBuf {
+        project_path.join(self.relative_path())
+    }
+}
+
+pub fn inject_context(
+    db: &Database,
+    session_id: &str,
+    project_path: &Path,
+    targets: &[InjectTarget],
+    redactor: &Redactor,
+) -> Result<Vec<PathBuf>> {
+    let context = redactor.redact(&export_context(db, session_id, DetailLevel::Summary)?);
+    targets
+        .iter()
+        .map(|t| inject_content(&context, project_path, *t))
+        .collect()
+}
+
+/// Write `context` into the target's instruction file inside an ail marker
+/// block. An existing block is replaced in place, so re-injecting never
+/// duplicates it. Returns the file written.
+pub fn inject_content(context: &str, project_path: &Path, target: InjectTarget) -> Result<PathBuf> {
+    let path = target.file_path(project_path);
+    let existing = if path.exists() {
+        std::fs::read_to_string(&path)?
+    } else if target == InjectTarget::Cursor {
+        CURSOR_RULE_HEADER.to_string()
+    } else {
+        String::new()
+    };
+
+    let block = format!("{}\n{}\n{}", START_MARKER, context.trim_end(), END_MARKER);
+    let (mut content, found) = replace_block(&existing, &block);
+    if !found {
+        content = existing.trim_end().to_string();
+        content.push_str(if content.is_empty() { "" } else { "\n\n" });
+        content.push_str(&block);
+        content.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Strip the ail block from the target's file. Files left empty (and the
+/// Cursor rule file, which ail owns) are deleted. Returns whether anything was removed.
+pub fn remove_injected(project_path: &Path, target: InjectTarget) -> Result<bool> {
+    let path = target.file_path(project_path);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let existing = std::fs::read_to_string(&path)?;
+    let (stripped, found) = replace_block(&existing, "");
+    if !found {
+        return Ok(false);
+    }
+
+    let content = stripped.trim_end();
+    if target == InjectTarget::Cursor || content.is_empty() {
+        std::fs::remove_file(&path)?;
+    } else {
+        std::fs::write(&path, format!("{}\n", content))?;
+    }
+    Ok(true)
+}
+
+/// Replace every ail marker block in `content` with `replacement` (only the
+/// first gets it; later duplicates from older versions are dropped).
+fn replace_block(content: &str, replacement: &str) -> (String, bool) {
+    let mut out = String::new();
+    let mut rest = content;
+    let mut found = false;
+
+    while let Some(start) = rest.find(START_MARKER) {
+        let Some(end_rel) = rest[start..].find(END_MARKER) else {
+            break;
+        };
+        let end = start + end_rel + END_MARKER.len();
+        let before = &rest[..start];
+        out.push_str(if replacement.is_empty() || found { before.trim_end_matches('\n') } else { before });
+        if !found {
+            out.push_str(replacement);
+        }
+        found = true;
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    (out, found)
+}
+
+pub fn auto_inject(
+    db: &Database,
+    targets: &[InjectTarget],
+    redactor: &Redactor,
+) -> Result<(String, Vec<PathBuf>)> {
+    let cwd = std::env::current_dir()?;
+    let cwd_str = cwd.to_string_lossy().to_string();
+
+    // Find the most recent session for the current project
+    let sessions = db.list_sessions(None, Some(&cwd_str), None, None, 1)?;
+
+    if let Some(session) = sessions.first() {
+        let written = inject_context(db, &session.id, &cwd, targets, redactor)?;
+        Ok((session.id.clone(), written))
+    } else {
+        bail!("No sessions found for current project: {}", cwd_str)
+    }
+}
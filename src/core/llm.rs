@@ -0,0 +1,390 @@
+use crate::config::SummarizeConfig;
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// A chat model that answers one prompt at a time
+pub trait LlmProvider {
+    /// Send `prompt` as a single user message and return the reply text
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String>;
+}
+
+const OPENAI_URL: &str = "https://api.openai.com/v1";
+
+/// Which API `[report.summarize]` talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Anthropic,
+    /// `/chat/completions` — OpenAI, llama.cpp's server, vLLM, LM Studio
+    OpenAi,
+    Ollama,
+}
+
+impl ProviderKind {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "anthropic" | "claude" => Some(ProviderKind::Anthropic),
+            "openai" | "openai-compatible" | "llamacpp" | "llama.cpp" => Some(ProviderKind::OpenAi),
+            "ollama" => Some(ProviderKind::Ollama),
+            _ => None,
+        }
+    }
+
+    fn default_base_url(self, name: &str) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "https://api.anthropic.com",
+            ProviderKind::OpenAi if name.starts_with("llama") => "http://localhost:8080/v1",
+            ProviderKind::OpenAi => OPENAI_URL,
+            ProviderKind::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// The model used when `model` isn't set, if the endpoint has an obvious one
+    fn default_model(self, name: &str, base_url: &str) -> Option<&'static str> {
+        match self {
+            ProviderKind::Anthropic => Some("claude-haiku-4-5-20251001"),
+            // llama-server answers with whichever model it loaded
+            ProviderKind::OpenAi if name.starts_with("llama") => Some("default"),
+            ProviderKind::OpenAi if base_url == OPENAI_URL => Some("gpt-4o-mini"),
+            ProviderKind::OpenAi => None,
+            ProviderKind::Ollama => Some("llama3.2"),
+        }
+    }
+
+    fn default_key_env(self) -> Option<&'static str> {
+        match self {
+            ProviderKind::Anthropic => Some("ANTHROPIC_API_KEY"),
+            ProviderKind::OpenAi => Some("OPENAI_API_KEY"),
+            ProviderKind::Ollama => None,
+        }
+    }
+}
+
+/// The provider configured under `[report.summarize]`
+pub fn provider(config: &SummarizeConfig) -> Result<Box<dyn LlmProvider>> {
+    let Some(kind) = ProviderKind::from_str(&config.provider) else {
+        bail!(
+            "Unknown summarize provider '{}'. Use anthropic, openai, llamacpp or ollama.",
+            config.provider
+        );
+    };
+    let name = config.provider.to_lowercase();
+    let base_url = match config.base_url {
+        Some(ref url) if !url.is_empty() => url.trim_end_matches('/').to_string(),
+        _ => kind.default_base_url(&name).to_string(),
+    };
+    let model = match config.model {
+        Some(ref model) if !model.is_empty() => model.clone(),
+        _ => match kind.default_model(&name, &base_url) {
+            Some(model) => model.to_string(),
+            None => bail!("Set model in [report.summarize] for {}", base_url),
+        },
+    };
+    let api_key = resolve_api_key(config, kind, base_url == kind.default_base_url(&name))?;
+
+    Ok(match kind {
+        ProviderKind::Anthropic => Box::new(Anthropic {
+            base_url,
+            api_key: api_key.unwrap_or_default(),
+            model,
+        }),
+        ProviderKind::OpenAi => Box::new(OpenAi { base_url, api_key, model }),
+        ProviderKind::Ollama => Box::new(Ollama { base_url, api_key, model }),
+    })
+}
+
+/// The API key: `api_key` in config, then the `api_key_env` variable. The
+/// provider's usual variable (ANTHROPIC_API_KEY / OPENAI_API_KEY) is only read
+/// for its own endpoint, so a key is never sent to some other host unasked.
+/// Only Anthropic and the hosted OpenAI endpoint require a key; local servers
+/// are called without one.
+fn resolve_api_key(config: &SummarizeConfig, kind: ProviderKind, default_url: bool) -> Result<Option<String>> {
+    if let Some(ref key) = config.api_key {
+        if !key.is_empty() {
+            return Ok(Some(key.clone()));
+        }
+    }
+    let env = match config.api_key_env {
+        Some(ref name) => Some(name.as_str()),
+        None if default_url => kind.default_key_env(),
+        None => None,
+    };
+    if let Some(key) = env.and_then(|name| std::env::var(name).ok()) {
+        if !key.is_empty() {
+            return Ok(Some(key));
+        }
+    }
+    let required = match kind {
+        ProviderKind::Anthropic => true,
+        ProviderKind::OpenAi => default_url && !config.provider.to_lowercase().starts_with("llama"),
+        ProviderKind::Ollama => false,
+    };
+    match env {
+        _ if !required => Ok(None),
+        Some(name) => bail!(
+            "No API key found. Set {} environment variable or add api_key to [report.summarize] in config.",
+            name
+        ),
+        None => bail!("No API key found. Add api_key or api_key_env to [report.summarize] in config."),
+    }
+}
+
+pub struct Anthropic {
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl LlmProvider for Anthropic {
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{ "role": "user", "content": prompt }]
+        });
+        let request = ureq::post(&format!("{}/v1/messages", self.base_url))
+            .set("x-api-key", &self.api_key)
+            .set("anthropic-version", "2023-06-01");
+        let json = send(request, body)?;
+        reply_text(json.pointer("/content/0/text"))
+    }
+}
+
+pub struct OpenAi {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl LlmProvider for OpenAi {
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{ "role": "user", "content": prompt }]
+        });
+        let request = bearer(ureq::post(&format!("{}/chat/completions", self.base_url)), &self.api_key);
+        let json = send(request, body)?;
+        reply_text(json.pointer("/choices/0/message/content"))
+    }
+}
+
+pub struct Ollama {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl LlmProvider for Ollama {
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "stream": false,
+            "options": { "num_predict": max_tokens },
+            "messages": [{ "role": "user", "content": prompt }]
+        });
+        let request = bearer(ureq::post(&format!("{}/api/chat", self.base_url)), &self.api_key);
+        let json = send(request, body)?;
+        reply_text(json.pointer("/message/content"))
+    }
+}
+
+fn bearer(request: ureq::Request, api_key: &Option<String>) -> ureq::Request {
+    match api_key {
+        Some(key) => request.set("authorization", &format!("Bearer {}", key)),
+        None => request,
+    }
+}
+
+fn send(request: ureq::Request, body: Value) -> Result<Value> {
+    let resp = match request.set("content-type", "application/json").send_json(body) {
+        Ok(r) => r,
+        Err(ureq::Error::Status(code, resp)) => {
+            let body = resp.into_string().unwrap_or_default();
+            bail!("API error ({}): {}", code, body);
+        }
+        Err(e) => bail!("Request failed: {}", e),
+    };
+    Ok(resp.into_json()?)
+}
+
+fn reply_text(text: Option<&Value>) -> Result<String> {
+    let text = text.and_then(|t| t.as_str()).unwrap_or("").trim();
+    if text.is_empty() {
+        bail!("Empty response from API");
+    }
+    Ok(text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// What the stub server received
+    struct Captured {
+        path: String,
+        headers: Vec<(String, String)>,
+        body: Value,
+    }
+
+    impl Captured {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Answer one request on 127.0.0.1 with `reply`; returns the base URL and
+    /// a receiver for the captured request
+    fn stub_server(reply: Value) -> (String, mpsc::Receiver<Captured>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let path = line.split_whitespace().nth(1).unwrap_or("").to_string();
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            let length: usize = headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, v)| v.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let reply = reply.to_string();
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .unwrap();
+            tx.send(Captured {
+                path,
+                headers,
+                body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+            })
+            .unwrap();
+        });
+        (url, rx)
+    }
+
+    fn config(provider: &str, base_url: &str) -> SummarizeConfig {
+        SummarizeConfig {
+            provider: provider.to_string(),
+            base_url: Some(base_url.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn anthropic_sends_messages_request() {
+        let (url, rx) = stub_server(serde_json::json!({ "content": [{ "type": "text", "text": " Fixed the login bug \n" }] }));
+        let mut config = config("anthropic", &url);
+        config.api_key = Some("sk-test".to_string());
+
+        let reply = provider(&config).unwrap().complete("Summarize this", 300).unwrap();
+        assert_eq!(reply, "Fixed the login bug");
+
+        let request = rx.recv().unwrap();
+        assert_eq!(request.path, "/v1/messages");
+        assert_eq!(request.header("x-api-key"), Some("sk-test"));
+        assert_eq!(request.header("anthropic-version"), Some("2023-06-01"));
+        assert_eq!(request.body["model"], "claude-haiku-4-5-20251001");
+        assert_eq!(request.body["max_tokens"], 300);
+        assert_eq!(request.body["messages"][0]["role"], "user");
+        assert_eq!(request.body["messages"][0]["content"], "Summarize this");
+    }
+
+    #[test]
+    fn openai_compatible_sends_chat_completion() {
+        let (url, rx) = stub_server(serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Added a timeout" } }]
+        }));
+        let mut config = config("openai", &format!("{}/v1/", url));
+        config.api_key = Some("local-key".to_string());
+        config.model = Some("qwen2.5-coder".to_string());
+
+        let reply = provider(&config).unwrap().complete("Summarize this", 100).unwrap();
+        assert_eq!(reply, "Added a timeout");
+
+        let request = rx.recv().unwrap();
+        assert_eq!(request.path, "/v1/chat/completions");
+        assert_eq!(request.header("authorization"), Some("Bearer local-key"));
+        assert_eq!(request.body["model"], "qwen2.5-coder");
+        assert_eq!(request.body["max_tokens"], 100);
+        assert_eq!(request.body["messages"][0]["content"], "Summarize this");
+    }
+
+    #[test]
+    fn ollama_sends_chat_without_key() {
+        let (url, rx) = stub_server(serde_json::json!({
+            "message": { "role": "assistant", "content": "Refactored the parser" },
+            "done": true
+        }));
+        let config = config("ollama", &url);
+
+        let reply = provider(&config).unwrap().complete("Summarize this", 50).unwrap();
+        assert_eq!(reply, "Refactored the parser");
+
+        let request = rx.recv().unwrap();
+        assert_eq!(request.path, "/api/chat");
+        assert_eq!(request.header("authorization"), None);
+        assert_eq!(request.body["model"], "llama3.2");
+        assert_eq!(request.body["stream"], false);
+        assert_eq!(request.body["options"]["num_predict"], 50);
+        assert_eq!(request.body["messages"][0]["content"], "Summarize this");
+    }
+
+    #[test]
+    fn empty_reply_is_an_error() {
+        let (url, _rx) = stub_server(serde_json::json!({ "message": { "content": "  " } }));
+        let err = provider(&config("ollama", &url)).unwrap().complete("hi", 10).unwrap_err();
+        assert!(err.to_string().contains("Empty response"));
+    }
+
+    #[test]
+    fn custom_endpoint_needs_a_model() {
+        let err = provider(&config("openai", "http://127.0.0.1:9/v1")).err().unwrap();
+        assert!(err.to_string().contains("Set model"));
+    }
+
+    #[test]
+    fn default_key_is_not_sent_to_other_hosts() {
+        std::env::set_var("OPENAI_API_KEY", "sk-real-openai-key");
+        let hosted = SummarizeConfig {
+            provider: "openai".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_api_key(&hosted, ProviderKind::OpenAi, true).unwrap().as_deref(),
+            Some("sk-real-openai-key")
+        );
+        let mut other = config("openai", "https://llm.example.com/v1");
+        other.model = Some("m".to_string());
+        assert_eq!(resolve_api_key(&other, ProviderKind::OpenAi, false).unwrap(), None);
+
+        let anthropic_proxy = config("anthropic", "https://proxy.example.com");
+        assert!(provider(&anthropic_proxy).is_err());
+    }
+}
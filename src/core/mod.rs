@@ -1,6 +1,27 @@
 pub mod db;
 pub mod indexer;
+pub mod paths;
 pub mod search;
 pub mod context;
 pub mod report;
 pub mod summarize;
+pub mod related;
+pub mod thread;
+pub mod template;
+pub mod budget;
+pub mod bundle;
+pub mod inject;
+pub mod convert;
+pub mod redact;
+pub mod crypto;
+pub mod html;
+pub mod display;
+pub mod archive;
+pub mod team;
+pub mod activity;
+pub mod trend;
+pub mod refs;
+pub mod schedule;
+pub mod clock;
+pub mod outcome;
+pub mod llm;
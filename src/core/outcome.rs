@@ -0,0 +1,305 @@
+use crate::config::SummarizeConfig;
+use crate::core::db::{Database, SessionRow};
+use crate::core::{llm, summarize};
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+
+/// How a session ended, as far as its transcript shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Failed,
+    Abandoned,
+    InProgress,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 4] = [Outcome::Success, Outcome::Failed, Outcome::Abandoned, Outcome::InProgress];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Failed => "failed",
+            Outcome::Abandoned => "abandoned",
+            Outcome::InProgress => "in-progress",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "success" | "succeeded" => Some(Outcome::Success),
+            "failed" | "failure" => Some(Outcome::Failed),
+            "abandoned" => Some(Outcome::Abandoned),
+            "in-progress" | "in_progress" | "ongoing" => Some(Outcome::InProgress),
+            _ => None,
+        }
+    }
+}
+
+/// What the rules look at, from a freshly parsed session or from the index
+#[derive(Debug, Default)]
+pub struct Evidence<'a> {
+    /// (role, content) in conversation order
+    pub messages: Vec<(&'a str, &'a str)>,
+    /// Whether each tool call's result was an error, in call order
+    pub tool_errors: Vec<bool>,
+    /// A tool call ran `git commit`
+    pub committed: bool,
+    pub files_changed: i64,
+    pub last_activity: Option<DateTime<Utc>>,
+}
+
+/// The user giving up on the task
+const ABANDON_PHRASES: &[&str] = &[
+    "never mind",
+    "nevermind",
+    "forget it",
+    "forget about it",
+    "cancel that",
+    "let's stop",
+    "stop here",
+    "do it myself",
+    "request interrupted by user",
+];
+
+/// The user rejecting the result, when their message opens with it
+const REJECT_PHRASES: &[&str] = &[
+    "that's wrong",
+    "that is wrong",
+    "this is wrong",
+    "that's not right",
+    "doesn't work",
+    "does not work",
+    "didn't work",
+    "still broken",
+    "still failing",
+    "still doesn't",
+];
+
+/// The user throwing the result away, only as a sentence of its own:
+/// "revert the config and add tests" is a new task
+const UNDO_PHRASES: &[&str] = &[
+    "revert",
+    "revert it",
+    "revert that",
+    "revert this",
+    "undo",
+    "undo it",
+    "undo that",
+    "undo this",
+    "roll it back",
+    "roll that back",
+];
+
+/// The agent saying it couldn't finish
+const GIVE_UP_PHRASES: &[&str] = &[
+    "i wasn't able to",
+    "i was unable to",
+    "i couldn't",
+    "i could not",
+    "unable to complete",
+    "i'm stuck",
+    "i don't have access",
+];
+
+/// The reason given when the user's last prompt went unanswered
+pub const NO_REPLY: &str = "no reply to the last prompt";
+
+/// This many failed tool calls in a row at the end means the agent was stuck
+const FAILING_TAIL: usize = 3;
+
+/// Label a session from its transcript, with a short reason. Activity within
+/// `idle` of `now` means it's still going; otherwise the first matching rule
+/// wins: a `git commit` tool call, the user's last word, the agent's last word, trailing tool
+/// errors, an unanswered prompt, then whether anything changed.
+pub fn classify(evidence: &Evidence, now: DateTime<Utc>, idle: Duration) -> (Outcome, String) {
+    if evidence.messages.is_empty() {
+        return (Outcome::Abandoned, "empty session".to_string());
+    }
+    if let Some(last) = evidence.last_activity {
+        if now - last < idle {
+            return (Outcome::InProgress, format!("active {}m ago", (now - last).num_minutes()));
+        }
+    }
+
+    if evidence.committed {
+        return (Outcome::Success, "committed".to_string());
+    }
+
+    let last_user = last_of(evidence, "user");
+    if let Some(phrase) = last_user.and_then(|text| find_phrase(text, ABANDON_PHRASES)) {
+        return (Outcome::Abandoned, format!("user said \"{}\"", phrase));
+    }
+    let rejected = last_user.and_then(|text| {
+        leading_phrase(text, REJECT_PHRASES)
+            .or_else(|| standalone_phrase(text, REJECT_PHRASES))
+            .or_else(|| standalone_phrase(text, UNDO_PHRASES))
+    });
+    if let Some(phrase) = rejected {
+        return (Outcome::Failed, format!("user said \"{}\"", phrase));
+    }
+    if let Some(phrase) = last_of(evidence, "assistant").and_then(|text| find_phrase(text, GIVE_UP_PHRASES)) {
+        return (Outcome::Failed, format!("agent said \"{}\"", phrase));
+    }
+
+    let failing = evidence.tool_errors.iter().rev().take_while(|e| **e).count();
+    if failing >= FAILING_TAIL {
+        return (Outcome::Failed, format!("last {} tool calls failed", failing));
+    }
+    if evidence.messages.last().is_some_and(|(role, _)| *role == "user") {
+        return (Outcome::Abandoned, NO_REPLY.to_string());
+    }
+    if evidence.files_changed > 0 {
+        return (Outcome::Success, format!("changed {} files", evidence.files_changed));
+    }
+    (Outcome::Success, "answered".to_string())
+}
+
+/// Ask the LLM configured under `[report.summarize]` to re-judge finished
+/// sessions the rules labelled, keeping the rules' label when the reply
+/// can't be read. Shows progress and continues on individual failures.
+pub fn refine_outcomes(db: &Database, sessions: &[SessionRow], config: &SummarizeConfig) -> Result<usize> {
+    let llm = llm::provider(config)?;
+
+    let mut to_refine = Vec::new();
+    for session in sessions {
+        let finished = session.outcome.as_deref().is_some_and(|o| o != Outcome::InProgress.as_str());
+        if finished && !db.outcome_refined(&session.id)? {
+            to_refine.push(session);
+        }
+    }
+    if to_refine.is_empty() {
+        return Ok(0);
+    }
+
+    let total = to_refine.len();
+    let mut refined = 0;
+    for (i, session) in to_refine.iter().enumerate() {
+        eprint!("Classifying outcomes {}/{}...\r", i + 1, total);
+        let text: String = summarize::build_session_text(db, session)
+            .chars()
+            .take(config.max_input_chars)
+            .collect();
+        let prompt = format!(
+            "How did this AI coding session end? A rule-based check said: {} ({}).\nReply with one word, success, failed or abandoned, then \" - \" and a reason under 80 chars.\n\nExample:\nabandoned - user switched to a different approach halfway\n\n{}",
+            session.outcome.as_deref().unwrap_or("-"),
+            session.outcome_reason.as_deref().unwrap_or("-"),
+            text
+        );
+        let result = llm.complete(&prompt, 100)
+            .and_then(|reply| parse_verdict(&reply))
+            .and_then(|(label, reason)| db.set_outcome(&session.id, label, &reason, "llm"));
+        match result {
+            Ok(()) => refined += 1,
+            Err(e) => eprintln!(
+                "\nFailed to classify session {}: {}",
+                &session.id[..session.id.len().min(8)],
+                e
+            ),
+        }
+    }
+    eprintln!("Classified {}/{} session outcomes.", refined, total);
+    Ok(refined)
+}
+
+/// `failed - tests still red` into its label and reason
+fn parse_verdict(reply: &str) -> Result<(Outcome, String)> {
+    let reply = reply.trim();
+    let (word, reason) = reply.split_once(" - ").unwrap_or((reply, ""));
+    match Outcome::from_str(word.trim().trim_matches(|c: char| !c.is_alphabetic() && c != '-')) {
+        Some(label) if label != Outcome::InProgress => Ok((label, reason.trim().to_string())),
+        _ => bail!("Unexpected reply: {}", reply),
+    }
+}
+
+fn last_of<'a>(evidence: &Evidence<'a>, role: &str) -> Option<&'a str> {
+    evidence
+        .messages
+        .iter()
+        .rev()
+        .find(|(r, _)| *r == role)
+        .map(|(_, content)| *content)
+}
+
+/// The first phrase found near the start of `text`; pasted logs further down don't count
+fn find_phrase(text: &str, phrases: &[&'static str]) -> Option<&'static str> {
+    let head = normalize(&text.chars().take(200).collect::<String>());
+    phrases.iter().copied().find(|p| contains_words(&head, p))
+}
+
+/// The phrase `text` opens with, ignoring case
+fn leading_phrase(text: &str, phrases: &[&'static str]) -> Option<&'static str> {
+    let text = normalize(text);
+    let text = text.trim_start();
+    phrases.iter().copied().find(|p| {
+        text.strip_prefix(p)
+            .is_some_and(|rest| !rest.starts_with(char::is_alphanumeric))
+    })
+}
+
+/// A phrase making up a whole sentence of `text`, e.g. "Undo." or "No. That's wrong!"
+fn standalone_phrase(text: &str, phrases: &[&'static str]) -> Option<&'static str> {
+    let text = normalize(text);
+    text.split(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .find_map(|sentence| phrases.iter().copied().find(|p| sentence == *p))
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase().replace('’', "'")
+}
+
+/// `phrase` in `text` on word boundaries, so "undo" doesn't match "undone"
+fn contains_words(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::runs_git_commit;
+
+    fn label(messages: &[(&'static str, &'static str)], committed: bool) -> Outcome {
+        let evidence = Evidence {
+            messages: messages.to_vec(),
+            committed,
+            ..Default::default()
+        };
+        classify(&evidence, Utc::now(), Duration::minutes(30)).0
+    }
+
+    #[test]
+    fn commit_comes_from_tool_calls_not_text() {
+        let talk = [("user", "fix it"), ("assistant", "Done, but I won't git commit yet."), ("user", "ok")];
+        assert_eq!(label(&talk, false), Outcome::Abandoned);
+        let talk = [("user", "fix it"), ("assistant", "Done."), ("user", "thanks")];
+        assert_eq!(label(&talk, false), Outcome::Abandoned);
+        assert_eq!(label(&talk, true), Outcome::Success);
+    }
+
+    #[test]
+    fn detects_git_commit_commands() {
+        assert!(runs_git_commit("git add -A && git commit -m 'x'"));
+        assert!(runs_git_commit("cd repo; git -C sub commit --amend"));
+        assert!(runs_git_commit("GIT_AUTHOR_NAME=x git commit -m y"));
+        assert!(!runs_git_commit("git status"));
+        assert!(!runs_git_commit("echo \"don't git commit\""));
+        assert!(!runs_git_commit("git log --grep commit"));
+    }
+
+    #[test]
+    fn reject_phrases_must_lead_or_stand_alone() {
+        let reply = |user: &'static str| {
+            label(&[("user", "fix it"), ("assistant", "Done."), ("user", user), ("assistant", "Ok.")], false)
+        };
+        assert_eq!(reply("That's wrong, the test still fails"), Outcome::Failed);
+        assert_eq!(reply("No. Revert it."), Outcome::Failed);
+        assert_eq!(reply("Undo"), Outcome::Failed);
+        assert_eq!(reply("revert the config and add tests"), Outcome::Success);
+        assert_eq!(reply("great, now make sure it doesn't work offline"), Outcome::Success);
+    }
+}
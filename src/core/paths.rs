@@ -0,0 +1,90 @@
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Normalize a tool-call file path to a repo-relative path.
+///
+/// Absolute paths are made relative to the git root (found from the session's
+/// project path, then from the file's own directory), falling back to the
+/// project path itself. Relative paths are cleaned of `./` segments.
+/// Returns `None` when no sensible root can be determined.
+pub fn normalize_file_path(file_path: &str, project_path: Option<&str>) -> Option<String> {
+    let path = Path::new(file_path);
+    if path.is_relative() {
+        return Some(clean_relative(path));
+    }
+
+    let project = project_path.map(Path::new);
+    let root = project
+        .and_then(find_git_root)
+        .filter(|root| path.starts_with(root))
+        .or_else(|| path.parent().and_then(find_git_root))
+        .or_else(|| project.filter(|p| path.starts_with(p)).map(Path::to_path_buf))?;
+
+    path.strip_prefix(&root)
+        .ok()
+        .map(clean_relative)
+        .filter(|rel| !rel.is_empty())
+}
+
+/// Walk up from `start` to the nearest directory containing `.git`
+/// (a directory for normal checkouts, a file for worktrees).
+pub fn find_git_root(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(d) = dir {
+        if d.join(".git").exists() {
+            return Some(d.to_path_buf());
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// Resolve a user-supplied path (absolute, or relative to `cwd`) to a
+/// path relative to the enclosing git root.
+pub fn to_repo_relative(input: &str, cwd: &Path) -> Option<(PathBuf, String)> {
+    let root = find_git_root(cwd)?;
+    let joined = if Path::new(input).is_absolute() {
+        PathBuf::from(input)
+    } else {
+        cwd.join(input)
+    };
+    // Canonicalize when the file still exists so symlinked checkouts line up
+    let abs = std::fs::canonicalize(&joined).unwrap_or(joined);
+    let root = std::fs::canonicalize(&root).unwrap_or(root);
+    let rel = abs.strip_prefix(&root).ok().map(clean_relative)?;
+    Some((root, rel))
+}
+
+/// All names a file has had in git history, following renames.
+/// The current name is always included first.
+pub fn git_path_history(repo_root: &Path, rel_path: &str) -> Vec<String> {
+    let mut names = vec![rel_path.to_string()];
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["log", "--follow", "--name-only", "--pretty=format:", "--", rel_path])
+        .output();
+
+    if let Ok(out) = output {
+        if out.status.success() {
+            for line in String::from_utf8_lossy(&out.stdout).lines() {
+                let name = line.trim();
+                if !name.is_empty() && !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+
+    names
+}
+
+fn clean_relative(path: &Path) -> String {
+    let parts: Vec<String> = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
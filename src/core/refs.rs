@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// Jira-style keys: `PROJ-123`
+pub const DEFAULT_PATTERNS: &[&str] = &[r"\b([A-Z][A-Z0-9]{1,9}-[0-9]+)\b"];
+
+/// Prefixes of things that are shaped like ticket keys but are standards or hashes
+pub const DEFAULT_IGNORE: &[&str] = &["UTF", "SHA", "ISO", "RFC", "AES", "TLS", "PEP", "CVE"];
+
+/// Finds ticket keys in session text with the configured patterns
+#[derive(Debug, Clone)]
+pub struct RefMatcher {
+    patterns: Vec<Regex>,
+    ignore: Vec<String>,
+}
+
+impl Default for RefMatcher {
+    fn default() -> Self {
+        Self {
+            patterns: DEFAULT_PATTERNS.iter().map(|p| Regex::new(p).unwrap()).collect(),
+            ignore: DEFAULT_IGNORE.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl RefMatcher {
+    pub fn new(patterns: &[String], ignore: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid ticket pattern '{}'", p)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            patterns,
+            ignore: ignore.to_vec(),
+        })
+    }
+
+    /// Keys in `text`, in order of first appearance
+    pub fn find(&self, text: &str) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for re in &self.patterns {
+            for caps in re.captures_iter(text) {
+                let Some(m) = caps.get(1).or_else(|| caps.get(0)) else {
+                    continue;
+                };
+                let key = m.as_str();
+                if key.is_empty() || self.is_ignored(key) || keys.iter().any(|k| k == key) {
+                    continue;
+                }
+                keys.push(key.to_string());
+            }
+        }
+        keys
+    }
+
+    fn is_ignored(&self, key: &str) -> bool {
+        let prefix = key.split(['-', '_', '#']).next().unwrap_or(key);
+        self.ignore.iter().any(|i| i.eq_ignore_ascii_case(prefix))
+    }
+
+    /// Keys referenced by a session, each with where it was first seen:
+    /// the git branch, a user prompt, or a commit message the agent wrote.
+    pub fn session_refs<'a>(
+        &self,
+        branch: Option<&str>,
+        messages: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Vec<(String, &'static str)> {
+        let mut refs: Vec<(String, &'static str)> = Vec::new();
+        let mut add = |keys: Vec<String>, source: &'static str| {
+            for key in keys {
+                if !refs.iter().any(|(k, _)| *k == key) {
+                    refs.push((key, source));
+                }
+            }
+        };
+        if let Some(branch) = branch {
+            add(self.find(branch), "branch");
+        }
+        for (role, content) in messages {
+            match role {
+                "user" => add(self.find(content), "prompt"),
+                "assistant" => {
+                    for line in content.lines().filter(|l| l.contains("git commit")) {
+                        add(self.find(line), "commit");
+                    }
+                }
+                _ => {}
+            }
+        }
+        refs
+    }
+}
@@ -0,0 +1,175 @@
+use crate::core::db::{Database, SessionRow};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Weights for each similarity signal
+const SHARED_FILE_WEIGHT: f64 = 3.0;
+const SHARED_TERM_WEIGHT: f64 = 1.0;
+const SAME_PROJECT_WEIGHT: f64 = 1.5;
+
+/// How many candidates to pull from each signal before scoring
+const CANDIDATE_LIMIT: usize = 50;
+
+const STOPWORDS: &[&str] = &[
+    "this", "that", "with", "from", "have", "what", "when", "where", "which", "will",
+    "would", "could", "should", "there", "their", "about", "into", "then", "than",
+    "them", "they", "your", "make", "like", "just", "also", "some", "does", "please",
+    "need", "want", "using", "file", "files", "code",
+];
+
+#[derive(Debug, Clone)]
+pub struct RelatedSession {
+    pub session: SessionRow,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+impl RelatedSession {
+    pub fn reason(&self) -> String {
+        self.reasons.join("; ")
+    }
+}
+
+#[derive(Default)]
+struct Candidate {
+    shared_files: Vec<String>,
+    shared_terms: Vec<String>,
+    same_project: bool,
+}
+
+/// Rank other sessions by similarity to `session_id`.
+///
+/// Signals: files touched by both sessions (from tool calls), shared project,
+/// and overlapping significant terms in the first user prompt.
+pub fn find_related(db: &Database, session_id: &str, limit: usize) -> Result<Vec<RelatedSession>> {
+    let session = db
+        .get_session(session_id)?
+        .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+    let mut candidates: HashMap<String, Candidate> = HashMap::new();
+
+    // Signal 1: shared files
+    for (other_id, file_path) in db.sessions_sharing_files(session_id)? {
+        let c = candidates.entry(other_id).or_default();
+        if !c.shared_files.contains(&file_path) {
+            c.shared_files.push(file_path);
+        }
+    }
+
+    // Signal 2: term overlap with the first user prompt; message text is
+    // unreadable while the index is locked
+    let own_text = if db.is_locked() { None } else { db.first_user_message(session_id)? };
+    let own_terms = match own_text {
+        Some(text) => significant_terms(&text),
+        None => Vec::new(),
+    };
+    if !own_terms.is_empty() {
+        let fts_query = own_terms
+            .iter()
+            .map(|t| format!("\"{}\"", t))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        for other_id in db.sessions_matching_user_text(&fts_query, session_id, CANDIDATE_LIMIT)? {
+            candidates.entry(other_id).or_default();
+        }
+    }
+
+    // Signal 3: same project
+    if let Some(ref project_path) = session.project_path {
+        for other in db.list_sessions(None, Some(project_path), None, None, CANDIDATE_LIMIT)? {
+            if other.id != session.id {
+                candidates.entry(other.id).or_default().same_project = true;
+            }
+        }
+    }
+
+    let own_term_set: HashSet<&str> = own_terms.iter().map(|t| t.as_str()).collect();
+    let mut related = Vec::new();
+
+    for (other_id, mut c) in candidates {
+        let Some(other) = db.get_session(&other_id)? else {
+            continue;
+        };
+        if !c.same_project {
+            c.same_project = session.project_path.is_some() && other.project_path == session.project_path;
+        }
+        if !own_terms.is_empty() {
+            if let Some(text) = db.first_user_message(&other_id)? {
+                c.shared_terms = significant_terms(&text)
+                    .into_iter()
+                    .filter(|t| own_term_set.contains(t.as_str()))
+                    .collect();
+            }
+        }
+
+        let score = c.shared_files.len() as f64 * SHARED_FILE_WEIGHT
+            + c.shared_terms.len() as f64 * SHARED_TERM_WEIGHT
+            + if c.same_project { SAME_PROJECT_WEIGHT } else { 0.0 };
+        if score <= 0.0 {
+            continue;
+        }
+
+        related.push(RelatedSession {
+            session: other,
+            score,
+            reasons: build_reasons(&c),
+        });
+    }
+
+    related.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.session.started_at.cmp(&a.session.started_at))
+    });
+    related.truncate(limit);
+    Ok(related)
+}
+
+fn build_reasons(c: &Candidate) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if !c.shared_files.is_empty() {
+        let mut files: Vec<String> = c.shared_files.iter().take(2).map(|f| short_path(f)).collect();
+        if c.shared_files.len() > 2 {
+            files.push("…".to_string());
+        }
+        let noun = if c.shared_files.len() == 1 { "file" } else { "files" };
+        reasons.push(format!(
+            "{} shared {}: {}",
+            c.shared_files.len(),
+            noun,
+            files.join(", ")
+        ));
+    }
+    if !c.shared_terms.is_empty() {
+        let terms: Vec<&str> = c.shared_terms.iter().take(4).map(|t| t.as_str()).collect();
+        reasons.push(format!("similar request: {}", terms.join(", ")));
+    }
+    if c.same_project {
+        reasons.push("same project".to_string());
+    }
+    reasons
+}
+
+/// Lowercased, de-duplicated words worth matching on (no stopwords, no short words)
+fn significant_terms(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(|w| w.to_lowercase())
+        // Hangul/CJK words carry more meaning per character than ASCII ones
+        .filter(|w| w.chars().count() >= if w.is_ascii() { 4 } else { 2 })
+        .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .filter(|w| seen.insert(w.clone()))
+        .take(12)
+        .collect()
+}
+
+fn short_path(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    if parts.len() <= 3 {
+        path.to_string()
+    } else {
+        parts[parts.len() - 3..].join("/")
+    }
+}
@@ -0,0 +1,114 @@
+use super::standup::Standup;
+use super::{ReportData, ReportRenderer};
+use crate::core::activity;
+use anyhow::Result;
+
+/// One row per session, for spreadsheets and timesheets
+pub struct CsvRenderer;
+
+const HEADER: &[&str] = &[
+    "id",
+    "agent",
+    "project",
+    "author",
+    "started_at",
+    "ended_at",
+    "active_minutes",
+    "active_hours",
+    "messages",
+    "files_created",
+    "files_modified",
+    "files_deleted",
+    "files",
+    "summary",
+    "work_summary",
+    "llm_summary",
+    "tags",
+    "branch",
+    "tickets",
+    "outcome",
+    "outcome_reason",
+];
+
+impl ReportRenderer for CsvRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let mut out = String::new();
+        push_row(&mut out, HEADER.iter().map(|h| h.to_string()));
+        for s in &report.sessions {
+            let active = s.active_secs.unwrap_or(0);
+            let files: Vec<String> = report
+                .file_changes(&s.id)
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect();
+            push_row(
+                &mut out,
+                [
+                    s.id.clone(),
+                    s.agent.clone(),
+                    s.project_name.clone().unwrap_or_default(),
+                    s.author.clone().unwrap_or_default(),
+                    s.started_at.clone().unwrap_or_default(),
+                    s.ended_at.clone().unwrap_or_default(),
+                    (active / 60).to_string(),
+                    format!("{:.1}", activity::hours(active)),
+                    s.message_count.to_string(),
+                    s.files_created.to_string(),
+                    s.files_modified.to_string(),
+                    s.files_deleted.to_string(),
+                    files.join(";"),
+                    s.summary.clone().unwrap_or_default(),
+                    s.work_summary.clone().unwrap_or_default(),
+                    s.llm_summary.clone().unwrap_or_default(),
+                    s.tags.clone(),
+                    s.git_branch.clone().unwrap_or_default(),
+                    report.db.session_refs(&s.id).unwrap_or_default().join(";"),
+                    s.outcome.clone().unwrap_or_default(),
+                    s.outcome_reason.clone().unwrap_or_default(),
+                ]
+                .into_iter(),
+            );
+        }
+        Ok(out)
+    }
+
+    /// One row per item
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let mut out = String::new();
+        push_row(
+            &mut out,
+            ["section", "project", "text", "note", "session_id"].iter().map(|h| h.to_string()),
+        );
+        for (title, items) in standup.sections() {
+            for item in items {
+                push_row(
+                    &mut out,
+                    [
+                        title.clone(),
+                        item.project.clone().unwrap_or_default(),
+                        item.text.clone(),
+                        item.note.clone().unwrap_or_default(),
+                        item.session_id.clone(),
+                    ]
+                    .into_iter(),
+                );
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn push_row(out: &mut String, fields: impl Iterator<Item = String>) {
+    let fields: Vec<String> = fields.map(|f| quote(&f)).collect();
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+}
+
+/// RFC 4180 quoting: wrap fields holding separators, quotes or line breaks
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
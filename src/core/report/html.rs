@@ -0,0 +1,203 @@
+use super::standup::Standup;
+use super::{agent_display, outcome_note, request_text, short_path, ReportData, ReportRenderer, OUTCOME_COLUMNS};
+use crate::core::activity;
+use crate::core::html::escape;
+use anyhow::Result;
+use std::fmt::Write;
+
+const STYLE: &str = include_str!("../assets/report.css");
+
+/// A standalone page with inline styles, for mail or a wiki's HTML macro
+pub struct HtmlRenderer;
+
+impl ReportRenderer for HtmlRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let stats = &report.stats;
+        let title = format!("AI Work Report ({})", report.label());
+        let mut out = String::new();
+
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">")?;
+        writeln!(out, "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>", escape(&title), STYLE)?;
+        writeln!(out, "<h1>{}</h1>", escape(&title))?;
+
+        writeln!(out, "<div class=\"cards\">")?;
+        let files = format!(
+            "{} / {} / {}",
+            stats.total_files_created, stats.total_files_modified, stats.total_files_deleted
+        );
+        for (value, label) in [
+            (stats.total_sessions.to_string(), "sessions"),
+            (stats.sessions_by_project.len().to_string(), "projects"),
+            (activity::format_active(stats.total_active_secs), "active time"),
+            (files, "files created / modified / deleted"),
+        ] {
+            writeln!(
+                out,
+                "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>",
+                escape(&value),
+                label
+            )?;
+        }
+        writeln!(out, "</div>")?;
+
+        let agents: Vec<(String, String)> = stats
+            .sessions_by_agent
+            .iter()
+            .map(|(agent, count)| (agent_display(agent).to_string(), count.to_string()))
+            .collect();
+        let authors: Vec<(String, String)> = stats
+            .sessions_by_author
+            .iter()
+            .map(|(author, count)| (author.clone(), count.to_string()))
+            .collect();
+        writeln!(out, "<div class=\"tables\">")?;
+        write_table(&mut out, &["Agent", "Sessions"], agents.iter().map(|(a, c)| vec![a.clone(), c.clone()]))?;
+        // Only worth a table in a team index
+        if authors.len() > 1 {
+            write_table(&mut out, &["Author", "Sessions"], authors.iter().map(|(a, c)| vec![a.clone(), c.clone()]))?;
+        }
+        writeln!(out, "</div>")?;
+
+        if let Some(ref c) = report.comparison {
+            writeln!(out, "<h2>Compared to {}</h2>", escape(&c.previous.label))?;
+            writeln!(out, "<div class=\"tables\">")?;
+            let head = ["Metric", c.previous.label.as_str(), c.current.label.as_str(), "Change"];
+            write_table(&mut out, &head, c.metric_rows().into_iter().map(Vec::from))?;
+            let shares = c.share_rows();
+            if !shares.is_empty() {
+                write_table(
+                    &mut out,
+                    &["Project", "Share before", "Share now", "Change"],
+                    shares.into_iter().map(Vec::from),
+                )?;
+            }
+            writeln!(out, "</div>")?;
+        }
+
+        if stats.total_active_secs > 0 {
+            writeln!(out, "<h2>Active Time</h2>\n<div class=\"tables\">")?;
+            for (label, rows) in report.active_tables() {
+                write_table(
+                    &mut out,
+                    &[label, "Active", "Hours"],
+                    rows.into_iter().map(|(name, secs)| {
+                        vec![
+                            name,
+                            activity::format_active(secs),
+                            format!("{:.1}", activity::hours(secs)),
+                        ]
+                    }),
+                )?;
+            }
+            writeln!(out, "</div>")?;
+        }
+
+        let outcome_tables = report.outcome_tables();
+        if !outcome_tables.is_empty() {
+            writeln!(out, "<h2>Outcomes</h2>\n<div class=\"tables\">")?;
+            for (label, rows) in outcome_tables {
+                let mut head = vec![label];
+                head.extend(OUTCOME_COLUMNS);
+                write_table(&mut out, &head, rows.iter().map(|r| Vec::from(r.row())))?;
+            }
+            writeln!(out, "</div>")?;
+        }
+
+        writeln!(out, "<h2>Activity by {}</h2>", report.group_by.title())?;
+        for (group, group_sessions) in report.groups() {
+            let active: i64 = group_sessions.iter().filter_map(|s| s.active_secs).sum();
+            writeln!(
+                out,
+                "<h3>{} <span class=\"work\">({} sessions, {} active)</span></h3>",
+                escape(&group),
+                group_sessions.len(),
+                activity::format_active(active)
+            )?;
+            writeln!(out, "<ol class=\"sessions\">")?;
+            for session in &group_sessions {
+                writeln!(out, "<li>{}", escape(request_text(session)))?;
+                if let Some(ref work) = session.work_summary {
+                    writeln!(out, "<div class=\"work\">Work: {}</div>", escape(work))?;
+                }
+                let files: Vec<String> = report
+                    .file_changes(&session.id)
+                    .iter()
+                    .map(|(path, prefix)| format!("<code>{}{}</code>", prefix, escape(&short_path(path))))
+                    .collect();
+                if !files.is_empty() {
+                    writeln!(out, "<div class=\"files\">Files: {}</div>", files.join(" "))?;
+                }
+                if let Some(note) = outcome_note(session) {
+                    writeln!(out, "<div class=\"work\">Outcome: {}</div>", escape(&note))?;
+                }
+                writeln!(out, "</li>")?;
+            }
+            writeln!(out, "</ol>")?;
+            let created: i64 = group_sessions.iter().map(|s| s.files_created).sum();
+            let modified: i64 = group_sessions.iter().map(|s| s.files_modified).sum();
+            writeln!(
+                out,
+                "<p class=\"totals\">Total: {} created, {} modified</p>",
+                created, modified
+            )?;
+        }
+
+        writeln!(out, "</body>\n</html>")?;
+        Ok(out)
+    }
+
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let title = format!("Standup ({})", standup.today.format("%Y-%m-%d"));
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>", escape(&title), STYLE)?;
+        writeln!(out, "<h1>{}</h1>", escape(&title))?;
+        for (section, items) in standup.sections() {
+            writeln!(out, "<h2>{}</h2>\n<ul class=\"sessions\">", escape(&section))?;
+            if items.is_empty() {
+                writeln!(out, "<li class=\"work\">None</li>")?;
+            }
+            for item in items {
+                writeln!(out, "<li>{}</li>", escape(&item.line()))?;
+            }
+            writeln!(out, "</ul>")?;
+        }
+        writeln!(out, "</body>\n</html>")?;
+        Ok(out)
+    }
+}
+
+/// First column as text, the rest right-aligned; signed changes are coloured
+fn write_table(
+    out: &mut String,
+    head: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> Result<()> {
+    writeln!(out, "<table>")?;
+    let th: Vec<String> = head.iter().map(|h| format!("<th>{}</th>", escape(h))).collect();
+    writeln!(out, "<tr>{}</tr>", th.join(""))?;
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let class = if i == 0 {
+                    ""
+                } else if cell.starts_with('-') {
+                    " class=\"num down\""
+                } else if cell.starts_with('+') && !cell.starts_with("+0") {
+                    " class=\"num up\""
+                } else {
+                    " class=\"num\""
+                };
+                format!("<td{}>{}</td>", class, escape(cell))
+            })
+            .collect();
+        writeln!(out, "<tr>{}</tr>", cells.join(""))?;
+    }
+    writeln!(out, "</table>")?;
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use super::standup::{Standup, StandupItem};
+use super::{period_to_range, project_shares, Comparison, OutcomeCounts, PeriodTotals, ReportData, ReportRenderer};
+use anyhow::Result;
+
+pub struct JsonRenderer;
+
+impl ReportRenderer for JsonRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let stats = &report.stats;
+        let (from, to) = period_to_range(&report.period);
+        let by_day: Vec<(String, i64)> = report
+            .active_by_day
+            .iter()
+            .map(|(day, secs)| (day.format("%Y-%m-%d").to_string(), *secs))
+            .collect();
+
+        let mut json = serde_json::json!({
+            "period": {
+                "label": report.label(),
+                "from": from.to_rfc3339(),
+                "to": to.to_rfc3339(),
+            },
+            "stats": {
+                "total_sessions": stats.total_sessions,
+                "sessions_by_agent": stats.sessions_by_agent,
+                "sessions_by_project": stats.sessions_by_project,
+                "sessions_by_author": stats.sessions_by_author,
+                "active_secs": stats.total_active_secs,
+                "active_secs_by_agent": stats.active_secs_by_agent,
+                "active_secs_by_project": stats.active_secs_by_project,
+                "active_secs_by_day": by_day,
+                "files_created": stats.total_files_created,
+                "files_modified": stats.total_files_modified,
+                "files_deleted": stats.total_files_deleted,
+            },
+            "sessions": report.sessions.iter().map(|s| serde_json::json!({
+                "id": s.id,
+                "agent": s.agent,
+                "project": s.project_name,
+                "author": s.author,
+                "summary": s.summary,
+                "work_summary": s.work_summary,
+                "llm_summary": s.llm_summary,
+                "started_at": s.started_at,
+                "active_secs": s.active_secs,
+                "files_created": s.files_created,
+                "files_modified": s.files_modified,
+                "files_deleted": s.files_deleted,
+                "tags": s.tags,
+                "git_branch": s.git_branch,
+                "tickets": report.db.session_refs(&s.id).unwrap_or_default(),
+                "outcome": s.outcome,
+                "outcome_reason": s.outcome_reason,
+            })).collect::<Vec<_>>(),
+            "group_by": report.group_by.title().to_lowercase(),
+            "groups": report.groups().into_iter().map(|(name, sessions)| serde_json::json!({
+                "name": name,
+                "sessions": sessions.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+                "active_secs": sessions.iter().filter_map(|s| s.active_secs).sum::<i64>(),
+            })).collect::<Vec<_>>(),
+        });
+
+        let outcome_tables = report.outcome_tables();
+        if !outcome_tables.is_empty() {
+            let mut outcomes = outcome_json(&report.outcome_totals());
+            for (label, rows) in outcome_tables {
+                outcomes[format!("by_{}", label.to_lowercase())] = rows.iter().map(outcome_json).collect();
+            }
+            json["outcomes"] = outcomes;
+        }
+
+        if let Some(ref c) = report.comparison {
+            json["compare"] = comparison_json(c);
+        }
+
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let items = |items: &[StandupItem]| -> Vec<serde_json::Value> {
+            items
+                .iter()
+                .map(|i| serde_json::json!({
+                    "session_id": i.session_id,
+                    "project": i.project,
+                    "text": i.text,
+                    "note": i.note,
+                }))
+                .collect()
+        };
+        let json = serde_json::json!({
+            "date": standup.today.format("%Y-%m-%d").to_string(),
+            "previous_day": standup.previous.format("%Y-%m-%d").to_string(),
+            "done": items(&standup.done),
+            "today": items(&standup.open),
+            "blockers": items(&standup.blockers),
+        });
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+}
+
+fn outcome_json(c: &OutcomeCounts) -> serde_json::Value {
+    serde_json::json!({
+        "name": c.name,
+        "success": c.success,
+        "failed": c.failed,
+        "abandoned": c.abandoned,
+        "in_progress": c.in_progress,
+        "success_rate": c.success_rate(),
+    })
+}
+
+fn totals_json(t: &PeriodTotals) -> serde_json::Value {
+    serde_json::json!({
+        "label": t.label,
+        "sessions": t.sessions,
+        "active_secs": t.active_secs,
+        "files_changed": t.files_changed,
+        "tokens": t.tokens,
+        "project_share": t.project_share,
+    })
+}
+
+fn comparison_json(c: &Comparison) -> serde_json::Value {
+    let (cur, prev) = (&c.current, &c.previous);
+    serde_json::json!({
+        "previous": totals_json(prev),
+        "delta": {
+            "sessions": cur.sessions - prev.sessions,
+            "active_secs": cur.active_secs - prev.active_secs,
+            "files_changed": cur.files_changed - prev.files_changed,
+            "tokens": match (cur.tokens, prev.tokens) {
+                (Some(t), Some(p)) => Some(t as i64 - p as i64),
+                _ => None,
+            },
+        },
+        "project_share": project_shares(c)
+            .into_iter()
+            .map(|(name, now, before)| serde_json::json!({
+                "project": name,
+                "share": now,
+                "previous_share": before,
+                "delta_points": now - before,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
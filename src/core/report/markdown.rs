@@ -0,0 +1,183 @@
+use super::standup::Standup;
+use super::{
+    agent_display, outcome_note, request_text, short_path, Comparison, ReportData, ReportRenderer, OUTCOME_COLUMNS,
+};
+use crate::core::activity;
+use anyhow::Result;
+use std::fmt::Write;
+
+pub struct MarkdownRenderer;
+
+impl ReportRenderer for MarkdownRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let stats = &report.stats;
+        let mut out = String::new();
+
+        // Title
+        writeln!(out, "# AI Work Report ({})", report.label())?;
+        writeln!(out)?;
+
+        // Summary stats
+        writeln!(out, "## Summary")?;
+        writeln!(
+            out,
+            "- Total: {} sessions, {} projects",
+            stats.total_sessions,
+            stats.sessions_by_project.len()
+        )?;
+        for (agent, count) in &stats.sessions_by_agent {
+            writeln!(out, "- {}: {} sessions", agent_display(agent), count)?;
+        }
+        // Only worth a line in a team index
+        if stats.sessions_by_author.len() > 1 {
+            let authors: Vec<String> = stats
+                .sessions_by_author
+                .iter()
+                .map(|(author, count)| format!("{} ({})", author, count))
+                .collect();
+            writeln!(out, "- Authors: {}", authors.join(", "))?;
+        }
+        writeln!(
+            out,
+            "- Active time: {}",
+            activity::format_active(stats.total_active_secs)
+        )?;
+        writeln!(
+            out,
+            "- Files: {} created, {} modified, {} deleted",
+            stats.total_files_created, stats.total_files_modified, stats.total_files_deleted
+        )?;
+        let outcomes = report.outcome_totals();
+        if let Some(rate) = outcomes.success_rate() {
+            writeln!(out, "- Success rate: {:.0}% of {} finished sessions", rate, outcomes.finished())?;
+        }
+        writeln!(out)?;
+
+        if let Some(ref c) = report.comparison {
+            write_comparison(&mut out, c)?;
+        }
+
+        if stats.total_active_secs > 0 {
+            writeln!(out, "## Active Time")?;
+            writeln!(out)?;
+            for (label, rows) in report.active_tables() {
+                writeln!(out, "| {} | Active | Hours |", label)?;
+                writeln!(out, "|---|---|---|")?;
+                for (name, secs) in rows {
+                    writeln!(
+                        out,
+                        "| {} | {} | {:.1} |",
+                        name,
+                        activity::format_active(secs),
+                        activity::hours(secs)
+                    )?;
+                }
+                writeln!(out)?;
+            }
+        }
+
+        let outcome_tables = report.outcome_tables();
+        if !outcome_tables.is_empty() {
+            writeln!(out, "## Outcomes")?;
+            writeln!(out)?;
+            for (label, rows) in outcome_tables {
+                writeln!(out, "| {} | {} |", label, OUTCOME_COLUMNS.join(" | "))?;
+                writeln!(out, "|---|---|---|---|---|---|")?;
+                for row in rows {
+                    writeln!(out, "| {} |", row.row().join(" | "))?;
+                }
+                writeln!(out)?;
+            }
+        }
+
+        writeln!(out, "## Activity by {}", report.group_by.title())?;
+        writeln!(out)?;
+
+        for (group, group_sessions) in report.groups() {
+            let active: i64 = group_sessions.iter().filter_map(|s| s.active_secs).sum();
+            writeln!(
+                out,
+                "### {} ({} sessions, {} active)",
+                group,
+                group_sessions.len(),
+                activity::format_active(active)
+            )?;
+            writeln!(out)?;
+
+            for (idx, session) in group_sessions.iter().enumerate() {
+                let request = request_text(session);
+                let work = session.work_summary.as_deref().unwrap_or("-");
+
+                let files_str = report
+                    .file_changes(&session.id)
+                    .iter()
+                    .map(|(path, prefix)| format!("{}{}", prefix, short_path(path)))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                writeln!(out, "{}. {}", idx + 1, request)?;
+                // Only show Work line if llm_summary was used (so request != work_summary)
+                if session.llm_summary.is_some() || work != "-" {
+                    writeln!(out, "   Work: {}", work)?;
+                }
+                if !files_str.is_empty() {
+                    writeln!(out, "   Files: {}", files_str)?;
+                }
+                if let Some(note) = outcome_note(session) {
+                    writeln!(out, "   Outcome: {}", note)?;
+                }
+                writeln!(out)?;
+            }
+
+            // Group file totals
+            let created: i64 = group_sessions.iter().map(|s| s.files_created).sum();
+            let modified: i64 = group_sessions.iter().map(|s| s.files_modified).sum();
+            writeln!(
+                out,
+                "Total: {} created, {} modified",
+                created, modified
+            )?;
+            writeln!(out)?;
+        }
+
+        Ok(out)
+    }
+
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# Standup ({})", standup.today.format("%Y-%m-%d"))?;
+        for (title, items) in standup.sections() {
+            writeln!(out)?;
+            writeln!(out, "## {}", title)?;
+            if items.is_empty() {
+                writeln!(out, "- None")?;
+            }
+            for item in items {
+                writeln!(out, "- {}", item.line())?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn write_comparison(out: &mut String, c: &Comparison) -> Result<()> {
+    writeln!(out, "## Compared to {}", c.previous.label)?;
+    writeln!(out)?;
+    writeln!(out, "| Metric | {} | {} | Change |", c.previous.label, c.current.label)?;
+    writeln!(out, "|---|---|---|---|")?;
+    for row in c.metric_rows() {
+        writeln!(out, "| {} |", row.join(" | "))?;
+    }
+    writeln!(out)?;
+
+    let shares = c.share_rows();
+    if !shares.is_empty() {
+        writeln!(out, "| Project | Share before | Share now | Change |")?;
+        writeln!(out, "|---|---|---|---|")?;
+        for row in shares {
+            writeln!(out, "| {} |", row.join(" | "))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,702 @@
+pub mod traits;
+pub mod markdown;
+pub mod slack;
+pub mod json;
+pub mod html;
+pub mod csv;
+pub mod wiki;
+pub mod standup;
+
+pub use traits::ReportRenderer;
+
+use crate::core::activity;
+use crate::core::budget;
+use crate::core::clock;
+use crate::core::db::{Database, SessionRow, Stats};
+use crate::core::outcome::Outcome;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub enum ReportPeriod {
+    Day(NaiveDate),
+    Week(NaiveDate, NaiveDate),
+    Month(i32, u32),
+    Quarter(i32, u8),
+    Custom(DateTime<Utc>, DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReportFormat {
+    Markdown,
+    Slack,
+    Json,
+    Html,
+    Csv,
+    Wiki,
+}
+
+impl ReportFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_lowercase().as_str() {
+            "markdown" | "md" => ReportFormat::Markdown,
+            "slack" => ReportFormat::Slack,
+            "json" => ReportFormat::Json,
+            "html" => ReportFormat::Html,
+            "csv" => ReportFormat::Csv,
+            "wiki" | "confluence" | "jira" => ReportFormat::Wiki,
+            _ => bail!("Unknown format '{}' (expected: markdown, slack, json, html, csv, wiki)", s),
+        })
+    }
+
+    pub fn renderer(self) -> Box<dyn ReportRenderer> {
+        match self {
+            ReportFormat::Markdown => Box::new(markdown::MarkdownRenderer),
+            ReportFormat::Slack => Box::new(slack::SlackRenderer),
+            ReportFormat::Json => Box::new(json::JsonRenderer),
+            ReportFormat::Html => Box::new(html::HtmlRenderer),
+            ReportFormat::Csv => Box::new(csv::CsvRenderer),
+            ReportFormat::Wiki => Box::new(wiki::WikiRenderer),
+        }
+    }
+}
+
+/// How the session list is sectioned (`--group-by`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Project,
+    Agent,
+    Branch,
+    Ticket,
+    Tag,
+}
+
+impl GroupBy {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "project" => Some(GroupBy::Project),
+            "agent" => Some(GroupBy::Agent),
+            "branch" => Some(GroupBy::Branch),
+            "ticket" | "issue" => Some(GroupBy::Ticket),
+            "tag" => Some(GroupBy::Tag),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            GroupBy::Project => "Project",
+            GroupBy::Agent => "Agent",
+            GroupBy::Branch => "Branch",
+            GroupBy::Ticket => "Ticket",
+            GroupBy::Tag => "Tag",
+        }
+    }
+}
+
+/// Headline numbers for one period, used to compare it with another
+#[derive(Debug, Clone)]
+pub struct PeriodTotals {
+    pub label: String,
+    pub sessions: i64,
+    pub active_secs: i64,
+    pub files_changed: i64,
+    /// Estimated message tokens; `None` while the index is locked
+    pub tokens: Option<usize>,
+    /// Each project's share of the period's sessions, in percent
+    pub project_share: Vec<(String, f64)>,
+}
+
+/// Session outcomes for one agent or project
+#[derive(Debug, Clone, Default)]
+pub struct OutcomeCounts {
+    pub name: String,
+    pub success: i64,
+    pub failed: i64,
+    pub abandoned: i64,
+    pub in_progress: i64,
+}
+
+/// Column headings after the name column of an outcome table
+pub const OUTCOME_COLUMNS: [&str; 5] = ["Success", "Failed", "Abandoned", "In progress", "Success rate"];
+
+impl OutcomeCounts {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Success => self.success += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Abandoned => self.abandoned += 1,
+            Outcome::InProgress => self.in_progress += 1,
+        }
+    }
+
+    pub fn finished(&self) -> i64 {
+        self.success + self.failed + self.abandoned
+    }
+
+    /// Percent of finished sessions that succeeded; `None` before any finished
+    pub fn success_rate(&self) -> Option<f64> {
+        let finished = self.finished();
+        (finished > 0).then(|| self.success as f64 * 100.0 / finished as f64)
+    }
+
+    /// The name followed by one cell per `OUTCOME_COLUMNS`
+    pub fn row(&self) -> [String; 6] {
+        [
+            self.name.clone(),
+            self.success.to_string(),
+            self.failed.to_string(),
+            self.abandoned.to_string(),
+            self.in_progress.to_string(),
+            self.success_rate().map_or("-".to_string(), |r| format!("{:.0}%", r)),
+        ]
+    }
+}
+
+/// The current period next to the one before it (`--compare previous`)
+pub struct Comparison {
+    pub current: PeriodTotals,
+    pub previous: PeriodTotals,
+}
+
+/// Everything a renderer needs for one report
+pub struct ReportData<'a> {
+    pub db: &'a Database,
+    pub period: ReportPeriod,
+    pub sessions: Vec<SessionRow>,
+    pub stats: Stats,
+    /// Present with `--compare previous`
+    pub comparison: Option<Comparison>,
+    /// Active time per day of the period
+    pub active_by_day: Vec<(NaiveDate, i64)>,
+    pub group_by: GroupBy,
+}
+
+impl ReportData<'_> {
+    pub fn label(&self) -> String {
+        period_label(&self.period)
+    }
+
+    /// Sessions grouped by `group_by`, groups in order of first appearance.
+    /// A session with several tickets or tags is listed under each; those
+    /// with none come last, under "(no ticket)" and the like.
+    pub fn groups(&self) -> Vec<(String, Vec<&SessionRow>)> {
+        let mut groups: Vec<(String, Vec<&SessionRow>)> = Vec::new();
+        let mut ungrouped: Vec<&SessionRow> = Vec::new();
+        for session in &self.sessions {
+            let keys = self.group_keys(session);
+            if keys.is_empty() {
+                ungrouped.push(session);
+            }
+            for key in keys {
+                match groups.iter_mut().find(|(g, _)| *g == key) {
+                    Some((_, list)) => list.push(session),
+                    None => groups.push((key, vec![session])),
+                }
+            }
+        }
+        if !ungrouped.is_empty() {
+            groups.push((format!("(no {})", self.group_by.title().to_lowercase()), ungrouped));
+        }
+        groups
+    }
+
+    fn group_keys(&self, session: &SessionRow) -> Vec<String> {
+        match self.group_by {
+            GroupBy::Project => vec![session.project_name.clone().unwrap_or_else(|| "unknown".to_string())],
+            GroupBy::Agent => vec![agent_display(&session.agent).to_string()],
+            GroupBy::Branch => session.git_branch.iter().cloned().collect(),
+            GroupBy::Ticket => self.db.session_refs(&session.id).unwrap_or_default(),
+            GroupBy::Tag => session
+                .tags
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    /// Outcome counts over all sessions, named "Total"
+    pub fn outcome_totals(&self) -> OutcomeCounts {
+        let mut totals = OutcomeCounts {
+            name: "Total".to_string(),
+            ..Default::default()
+        };
+        for outcome in self.sessions.iter().filter_map(session_outcome) {
+            totals.add(outcome);
+        }
+        totals
+    }
+
+    /// Outcome counts by agent and by project; empty when no session has one
+    pub fn outcome_tables(&self) -> Vec<(&'static str, Vec<OutcomeCounts>)> {
+        if self.sessions.iter().all(|s| session_outcome(s).is_none()) {
+            return Vec::new();
+        }
+        let count = |key: fn(&SessionRow) -> String| -> Vec<OutcomeCounts> {
+            let mut rows: Vec<OutcomeCounts> = Vec::new();
+            for session in &self.sessions {
+                let Some(outcome) = session_outcome(session) else {
+                    continue;
+                };
+                let name = key(session);
+                match rows.iter_mut().find(|r| r.name == name) {
+                    Some(row) => row.add(outcome),
+                    None => {
+                        let mut row = OutcomeCounts {
+                            name,
+                            ..Default::default()
+                        };
+                        row.add(outcome);
+                        rows.push(row);
+                    }
+                }
+            }
+            rows
+        };
+        vec![
+            ("Agent", count(|s| agent_display(&s.agent).to_string())),
+            ("Project", count(|s| s.project_name.clone().unwrap_or_else(|| "unknown".to_string()))),
+        ]
+    }
+
+    /// Files a session touched, each with a +/~/- change marker
+    pub fn file_changes(&self, session_id: &str) -> Vec<(String, &'static str)> {
+        get_session_file_changes(self.db, session_id)
+    }
+
+    /// Active time by project, agent and day, leaving out rows with no time
+    pub fn active_tables(&self) -> Vec<(&'static str, Vec<(String, i64)>)> {
+        let keep = |rows: Vec<(String, i64)>| -> Vec<(String, i64)> {
+            rows.into_iter().filter(|(_, secs)| *secs > 0).collect()
+        };
+        vec![
+            ("Project", keep(self.stats.active_secs_by_project.clone())),
+            (
+                "Agent",
+                keep(
+                    self.stats
+                        .active_secs_by_agent
+                        .iter()
+                        .map(|(agent, secs)| (agent_display(agent).to_string(), *secs))
+                        .collect(),
+                ),
+            ),
+            (
+                "Day",
+                keep(
+                    self.active_by_day
+                        .iter()
+                        .map(|(day, secs)| (day.format("%Y-%m-%d %a").to_string(), *secs))
+                        .collect(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl Comparison {
+    /// (metric, previous, current, change) rows
+    pub fn metric_rows(&self) -> Vec<[String; 4]> {
+        let (cur, prev) = (&self.current, &self.previous);
+        let mut rows = vec![
+            [
+                "Sessions".to_string(),
+                prev.sessions.to_string(),
+                cur.sessions.to_string(),
+                delta_count(cur.sessions, prev.sessions),
+            ],
+            [
+                "Active time".to_string(),
+                activity::format_active(prev.active_secs),
+                activity::format_active(cur.active_secs),
+                delta_active(cur.active_secs, prev.active_secs),
+            ],
+            [
+                "Files changed".to_string(),
+                prev.files_changed.to_string(),
+                cur.files_changed.to_string(),
+                delta_count(cur.files_changed, prev.files_changed),
+            ],
+        ];
+        if let (Some(t), Some(p)) = (cur.tokens, prev.tokens) {
+            rows.push([
+                "Tokens (est.)".to_string(),
+                format_tokens(p),
+                format_tokens(t),
+                delta_tokens(t, p),
+            ]);
+        }
+        rows
+    }
+
+    /// (project, share before, share now, change) rows
+    pub fn share_rows(&self) -> Vec<[String; 4]> {
+        project_shares(self)
+            .into_iter()
+            .map(|(name, now, before)| {
+                [
+                    name,
+                    format!("{:.0}%", before),
+                    format!("{:.0}%", now),
+                    format!("{:+.0} pts", now - before),
+                ]
+            })
+            .collect()
+    }
+}
+
+fn session_outcome(session: &SessionRow) -> Option<Outcome> {
+    session.outcome.as_deref().and_then(Outcome::from_str)
+}
+
+/// `failed (last 3 tool calls failed)` for sessions that didn't succeed
+pub fn outcome_note(session: &SessionRow) -> Option<String> {
+    let outcome = session.outcome.as_deref()?;
+    if outcome == Outcome::Success.as_str() {
+        return None;
+    }
+    Some(match session.outcome_reason.as_deref() {
+        Some(reason) if !reason.is_empty() => format!("{} ({})", outcome, reason),
+        _ => outcome.to_string(),
+    })
+}
+
+/// What the session asked for: the LLM summary when there is one
+pub fn request_text(session: &SessionRow) -> &str {
+    session
+        .llm_summary
+        .as_deref()
+        .or(session.summary.as_deref())
+        .unwrap_or("-")
+}
+
+pub fn generate_report(
+    db: &Database,
+    period: &ReportPeriod,
+    project: Option<&str>,
+    author: Option<&str>,
+    format: ReportFormat,
+    compare: bool,
+    group_by: GroupBy,
+) -> Result<String> {
+    let (from, to) = period_to_range(period);
+    let sessions = db.list_all_sessions(None, project, author, Some(from), Some(to))?;
+    let stats = db.get_stats(Some(from), Some(to), None, project, author)?;
+    let comparison = if compare {
+        Some(Comparison {
+            current: period_totals(db, period, project, author)?,
+            previous: period_totals(db, &previous_period(period), project, author)?,
+        })
+    } else {
+        None
+    };
+    let active_by_day = active_by_day(db, &sessions, period);
+
+    let data = ReportData {
+        db,
+        period: period.clone(),
+        sessions,
+        stats,
+        comparison,
+        active_by_day,
+        group_by,
+    };
+    format.renderer().render(&data)
+}
+
+/// The period of the same kind immediately before `period`
+pub fn previous_period(period: &ReportPeriod) -> ReportPeriod {
+    match period {
+        ReportPeriod::Day(date) => ReportPeriod::Day(*date - Duration::days(1)),
+        ReportPeriod::Week(start, end) => {
+            ReportPeriod::Week(*start - Duration::days(7), *end - Duration::days(7))
+        }
+        ReportPeriod::Month(year, month) => {
+            if *month == 1 {
+                ReportPeriod::Month(year - 1, 12)
+            } else {
+                ReportPeriod::Month(*year, month - 1)
+            }
+        }
+        ReportPeriod::Quarter(year, quarter) => {
+            if *quarter <= 1 {
+                ReportPeriod::Quarter(year - 1, 4)
+            } else {
+                ReportPeriod::Quarter(*year, quarter - 1)
+            }
+        }
+        ReportPeriod::Custom(from, to) => {
+            let len = *to - *from;
+            ReportPeriod::Custom(*from - len, *from)
+        }
+    }
+}
+
+pub fn period_totals(
+    db: &Database,
+    period: &ReportPeriod,
+    project: Option<&str>,
+    author: Option<&str>,
+) -> Result<PeriodTotals> {
+    let (from, to) = period_to_range(period);
+    let stats = db.get_stats(Some(from), Some(to), None, project, author)?;
+    let tokens = if db.is_locked() {
+        None
+    } else {
+        let sessions = db.list_all_sessions(None, project, author, Some(from), Some(to))?;
+        let mut tokens = 0;
+        for session in &sessions {
+            for msg in db.get_messages(&session.id)? {
+                tokens += budget::estimate_tokens(&msg.content);
+            }
+        }
+        Some(tokens)
+    };
+    let project_share = stats
+        .sessions_by_project
+        .iter()
+        .map(|(name, count)| (name.clone(), share(*count, stats.total_sessions)))
+        .collect();
+    Ok(PeriodTotals {
+        label: period_label(period),
+        sessions: stats.total_sessions,
+        active_secs: stats.total_active_secs,
+        files_changed: stats.total_files_created + stats.total_files_modified + stats.total_files_deleted,
+        tokens,
+        project_share,
+    })
+}
+
+fn share(count: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+/// The period's first and last instants, with days cut by the configured calendar
+pub fn period_to_range(period: &ReportPeriod) -> (DateTime<Utc>, DateTime<Utc>) {
+    let calendar = clock::calendar();
+    match period {
+        ReportPeriod::Day(date) => calendar.days(*date, *date),
+        ReportPeriod::Week(start, end) => calendar.days(*start, *end),
+        ReportPeriod::Month(year, month) => {
+            let start = NaiveDate::from_ymd_opt(*year, *month, 1).unwrap();
+            let end = if *month == 12 {
+                NaiveDate::from_ymd_opt(*year + 1, 1, 1).unwrap() - Duration::days(1)
+            } else {
+                NaiveDate::from_ymd_opt(*year, *month + 1, 1).unwrap() - Duration::days(1)
+            };
+            calendar.days(start, end)
+        }
+        ReportPeriod::Quarter(year, quarter) => {
+            let start_month = (quarter - 1) * 3 + 1;
+            let end_month = start_month + 2;
+            let start = NaiveDate::from_ymd_opt(*year, start_month as u32, 1).unwrap();
+            let end = if end_month == 12 {
+                NaiveDate::from_ymd_opt(*year + 1, 1, 1).unwrap() - Duration::days(1)
+            } else {
+                NaiveDate::from_ymd_opt(*year, end_month as u32 + 1, 1).unwrap() - Duration::days(1)
+            };
+            calendar.days(start, end)
+        }
+        ReportPeriod::Custom(from, to) => (*from, *to),
+    }
+}
+
+/// (project, share now, share before) for every project in either period
+fn project_shares(c: &Comparison) -> Vec<(String, f64, f64)> {
+    let mut rows: Vec<(String, f64, f64)> = c
+        .current
+        .project_share
+        .iter()
+        .map(|(name, now)| {
+            let before = c
+                .previous
+                .project_share
+                .iter()
+                .find(|(p, _)| p == name)
+                .map_or(0.0, |(_, s)| *s);
+            (name.clone(), *now, before)
+        })
+        .collect();
+    for (name, before) in &c.previous.project_share {
+        if !rows.iter().any(|(p, _, _)| p == name) {
+            rows.push((name.clone(), 0.0, *before));
+        }
+    }
+    rows
+}
+
+/// "+3 (+25%)"; the percentage is left out when there's nothing to compare to
+fn delta_count(now: i64, before: i64) -> String {
+    format!("{:+}{}", now - before, percent_change(now, before))
+}
+
+fn delta_active(now: i64, before: i64) -> String {
+    let diff = now - before;
+    let sign = if diff < 0 { "-" } else { "+" };
+    format!("{}{}{}", sign, activity::format_active(diff.abs()), percent_change(now, before))
+}
+
+fn delta_tokens(now: usize, before: usize) -> String {
+    let diff = now as i64 - before as i64;
+    let sign = if diff < 0 { "-" } else { "+" };
+    format!(
+        "{}{}{}",
+        sign,
+        format_tokens(diff.unsigned_abs() as usize),
+        percent_change(now as i64, before as i64)
+    )
+}
+
+fn percent_change(now: i64, before: i64) -> String {
+    if before == 0 {
+        String::new()
+    } else {
+        format!(" ({:+.0}%)", (now - before) as f64 * 100.0 / before as f64)
+    }
+}
+
+fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Active time per day of the period, from each session's message times
+fn active_by_day(db: &Database, sessions: &[SessionRow], period: &ReportPeriod) -> Vec<(NaiveDate, i64)> {
+    let (from, to) = period_to_range(period);
+    let mut days: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    for session in sessions {
+        let timestamps = db.activity_timestamps(&session.id).unwrap_or_default();
+        for (day, secs) in activity::active_seconds_by_day(&timestamps, db.idle_threshold()) {
+            *days.entry(day).or_insert(0) += secs;
+        }
+    }
+    let calendar = clock::calendar();
+    let (first, last) = (calendar.day_of(from), calendar.day_of(to));
+    days.into_iter()
+        .filter(|(day, _)| *day >= first && *day <= last)
+        .collect()
+}
+
+fn get_session_file_changes(db: &Database, session_id: &str) -> Vec<(String, &'static str)> {
+    let tool_calls = db.get_tool_calls(session_id).unwrap_or_default();
+    let mut files = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for tc in &tool_calls {
+        if let Some(ref fp) = tc.file_path {
+            if seen.insert(fp.clone()) {
+                let prefix = match tc.tool_name.as_str() {
+                    "Write" | "create_file" => "+",
+                    "Edit" | "edit_file" => "~",
+                    "delete_file" => "-",
+                    _ => "~",
+                };
+                files.push((fp.clone(), prefix));
+            }
+        }
+    }
+    files
+}
+
+fn short_path(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    if parts.len() <= 1 {
+        path.to_string()
+    } else {
+        parts.last().unwrap_or(&path).to_string()
+    }
+}
+
+fn agent_display(agent: &str) -> &str {
+    match agent {
+        "claude-code" => "Claude Code",
+        "codex" => "Codex",
+        "cursor" => "Cursor",
+        _ => agent,
+    }
+}
+
+fn period_label(period: &ReportPeriod) -> String {
+    match period {
+        ReportPeriod::Day(date) => date.format("%Y-%m-%d").to_string(),
+        ReportPeriod::Week(start, end) => {
+            format!("{} ~ {}", start.format("%Y.%m.%d"), end.format("%m.%d"))
+        }
+        ReportPeriod::Month(year, month) => format!("{}-{:02}", year, month),
+        ReportPeriod::Quarter(year, q) => format!("{} Q{}", year, q),
+        ReportPeriod::Custom(from, to) => {
+            format!(
+                "{} ~ {}",
+                from.format("%Y.%m.%d"),
+                to.format("%m.%d")
+            )
+        }
+    }
+}
+
+pub fn resolve_period(
+    day: bool,
+    date: Option<&str>,
+    week: bool,
+    month: bool,
+    quarter: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<ReportPeriod> {
+    let calendar = clock::calendar();
+    let today = calendar.today();
+
+    if let (Some(from_str), Some(to_str)) = (from, to) {
+        let from_dt = crate::core::db::parse_datetime(from_str)
+            .ok_or_else(|| anyhow::anyhow!("Invalid --from date: {}", from_str))?;
+        // A bare date includes that whole day
+        let to_dt = match NaiveDate::parse_from_str(to_str, "%Y-%m-%d") {
+            Ok(date) => calendar.days(date, date).1,
+            Err(_) => crate::core::db::parse_datetime(to_str)
+                .ok_or_else(|| anyhow::anyhow!("Invalid --to date: {}", to_str))?,
+        };
+        return Ok(ReportPeriod::Custom(from_dt, to_dt));
+    }
+
+    if let Some(q) = quarter {
+        let q_num: u8 = q
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .parse()
+            .unwrap_or(1);
+        return Ok(ReportPeriod::Quarter(today.year(), q_num));
+    }
+
+    if month {
+        return Ok(ReportPeriod::Month(today.year(), today.month()));
+    }
+
+    if week {
+        let start = calendar.week_of(today);
+        return Ok(ReportPeriod::Week(start, start + Duration::days(6)));
+    }
+
+    if day {
+        let d = if let Some(date_str) = date {
+            NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid date format: {}", date_str))?
+        } else {
+            today
+        };
+        return Ok(ReportPeriod::Day(d));
+    }
+
+    // Default to weekly
+    let start = calendar.week_of(today);
+    Ok(ReportPeriod::Week(start, start + Duration::days(6)))
+}
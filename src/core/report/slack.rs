@@ -0,0 +1,77 @@
+use super::standup::Standup;
+use super::{agent_display, request_text, ReportData, ReportRenderer};
+use crate::core::activity;
+use anyhow::Result;
+use std::fmt::Write;
+
+pub struct SlackRenderer;
+
+impl ReportRenderer for SlackRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let stats = &report.stats;
+        let mut out = String::new();
+
+        writeln!(out, "*AI Work Report ({})*", report.label())?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "> {} sessions across {} projects, {} active",
+            stats.total_sessions,
+            stats.sessions_by_project.len(),
+            activity::format_active(stats.total_active_secs)
+        )?;
+        for (agent, count) in &stats.sessions_by_agent {
+            writeln!(out, "> {} {} sessions", agent_display(agent), count)?;
+        }
+        if stats.sessions_by_author.len() > 1 {
+            for (author, count) in &stats.sessions_by_author {
+                writeln!(out, "> {}: {} sessions", author, count)?;
+            }
+        }
+        let outcomes = report.outcome_totals();
+        if let Some(rate) = outcomes.success_rate() {
+            writeln!(
+                out,
+                "> {:.0}% succeeded ({} failed, {} abandoned)",
+                rate, outcomes.failed, outcomes.abandoned
+            )?;
+        }
+        if let Some(ref c) = report.comparison {
+            writeln!(out, "> vs {}:", c.previous.label)?;
+            for [metric, _, _, change] in c.metric_rows() {
+                writeln!(out, ">   {} {}", metric.to_lowercase(), change)?;
+            }
+        }
+        writeln!(out)?;
+
+        for (group, group_sessions) in report.groups() {
+            writeln!(out, "*{}* ({} sessions)", group, group_sessions.len())?;
+            for (idx, session) in group_sessions.iter().enumerate() {
+                let work = session.work_summary.as_deref().unwrap_or("-");
+                writeln!(out, "  {}. {}", idx + 1, request_text(session))?;
+                if work != "-" {
+                    writeln!(out, "      Work: {}", work)?;
+                }
+            }
+            writeln!(out)?;
+        }
+
+        Ok(out)
+    }
+
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "*Standup ({})*", standup.today.format("%a %b %-d"))?;
+        for (title, items) in standup.sections() {
+            writeln!(out)?;
+            writeln!(out, "*{}*", title)?;
+            if items.is_empty() {
+                writeln!(out, "• _none_")?;
+            }
+            for item in items {
+                writeln!(out, "• {}", item.line())?;
+            }
+        }
+        Ok(out)
+    }
+}
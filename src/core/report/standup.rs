@@ -0,0 +1,174 @@
+use super::ReportFormat;
+use crate::core::clock;
+use crate::core::db::{parse_datetime, Database, SessionRow};
+use crate::core::outcome::{self, Outcome};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// One line of a standup
+#[derive(Debug, Clone)]
+pub struct StandupItem {
+    pub session_id: String,
+    pub project: Option<String>,
+    pub text: String,
+    /// Why a blocker is one, e.g. `last 3 tool calls failed`
+    pub note: Option<String>,
+}
+
+impl StandupItem {
+    /// `proj: text (note)`
+    pub fn line(&self) -> String {
+        let mut line = match self.project {
+            Some(ref project) => format!("{}: {}", project, self.text),
+            None => self.text.clone(),
+        };
+        if let Some(ref note) = self.note {
+            line.push_str(&format!(" ({})", note));
+        }
+        line
+    }
+}
+
+/// What was done on the last working day, what's open today, and what failed
+pub struct Standup {
+    pub today: NaiveDate,
+    /// The working day before `today`; weekends are skipped
+    pub previous: NaiveDate,
+    pub done: Vec<StandupItem>,
+    pub open: Vec<StandupItem>,
+    pub blockers: Vec<StandupItem>,
+}
+
+impl Standup {
+    /// "Yesterday", or "Since Friday" after a weekend
+    pub fn done_title(&self) -> String {
+        if self.previous == self.today - Duration::days(1) {
+            "Yesterday".to_string()
+        } else {
+            format!("Since {}", self.previous.format("%A"))
+        }
+    }
+
+    /// (title, items) for each section, in standup order
+    pub fn sections(&self) -> [(String, &[StandupItem]); 3] {
+        [
+            (self.done_title(), &self.done),
+            ("Today".to_string(), &self.open),
+            ("Blockers".to_string(), &self.blockers),
+        ]
+    }
+}
+
+pub fn generate_standup(
+    db: &Database,
+    today: NaiveDate,
+    project: Option<&str>,
+    author: Option<&str>,
+    format: ReportFormat,
+) -> Result<String> {
+    let standup = build_standup(db, today, project, author)?;
+    format.renderer().render_standup(&standup)
+}
+
+/// Sessions active from the previous working day through `today`, started
+/// or ended in that window, sorted into sections: work finished before today
+/// is done; today's work and unfinished threads are today's; failures are
+/// also blockers. A thread is unfinished when it failed, is still going, its
+/// last prompt got no reply, or its changes were never committed. Sessions
+/// the user gave up on are left out.
+pub fn build_standup(db: &Database, today: NaiveDate, project: Option<&str>, author: Option<&str>) -> Result<Standup> {
+    let calendar = clock::calendar();
+    let previous = previous_workday(today);
+    let (from, _) = calendar.days(previous, previous);
+    let (_, to) = calendar.days(today, today);
+    let sessions = db.list_sessions_active_between(project, author, from, to)?;
+
+    let mut standup = Standup {
+        today,
+        previous,
+        done: Vec::new(),
+        open: Vec::new(),
+        blockers: Vec::new(),
+    };
+    // Oldest first, so each section reads in the order the work happened
+    for session in sessions.iter().rev() {
+        let outcome = session.outcome.as_deref().and_then(Outcome::from_str);
+        let day = session
+            .ended_at
+            .as_deref()
+            .or(session.started_at.as_deref())
+            .and_then(parse_datetime)
+            .map(|t| calendar.day_of(t))
+            .unwrap_or(today);
+        match outcome {
+            Some(Outcome::Failed) => {
+                push(&mut standup.open, session, vec![request(session)], Some("failed".to_string()));
+                push(&mut standup.blockers, session, vec![request(session)], session.outcome_reason.clone());
+            }
+            Some(Outcome::InProgress) => push(&mut standup.open, session, vec![request(session)], Some("in progress".to_string())),
+            Some(Outcome::Abandoned) if session.outcome_reason.as_deref() == Some(outcome::NO_REPLY) => {
+                push(&mut standup.open, session, vec![request(session)], Some("no reply yet".to_string()))
+            }
+            Some(Outcome::Abandoned) => {}
+            _ => {
+                let changed = session.files_created + session.files_modified + session.files_deleted > 0;
+                let uncommitted = (changed && !db.session_committed(&session.id)?).then(|| "not committed".to_string());
+                if day < today {
+                    push(&mut standup.done, session, accomplished(session), None);
+                    if uncommitted.is_some() {
+                        push(&mut standup.open, session, vec![request(session)], uncommitted);
+                    }
+                } else {
+                    push(&mut standup.open, session, accomplished(session), uncommitted);
+                }
+            }
+        }
+    }
+    Ok(standup)
+}
+
+/// The working day before `date`, skipping Saturday and Sunday
+pub fn previous_workday(date: NaiveDate) -> NaiveDate {
+    let mut day = date - Duration::days(1);
+    while matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+        day -= Duration::days(1);
+    }
+    day
+}
+
+fn push(items: &mut Vec<StandupItem>, session: &SessionRow, texts: Vec<String>, note: Option<String>) {
+    for text in texts {
+        if items.iter().any(|i| i.text == text && i.project == session.project_name) {
+            continue;
+        }
+        items.push(StandupItem {
+            session_id: session.id.clone(),
+            project: session.project_name.clone(),
+            text,
+            note: note.clone(),
+        });
+    }
+}
+
+/// What got done: each bullet of the LLM summary, else the work summary,
+/// else the request
+fn accomplished(session: &SessionRow) -> Vec<String> {
+    if let Some(ref summary) = session.llm_summary {
+        let bullets: Vec<String> = summary
+            .lines()
+            .map(|l| l.trim().trim_start_matches(['-', '*', '•']).trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !bullets.is_empty() {
+            return bullets;
+        }
+    }
+    match session.work_summary {
+        Some(ref work) if !work.is_empty() => vec![work.clone()],
+        _ => vec![request(session)],
+    }
+}
+
+fn request(session: &SessionRow) -> String {
+    super::request_text(session).lines().next().unwrap_or("-").to_string()
+}
@@ -0,0 +1,11 @@
+use super::standup::Standup;
+use super::ReportData;
+use anyhow::Result;
+
+/// One output format for `ail report`
+pub trait ReportRenderer {
+    fn render(&self, report: &ReportData) -> Result<String>;
+
+    /// The same format for `ail standup`
+    fn render_standup(&self, standup: &Standup) -> Result<String>;
+}
@@ -0,0 +1,164 @@
+use super::standup::Standup;
+use super::{agent_display, outcome_note, request_text, short_path, ReportData, ReportRenderer, OUTCOME_COLUMNS};
+use crate::core::activity;
+use anyhow::Result;
+use std::fmt::Write;
+
+/// Atlassian wiki markup, pasteable into Confluence and Jira
+pub struct WikiRenderer;
+
+impl ReportRenderer for WikiRenderer {
+    fn render(&self, report: &ReportData) -> Result<String> {
+        let stats = &report.stats;
+        let mut out = String::new();
+
+        writeln!(out, "h1. AI Work Report ({})", escape(&report.label()))?;
+        writeln!(out)?;
+
+        writeln!(out, "h2. Summary")?;
+        writeln!(
+            out,
+            "* Total: {} sessions, {} projects",
+            stats.total_sessions,
+            stats.sessions_by_project.len()
+        )?;
+        for (agent, count) in &stats.sessions_by_agent {
+            writeln!(out, "* {}: {} sessions", agent_display(agent), count)?;
+        }
+        if stats.sessions_by_author.len() > 1 {
+            let authors: Vec<String> = stats
+                .sessions_by_author
+                .iter()
+                .map(|(author, count)| format!("{} ({})", escape(author), count))
+                .collect();
+            writeln!(out, "* Authors: {}", authors.join(", "))?;
+        }
+        writeln!(out, "* Active time: {}", activity::format_active(stats.total_active_secs))?;
+        writeln!(
+            out,
+            "* Files: {} created, {} modified, {} deleted",
+            stats.total_files_created, stats.total_files_modified, stats.total_files_deleted
+        )?;
+        let outcomes = report.outcome_totals();
+        if let Some(rate) = outcomes.success_rate() {
+            writeln!(out, "* Success rate: {:.0}% of {} finished sessions", rate, outcomes.finished())?;
+        }
+        writeln!(out)?;
+
+        if let Some(ref c) = report.comparison {
+            writeln!(out, "h2. Compared to {}", escape(&c.previous.label))?;
+            write_table(&mut out, &["Metric", &c.previous.label, &c.current.label, "Change"], c.metric_rows())?;
+            let shares = c.share_rows();
+            if !shares.is_empty() {
+                write_table(&mut out, &["Project", "Share before", "Share now", "Change"], shares)?;
+            }
+        }
+
+        if stats.total_active_secs > 0 {
+            writeln!(out, "h2. Active Time")?;
+            for (label, rows) in report.active_tables() {
+                let rows: Vec<[String; 3]> = rows
+                    .into_iter()
+                    .map(|(name, secs)| {
+                        [
+                            name,
+                            activity::format_active(secs),
+                            format!("{:.1}", activity::hours(secs)),
+                        ]
+                    })
+                    .collect();
+                write_table(&mut out, &[label, "Active", "Hours"], rows)?;
+            }
+        }
+
+        let outcome_tables = report.outcome_tables();
+        if !outcome_tables.is_empty() {
+            writeln!(out, "h2. Outcomes")?;
+            for (label, rows) in outcome_tables {
+                let [a, b, c, d, e] = OUTCOME_COLUMNS;
+                write_table(&mut out, &[label, a, b, c, d, e], rows.iter().map(|r| r.row()).collect())?;
+            }
+        }
+
+        writeln!(out, "h2. Activity by {}", report.group_by.title())?;
+        writeln!(out)?;
+        for (group, group_sessions) in report.groups() {
+            let active: i64 = group_sessions.iter().filter_map(|s| s.active_secs).sum();
+            writeln!(
+                out,
+                "h3. {} ({} sessions, {} active)",
+                escape(&group),
+                group_sessions.len(),
+                activity::format_active(active)
+            )?;
+            for session in &group_sessions {
+                // `\\` breaks the line without ending the list item
+                let mut item = format!("# {}", escape(request_text(session)));
+                if let Some(ref work) = session.work_summary {
+                    item.push_str(&format!(" \\\\ _Work:_ {}", escape(work)));
+                }
+                let files: Vec<String> = report
+                    .file_changes(&session.id)
+                    .iter()
+                    .map(|(path, prefix)| format!("{{{{{}}}}}", escape(&format!("{}{}", prefix, short_path(path)))))
+                    .collect();
+                if !files.is_empty() {
+                    item.push_str(&format!(" \\\\ _Files:_ {}", files.join(" ")));
+                }
+                if let Some(note) = outcome_note(session) {
+                    item.push_str(&format!(" \\\\ _Outcome:_ {}", escape(&note)));
+                }
+                writeln!(out, "{}", item)?;
+            }
+            let created: i64 = group_sessions.iter().map(|s| s.files_created).sum();
+            let modified: i64 = group_sessions.iter().map(|s| s.files_modified).sum();
+            writeln!(out)?;
+            writeln!(out, "Total: {} created, {} modified", created, modified)?;
+            writeln!(out)?;
+        }
+
+        Ok(out)
+    }
+
+    fn render_standup(&self, standup: &Standup) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "h1. Standup ({})", standup.today.format("%Y-%m-%d"))?;
+        for (title, items) in standup.sections() {
+            writeln!(out)?;
+            writeln!(out, "h2. {}", title)?;
+            if items.is_empty() {
+                writeln!(out, "* None")?;
+            }
+            for item in items {
+                writeln!(out, "* {}", escape(&item.line()))?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn write_table<const N: usize>(out: &mut String, head: &[&str; N], rows: Vec<[String; N]>) -> Result<()> {
+    let head: Vec<String> = head.iter().map(|h| escape(h)).collect();
+    writeln!(out, "||{}||", head.join("||"))?;
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+        writeln!(out, "|{}|", cells.join("|"))?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Backslash-escape characters that wiki markup treats as formatting
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '|' | '{' | '}' | '[' | ']' | '*' | '_' | '^' | '~' | '+') {
+            out.push('\\');
+        }
+        out.push(match c {
+            '\n' | '\r' => ' ',
+            c => c,
+        });
+    }
+    out
+}
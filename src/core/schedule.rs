@@ -0,0 +1,316 @@
+use crate::config::{self, AilConfig, ReportSchedule};
+use crate::core::clock;
+use crate::core::db::Database;
+use crate::core::outcome;
+use crate::core::redact::Redactor;
+use crate::core::report::{self, GroupBy, ReportFormat, ReportPeriod};
+use crate::core::summarize;
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, IsoWeek, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A lock older than this is left over from a run that died
+const STALE_LOCK: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// What happened to one schedule in a run
+#[derive(Debug)]
+pub struct Outcome {
+    pub name: String,
+    /// Period key, e.g. `2026-W41`
+    pub period: String,
+    pub destination: String,
+    pub status: Status,
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Delivered,
+    AlreadyDelivered,
+    /// `--dry-run`: would have been delivered
+    Due,
+    Failed(String),
+}
+
+/// Last period delivered per schedule, kept in `schedules.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct Delivered {
+    period: String,
+    destination: String,
+    delivered_at: String,
+}
+
+/// Deliver each schedule's most recently ended period, unless it already was.
+/// A failed delivery isn't recorded, so the next run tries it again. Runs
+/// hold a lock file, so overlapping cron or timer invocations don't double-send.
+pub fn run_scheduled(
+    db: &Database,
+    config: &AilConfig,
+    redactor: &Redactor,
+    only: Option<&str>,
+    force: bool,
+    dry_run: bool,
+) -> Result<Vec<Outcome>> {
+    let schedules: Vec<(&String, &ReportSchedule)> = config
+        .report
+        .schedules
+        .iter()
+        .filter(|(name, _)| only.is_none_or(|o| o == name.as_str()))
+        .collect();
+    if let Some(name) = only {
+        if schedules.is_empty() {
+            bail!("No schedule named '{}' in [report.schedules]", name);
+        }
+    }
+
+    config::ensure_data_dir()?;
+    let _lock = if dry_run { None } else { Some(RunLock::acquire()?) };
+    let mut log = load_log();
+    let today = clock::calendar().today();
+
+    let mut outcomes = Vec::new();
+    for (name, schedule) in schedules {
+        let period = match last_completed(&schedule.period, today) {
+            Ok(p) => p,
+            Err(e) => {
+                outcomes.push(Outcome {
+                    name: name.clone(),
+                    period: String::new(),
+                    destination: String::new(),
+                    status: Status::Failed(e.to_string()),
+                });
+                continue;
+            }
+        };
+        let key = period_key(&period);
+        let destination = expand_destination(&schedule.destination, name, &period);
+        let mut outcome = Outcome {
+            name: name.clone(),
+            period: key.clone(),
+            destination: display_destination(&destination),
+            status: Status::Due,
+        };
+
+        if !force && log.get(name.as_str()).is_some_and(|d| d.period == key) {
+            outcome.status = Status::AlreadyDelivered;
+        } else if !dry_run {
+            outcome.status = match deliver(db, config, redactor, schedule, &period, &destination) {
+                Ok(()) => {
+                    log.insert(
+                        name.clone(),
+                        Delivered {
+                            period: key,
+                            destination: outcome.destination.clone(),
+                            delivered_at: chrono::Utc::now().to_rfc3339(),
+                        },
+                    );
+                    save_log(&log)?;
+                    Status::Delivered
+                }
+                Err(e) => Status::Failed(format!("{:#}", e)),
+            };
+        }
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+fn deliver(
+    db: &Database,
+    config: &AilConfig,
+    redactor: &Redactor,
+    schedule: &ReportSchedule,
+    period: &ReportPeriod,
+    destination: &str,
+) -> Result<()> {
+    if destination.is_empty() {
+        bail!("No destination set");
+    }
+    let Some(group_by) = GroupBy::from_str(&schedule.group_by) else {
+        bail!("Unknown group_by '{}'", schedule.group_by);
+    };
+    let project = schedule.project.as_deref();
+    if config.report.summarize.enabled {
+        let (from, to) = report::period_to_range(period);
+        let sessions = db.list_all_sessions(None, project, None, Some(from), Some(to))?;
+        summarize::summarize_sessions(db, &sessions, &config.report.summarize)?;
+    }
+    if config.report.refine_outcomes {
+        let (from, to) = report::period_to_range(period);
+        let sessions = db.list_all_sessions(None, project, None, Some(from), Some(to))?;
+        outcome::refine_outcomes(db, &sessions, &config.report.summarize)?;
+    }
+
+    let format = ReportFormat::from_str(&schedule.format)?;
+    let content = report::generate_report(db, period, project, None, format, schedule.compare, group_by)?;
+    let content = redactor.redact_output(&content);
+
+    if is_webhook(destination) {
+        post_webhook(destination, &content, format)
+    } else {
+        let path = config::expand_home(destination);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        Ok(())
+    }
+}
+
+/// The last day, week, month or quarter that ended before `today`
+pub fn last_completed(period: &str, today: NaiveDate) -> Result<ReportPeriod> {
+    let current = match period.to_lowercase().as_str() {
+        "day" | "daily" => ReportPeriod::Day(today),
+        "week" | "weekly" => {
+            let start = clock::calendar().week_of(today);
+            ReportPeriod::Week(start, start + Duration::days(6))
+        }
+        "month" | "monthly" => ReportPeriod::Month(today.year(), today.month()),
+        "quarter" | "quarterly" => ReportPeriod::Quarter(today.year(), ((today.month() - 1) / 3 + 1) as u8),
+        other => bail!("Unknown period '{}' (expected: day, week, month, quarter)", other),
+    };
+    Ok(report::previous_period(&current))
+}
+
+/// Stable name for a period: `2026-10-17`, `2026-W41`, `2026-10`, `2026-Q3`
+pub fn period_key(period: &ReportPeriod) -> String {
+    match period {
+        ReportPeriod::Day(date) => date.format("%Y-%m-%d").to_string(),
+        ReportPeriod::Week(start, _) => {
+            let week = iso_week(*start);
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        ReportPeriod::Month(year, month) => format!("{}-{:02}", year, month),
+        ReportPeriod::Quarter(year, quarter) => format!("{}-Q{}", year, quarter),
+        ReportPeriod::Custom(from, to) => format!("{}_{}", from.format("%Y-%m-%d"), to.format("%Y-%m-%d")),
+    }
+}
+
+/// Fill `{name}`, `{year}`, `{month}`, `{week}`, `{quarter}` and `{date}`
+/// (the period's first day) in a destination pattern
+pub fn expand_destination(pattern: &str, name: &str, period: &ReportPeriod) -> String {
+    let start = match period {
+        ReportPeriod::Day(date) | ReportPeriod::Week(date, _) => *date,
+        ReportPeriod::Month(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1).unwrap(),
+        ReportPeriod::Quarter(year, q) => NaiveDate::from_ymd_opt(*year, (*q as u32 - 1) * 3 + 1, 1).unwrap(),
+        ReportPeriod::Custom(from, _) => clock::calendar().day_of(*from),
+    };
+    let week = iso_week(start);
+    let year = match period {
+        // Weeks belong to their ISO year: 2024-12-30 is in 2025-W01
+        ReportPeriod::Week(..) => week.year(),
+        _ => start.year(),
+    };
+    pattern
+        .replace("{name}", name)
+        .replace("{year}", &year.to_string())
+        .replace("{month}", &format!("{:02}", start.month()))
+        .replace("{week}", &format!("{:02}", week.week()))
+        .replace("{quarter}", &((start.month() - 1) / 3 + 1).to_string())
+        .replace("{date}", &start.format("%Y-%m-%d").to_string())
+}
+
+/// ISO week of the week starting on `start`, judged by its middle so a
+/// Sunday-start week takes the number of the Monday-start week it mostly overlaps
+fn iso_week(start: NaiveDate) -> IsoWeek {
+    (start + Duration::days(3)).iso_week()
+}
+
+fn is_webhook(destination: &str) -> bool {
+    destination.starts_with("https://") || destination.starts_with("http://")
+}
+
+/// Webhook URLs carry their secret in the path; only show the host
+fn display_destination(destination: &str) -> String {
+    if is_webhook(destination) {
+        let host = destination.split("://").nth(1).and_then(|r| r.split('/').next()).unwrap_or("");
+        format!("webhook ({})", host)
+    } else {
+        destination.to_string()
+    }
+}
+
+/// Slack and Teams incoming webhooks both take `{"text": ...}`; a JSON
+/// report is posted as-is for generic receivers
+fn post_webhook(url: &str, content: &str, format: ReportFormat) -> Result<()> {
+    let body = match format {
+        ReportFormat::Json => serde_json::from_str(content)?,
+        _ => serde_json::json!({ "text": content }),
+    };
+    let resp = ureq::post(url)
+        .timeout(std::time::Duration::from_secs(30))
+        .send_json(body);
+    match resp {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, resp)) => {
+            let body = resp.into_string().unwrap_or_default();
+            bail!("Webhook error ({}): {}", code, body.trim());
+        }
+        // Not `{e}`: that includes the URL
+        Err(ureq::Error::Transport(t)) => match t.message() {
+            Some(message) => bail!("Webhook request failed: {}: {}", t.kind(), message),
+            None => bail!("Webhook request failed: {}", t.kind()),
+        },
+    }
+}
+
+fn log_path() -> PathBuf {
+    config::data_dir().join("schedules.json")
+}
+
+fn load_log() -> BTreeMap<String, Delivered> {
+    fs::read_to_string(log_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Written to a temp file and renamed, so a killed run never leaves it half-written
+fn save_log(log: &BTreeMap<String, Delivered>) -> Result<()> {
+    let path = log_path();
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(log)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// `schedules.lock` in the data directory, removed when dropped
+struct RunLock(PathBuf);
+
+impl RunLock {
+    fn acquire() -> Result<Self> {
+        let path = config::data_dir().join("schedules.lock");
+        for _ in 0..2 {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id()).ok();
+                    return Ok(Self(path));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .map(|t| t.elapsed().unwrap_or_default() > STALE_LOCK)
+                        .unwrap_or(true);
+                    if !stale {
+                        bail!(
+                            "Another scheduled run is in progress (remove {} if it isn't)",
+                            path.display()
+                        );
+                    }
+                    fs::remove_file(&path).ok();
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        bail!("Could not take {}", path.display())
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.0).ok();
+    }
+}
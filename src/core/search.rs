@@ -1,11 +1,17 @@
-use crate::core::db::{Database, SearchResult, SessionRow};
-use anyhow::Result;
+use crate::core::clock;
+use crate::core::outcome::Outcome;
+use crate::core::paths;
+use crate::core::db::{
+    parse_datetime, Cursor, Database, Page, SearchResult, SessionFilter, SessionRow,
+};
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
 
 pub struct SearchOptions {
     pub keyword: Option<String>,
     pub agent: Option<String>,
     pub project: Option<String>,
+    pub author: Option<String>,
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
     pub file: Option<String>,
@@ -18,6 +24,7 @@ impl Default for SearchOptions {
             keyword: None,
             agent: None,
             project: None,
+            author: None,
             from: None,
             to: None,
             file: None,
@@ -27,30 +34,186 @@ impl Default for SearchOptions {
 }
 
 pub fn search_history(db: &Database, opts: &SearchOptions) -> Result<Vec<SearchResult>> {
+    Ok(search_history_page(db, opts, None)?.items)
+}
+
+pub fn search_history_page(
+    db: &Database,
+    opts: &SearchOptions,
+    after: Option<&Cursor>,
+) -> Result<Page<SearchResult>> {
     if let Some(ref keyword) = opts.keyword {
-        db.search_messages(
+        db.search_messages_page(
             keyword,
             opts.agent.as_deref(),
             opts.project.as_deref(),
+            opts.author.as_deref(),
             opts.from,
             opts.to,
+            after,
             opts.limit,
         )
     } else {
-        Ok(Vec::new())
+        Ok(Page {
+            items: Vec::new(),
+            next_cursor: None,
+        })
     }
 }
 
-pub fn search_by_file(db: &Database, file_path: &str, limit: usize) -> Result<Vec<SessionRow>> {
-    db.search_by_file(file_path, limit)
+/// The raw input plus, inside a git repo, its repo-relative path and former names
+pub fn file_name_variants(file_path: &str) -> Vec<String> {
+    let mut names = vec![file_path.to_string()];
+    let cwd = std::env::current_dir().unwrap_or_default();
+    if let Some((root, rel)) = paths::to_repo_relative(file_path, &cwd) {
+        for name in paths::git_path_history(&root, &rel) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
 }
 
 pub fn list_sessions(db: &Database, opts: &SearchOptions) -> Result<Vec<SessionRow>> {
-    db.list_sessions(
+    Ok(list_sessions_page(db, opts, None)?.items)
+}
+
+pub fn list_sessions_page(
+    db: &Database,
+    opts: &SearchOptions,
+    after: Option<&Cursor>,
+) -> Result<Page<SessionRow>> {
+    db.list_sessions_page(
         opts.agent.as_deref(),
         opts.project.as_deref(),
+        opts.author.as_deref(),
         opts.from,
         opts.to,
+        after,
         opts.limit,
     )
 }
+
+/// Resolve a CLI `--page N` / `--after CURSOR` pair to the requested page.
+///
+/// Pages are 1-based. With `--page`, earlier pages are walked to find the
+/// cursor, so `--after` is the cheaper choice for deep pages.
+pub fn fetch_page<T>(
+    page: Option<usize>,
+    after: Option<&str>,
+    mut fetch: impl FnMut(Option<&Cursor>) -> Result<Page<T>>,
+) -> Result<Page<T>> {
+    let mut cursor = after.map(Cursor::decode).transpose()?;
+    for _ in 1..page.unwrap_or(1).max(1) {
+        match fetch(cursor.as_ref())?.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => {
+                return Ok(Page {
+                    items: Vec::new(),
+                    next_cursor: None,
+                })
+            }
+        }
+    }
+    fetch(cursor.as_ref())
+}
+
+/// Read session pages until `limit` rows pass `keep`, so the returned cursor
+/// continues after the last row kept rather than the last row read.
+pub fn fetch_filtered_page(
+    after: Option<&Cursor>,
+    limit: usize,
+    mut fetch: impl FnMut(Option<&Cursor>) -> Result<Page<SessionRow>>,
+    keep: impl Fn(&SessionRow) -> bool,
+) -> Result<Page<SessionRow>> {
+    let mut cursor = after.cloned();
+    let mut items = Vec::new();
+    loop {
+        let page = fetch(cursor.as_ref())?;
+        items.extend(page.items.into_iter().filter(|s| keep(s)));
+        if items.len() > limit {
+            items.truncate(limit);
+            let next_cursor = items.last().map(|s| Cursor::Session {
+                started_at: s.started_at.clone().unwrap_or_default(),
+                id: s.id.clone(),
+            });
+            return Ok(Page { items, next_cursor });
+        }
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(Page { items, next_cursor: None }),
+        }
+    }
+}
+
+/// Parse a saved-search query string into a `SessionFilter`.
+///
+/// Supported qualifiers: `agent:`, `project:`, `tag:`, `file:`, `author:`,
+/// `after:` and `before:`. Dates accept `YYYY-MM-DD`, RFC3339, or a relative duration
+/// like `-7d` (resolved against the current time, so saved searches stay live).
+/// Everything else is joined into the FTS keyword.
+pub fn parse_query(query: &str) -> Result<SessionFilter> {
+    let mut filter = SessionFilter::default();
+    let mut keywords: Vec<&str> = Vec::new();
+
+    for token in query.split_whitespace() {
+        let Some((key, value)) = token.split_once(':') else {
+            keywords.push(token);
+            continue;
+        };
+        if value.is_empty() {
+            keywords.push(token);
+            continue;
+        }
+        match key.to_lowercase().as_str() {
+            "agent" => filter.agent = Some(value.to_string()),
+            "project" => filter.project = Some(value.to_string()),
+            "tag" => filter.tag = Some(value.to_string()),
+            "file" => filter.file = Some(value.to_string()),
+            "author" => filter.author = Some(value.to_string()),
+            "outcome" => match Outcome::from_str(value) {
+                Some(o) => filter.outcome = Some(o.as_str().to_string()),
+                None => bail!("Unknown outcome in query: {}", value),
+            },
+            "after" | "from" => filter.from = Some(parse_query_date(value)?),
+            "before" | "to" => filter.to = Some(parse_query_date(value)?),
+            _ => keywords.push(token),
+        }
+    }
+
+    if !keywords.is_empty() {
+        filter.keyword = Some(keywords.join(" "));
+    }
+    Ok(filter)
+}
+
+fn parse_query_date(value: &str) -> Result<DateTime<Utc>> {
+    if let Some(rel) = value.strip_prefix('-') {
+        if let Some(since) = clock::calendar().since(rel) {
+            return Ok(since);
+        }
+    }
+    match parse_datetime(value) {
+        Some(dt) => Ok(dt),
+        None => bail!("Invalid date in query: {}", value),
+    }
+}
+
+/// Run a saved search by name, returning matching sessions (most recent first).
+pub fn run_saved_search(db: &Database, name: &str, limit: usize) -> Result<Vec<SessionRow>> {
+    Ok(run_saved_search_page(db, name, None, limit)?.items)
+}
+
+pub fn run_saved_search_page(
+    db: &Database,
+    name: &str,
+    after: Option<&Cursor>,
+    limit: usize,
+) -> Result<Page<SessionRow>> {
+    let saved = db
+        .get_saved_search(name)?
+        .ok_or_else(|| anyhow::anyhow!("Saved search not found: {}", name))?;
+    let filter = parse_query(&saved.query)?;
+    db.query_sessions_page(&filter, after, limit)
+}
@@ -1,83 +1,24 @@
 use crate::config::SummarizeConfig;
 use crate::core::db::{Database, SessionRow};
-use anyhow::{bail, Result};
+use crate::core::llm::{self, LlmProvider};
+use anyhow::Result;
 
-/// Resolve the API key: config value takes precedence, then ANTHROPIC_API_KEY env var
-fn resolve_api_key(config: &SummarizeConfig) -> Result<String> {
-    if let Some(ref key) = config.api_key {
-        if !key.is_empty() {
-            return Ok(key.clone());
-        }
-    }
-    if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
-        if !key.is_empty() {
-            return Ok(key);
-        }
-    }
-    bail!(
-        "No API key found. Set ANTHROPIC_API_KEY environment variable or add api_key to [report.summarize] in config."
-    )
-}
-
-/// Call Claude API to generate a one-line summary of a session
-fn call_claude_summarize(
-    api_key: &str,
-    model: &str,
-    session_text: &str,
-    max_input_chars: usize,
-) -> Result<String> {
+/// Ask the model for a one-line (or up to three bullet) summary of a session
+fn summarize_text(llm: &dyn LlmProvider, session_text: &str, max_input_chars: usize) -> Result<String> {
     // Truncate input to max_input_chars
     let input: String = session_text.chars().take(max_input_chars).collect();
 
-    let body = serde_json::json!({
-        "model": model,
-        "max_tokens": 300,
-        "messages": [{
-            "role": "user",
-            "content": format!(
-                "Summarize this AI coding session. Focus on what was accomplished.\nIf multiple distinct tasks were done, list each as a bullet point (max 3 bullets, each under 80 chars).\nIf only one task, use a single sentence (max 100 chars).\nReply with ONLY the summary, no quotes or prefixes.\n\nExample (multi-task):\n- Implemented user authentication with JWT\n- Fixed database migration bug in users table\n\nExample (single task):\nAdded dark mode toggle to application settings\n\n{}",
-                input
-            )
-        }]
-    });
-
-    let resp = ureq::post("https://api.anthropic.com/v1/messages")
-        .set("x-api-key", api_key)
-        .set("anthropic-version", "2023-06-01")
-        .set("content-type", "application/json")
-        .send_json(body);
-
-    let resp = match resp {
-        Ok(r) => r,
-        Err(ureq::Error::Status(code, resp)) => {
-            let body = resp.into_string().unwrap_or_default();
-            bail!("API error ({}): {}", code, body);
-        }
-        Err(e) => bail!("Request failed: {}", e),
-    };
-
-    let json: serde_json::Value = resp.into_json()?;
-
-    // Extract text from response
-    let text = json
-        .get("content")
-        .and_then(|c| c.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|item| item.get("text"))
-        .and_then(|t| t.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string();
-
-    if text.is_empty() {
-        bail!("Empty response from API");
-    }
-
-    Ok(text)
+    llm.complete(
+        &format!(
+            "Summarize this AI coding session. Focus on what was accomplished.\nIf multiple distinct tasks were done, list each as a bullet point (max 3 bullets, each under 80 chars).\nIf only one task, use a single sentence (max 100 chars).\nReply with ONLY the summary, no quotes or prefixes.\n\nExample (multi-task):\n- Implemented user authentication with JWT\n- Fixed database migration bug in users table\n\nExample (single task):\nAdded dark mode toggle to application settings\n\n{}",
+            input
+        ),
+        300,
+    )
 }
 
 /// Build a text representation of a session for summarization
-fn build_session_text(db: &Database, session: &SessionRow) -> String {
+pub fn build_session_text(db: &Database, session: &SessionRow) -> String {
     let mut text = String::new();
 
     if let Some(ref project) = session.project_name {
@@ -114,7 +55,7 @@ pub fn summarize_sessions(
     sessions: &[SessionRow],
     config: &SummarizeConfig,
 ) -> Result<usize> {
-    let api_key = resolve_api_key(config)?;
+    let llm = llm::provider(config)?;
 
     // Filter to sessions without llm_summary
     let to_summarize: Vec<&SessionRow> = sessions
@@ -134,8 +75,7 @@ pub fn summarize_sessions(
         eprint!("Summarizing {}/{}...\r", i + 1, total);
 
         let session_text = build_session_text(db, session);
-        match call_claude_summarize(&api_key, &config.model, &session_text, config.max_input_chars)
-        {
+        match summarize_text(llm.as_ref(), &session_text, config.max_input_chars) {
             Ok(summary) => {
                 if let Err(e) = db.update_llm_summary(&session.id, &summary) {
                     eprintln!("\nFailed to save summary for {}: {}", &session.id[..8], e);
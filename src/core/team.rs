@@ -0,0 +1,80 @@
+use crate::config::{self, TeamConfig};
+use crate::core::archive::{self, ImportResult, OnConflict};
+use crate::core::db::{Database, SessionRow};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Who ran a session and on which machine.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub author: String,
+    pub machine: String,
+}
+
+impl Identity {
+    /// This user: `[team] author`/`machine` from the config, else the login
+    /// name and hostname.
+    pub fn local(config: &TeamConfig) -> Self {
+        let author = non_empty(&config.author)
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "unknown".to_string());
+        let machine = non_empty(&config.machine)
+            .or_else(hostname)
+            .unwrap_or_else(|| "unknown".to_string());
+        Self { author, machine }
+    }
+
+    /// `local` with the user's saved config
+    pub fn load() -> Self {
+        let config = crate::config::load_config().map(|c| c.team).unwrap_or_default();
+        Self::local(&config)
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+fn hostname() -> Option<String> {
+    for var in ["HOSTNAME", "COMPUTERNAME"] {
+        if let Some(h) = std::env::var(var).ok().as_deref().and_then(non_empty) {
+            return Some(h);
+        }
+    }
+    if let Some(h) = std::fs::read_to_string("/etc/hostname").ok().as_deref().and_then(non_empty) {
+        return Some(h);
+    }
+    let out = std::process::Command::new("hostname").output().ok()?;
+    non_empty(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Whether the privacy settings allow sharing `session` with the team.
+pub fn is_shareable(session: &SessionRow, config: &TeamConfig) -> bool {
+    let excluded_project = config.exclude_projects.iter().any(|p| {
+        session.project_name.as_deref() == Some(p.as_str())
+            || session
+                .project_path
+                .as_deref()
+                .is_some_and(|path| Path::new(path).starts_with(config::expand_home(p)))
+    });
+    let excluded_tag = session
+        .tags
+        .split(',')
+        .any(|t| !t.is_empty() && config.exclude_tags.iter().any(|e| e == t));
+    !excluded_project && !excluded_tag
+}
+
+/// Fold team members' archives into a shared index at `db_path`. Sessions
+/// seen before are replaced, so merging fresh exports keeps the index current.
+pub fn merge_archives(db_path: &Path, archives: &[PathBuf]) -> Result<Vec<ImportResult>> {
+    let mut db = Database::open(db_path)?;
+    db.apply_config()?;
+    let mut results = Vec::new();
+    for path in archives {
+        results.push(archive::import_archive(&db, path, &[], OnConflict::Replace)?);
+    }
+    db.make_shareable()?;
+    Ok(results)
+}
@@ -0,0 +1,103 @@
+use crate::config;
+use crate::core::display;
+use anyhow::{Context, Result};
+use minijinja::{AutoEscape, Environment};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Templates shipped with ail. A file of the same name in the user's
+/// template directory takes precedence.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("default", include_str!("templates/default.md.j2")),
+    ("handoff", include_str!("templates/handoff.md.j2")),
+    ("pr", include_str!("templates/pr.md.j2")),
+    ("postmortem", include_str!("templates/postmortem.md.j2")),
+];
+
+/// Extensions tried, in order, when looking up a user template by name
+const TEMPLATE_EXTENSIONS: &[&str] = &["md.j2", "j2", "md", "jinja"];
+
+/// `~/.config/ail/templates`
+pub fn templates_dir() -> PathBuf {
+    config::config_dir().join("templates")
+}
+
+/// Render the template `name` with `ctx` as its context.
+pub fn render<S: Serialize>(name: &str, ctx: &S) -> Result<String> {
+    let source = load_source(name)?;
+
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.add_filter("truncate", truncate);
+    env.add_template_owned(name.to_string(), source)
+        .with_context(|| format!("Invalid template '{}'", name))?;
+
+    let tmpl = env.get_template(name)?;
+    tmpl.render(ctx)
+        .with_context(|| format!("Failed to render template '{}'", name))
+}
+
+/// All available template names: built-ins plus user templates.
+pub fn list_templates() -> Vec<(String, &'static str)> {
+    let mut names: Vec<(String, &'static str)> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(templates_dir()) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let stem = TEMPLATE_EXTENSIONS
+                .iter()
+                .find_map(|ext| file_name.strip_suffix(&format!(".{}", ext)))
+                .unwrap_or(&file_name)
+                .to_string();
+            if !names.iter().any(|(n, _)| *n == stem) {
+                names.push((stem, "user"));
+            }
+        }
+    }
+    for (name, _) in BUILTIN_TEMPLATES {
+        if !names.iter().any(|(n, _)| n == name) {
+            names.push((name.to_string(), "built-in"));
+        }
+    }
+    names.sort();
+    names
+}
+
+fn load_source(name: &str) -> Result<String> {
+    // A name, not a path: templates are only read from the templates directory
+    if name.is_empty()
+        || name.contains(['/', '\\'])
+        || name.contains("..")
+        || std::path::Path::new(name).is_absolute()
+    {
+        anyhow::bail!("Invalid template name: {}", name);
+    }
+
+    let dir = templates_dir();
+    let candidates = std::iter::once(dir.join(name))
+        .chain(TEMPLATE_EXTENSIONS.iter().map(|ext| dir.join(format!("{}.{}", name, ext))));
+    for path in candidates {
+        if path.is_file() {
+            return std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read template: {}", path.display()));
+        }
+    }
+
+    if let Some((_, source)) = BUILTIN_TEMPLATES.iter().find(|(n, _)| *n == name) {
+        return Ok(source.to_string());
+    }
+
+    let available: Vec<String> = list_templates().into_iter().map(|(n, _)| n).collect();
+    anyhow::bail!(
+        "Template not found: {} (available: {})",
+        name,
+        available.join(", ")
+    )
+}
+
+/// `{{ text | truncate(200) }}`: cut to a character count, adding "..."
+fn truncate(value: String, max_chars: Option<usize>) -> String {
+    display::truncate(&value, max_chars.unwrap_or(255))
+}
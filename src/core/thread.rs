@@ -0,0 +1,205 @@
+use crate::core::db::{MessageRow, ToolCallRow};
+use std::collections::HashMap;
+
+/// One exchange: a user prompt and everything the assistant did in reply
+/// (text replies and the tool calls they issued).
+#[derive(Debug, Clone)]
+pub struct Turn<'a> {
+    /// `None` for replies that open a branch (a retried answer) or resume after one
+    pub prompt: Option<&'a MessageRow>,
+    pub replies: Vec<&'a MessageRow>,
+    pub tool_calls: Vec<&'a ToolCallRow>,
+    /// 0 for the live conversation, +1 for each abandoned fork level
+    pub depth: usize,
+    /// On a branch the user rewound or retried away from
+    pub abandoned: bool,
+    /// First turn of an abandoned branch
+    pub branch_start: bool,
+}
+
+impl<'a> Turn<'a> {
+    pub fn messages(&self) -> impl Iterator<Item = &'a MessageRow> + '_ {
+        self.prompt.into_iter().chain(self.replies.iter().copied())
+    }
+}
+
+struct Item {
+    idx: usize,
+    depth: usize,
+    abandoned: bool,
+    branch_start: bool,
+}
+
+/// Group messages into turns following the conversation tree.
+///
+/// Messages are linked by `uuid`/`parent_uuid`. The live branch is the path
+/// from the last message back to the root; sibling subtrees (rewinds, retries)
+/// are emitted just before the point where the live branch continues, one
+/// level deeper and marked abandoned. Sessions without ids (other agents,
+/// rows indexed before threading) come out as a single linear branch.
+pub fn build_turns<'a>(messages: &'a [MessageRow], tool_calls: &'a [ToolCallRow]) -> Vec<Turn<'a>> {
+    let parents = parent_indices(messages);
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); messages.len()];
+    let mut roots = Vec::new();
+    for (i, parent) in parents.iter().enumerate() {
+        match parent {
+            Some(p) => children[*p].push(i),
+            None => roots.push(i),
+        }
+    }
+
+    let live = live_set(&parents);
+    let mut items = Vec::new();
+    let main_root = roots.iter().copied().find(|r| live[*r]);
+    for &root in &roots {
+        if Some(root) != main_root {
+            walk(root, 1, true, &children, &live, &mut items);
+        }
+    }
+    if let Some(root) = main_root {
+        walk(root, 0, false, &children, &live, &mut items);
+    }
+
+    let mut turns: Vec<Turn<'a>> = Vec::new();
+    for item in items {
+        let msg = &messages[item.idx];
+        let continues = turns.last().is_some_and(|t| {
+            msg.role != "user" && !item.branch_start && t.depth == item.depth && t.abandoned == item.abandoned
+        });
+        if continues {
+            if let Some(t) = turns.last_mut() {
+                t.replies.push(msg);
+            }
+            continue;
+        }
+        let is_prompt = msg.role == "user";
+        turns.push(Turn {
+            prompt: is_prompt.then_some(msg),
+            replies: if is_prompt { Vec::new() } else { vec![msg] },
+            tool_calls: Vec::new(),
+            depth: item.depth,
+            abandoned: item.abandoned,
+            branch_start: item.branch_start,
+        });
+    }
+
+    let mut turn_of: HashMap<&str, usize> = HashMap::new();
+    for (t, turn) in turns.iter().enumerate() {
+        for m in turn.messages() {
+            if let Some(ref uuid) = m.uuid {
+                turn_of.insert(uuid.as_str(), t);
+            }
+        }
+    }
+    for tc in tool_calls {
+        if let Some(&t) = tc.message_uuid.as_deref().and_then(|u| turn_of.get(u)) {
+            turns[t].tool_calls.push(tc);
+        }
+    }
+
+    turns
+}
+
+/// The messages of the live branch only, in order (abandoned branches dropped).
+pub fn live_branch(messages: &[MessageRow]) -> Vec<&MessageRow> {
+    let parents = parent_indices(messages);
+    let live = live_set(&parents);
+    messages
+        .iter()
+        .zip(live)
+        .filter(|(_, on_live)| *on_live)
+        .map(|(m, _)| m)
+        .collect()
+}
+
+/// Number of abandoned branches (forks off any message) in a conversation.
+pub fn branch_count(turns: &[Turn]) -> usize {
+    turns.iter().filter(|t| t.branch_start).count()
+}
+
+/// One-line description of a turn's tool calls, e.g. `Edit src/db.rs, Bash (+2 more)`.
+pub fn tool_call_summary(tool_calls: &[&ToolCallRow]) -> String {
+    const SHOWN: usize = 4;
+    let mut parts: Vec<String> = tool_calls
+        .iter()
+        .take(SHOWN)
+        .map(|tc| match tc.rel_path.as_deref().or(tc.file_path.as_deref()) {
+            Some(path) => format!("{} {}", tc.tool_name, path),
+            None => tc.tool_name.clone(),
+        })
+        .collect();
+    if tool_calls.len() > SHOWN {
+        parts.push(format!("(+{} more)", tool_calls.len() - SHOWN));
+    }
+    parts.join(", ")
+}
+
+/// Parent index for each message. Parents always precede children in the
+/// stored order, which also rules out cycles.
+fn parent_indices(messages: &[MessageRow]) -> Vec<Option<usize>> {
+    if messages.iter().all(|m| m.uuid.is_none()) {
+        return (0..messages.len()).map(|i| i.checked_sub(1)).collect();
+    }
+
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut parents = Vec::with_capacity(messages.len());
+    for (i, m) in messages.iter().enumerate() {
+        let parent = match (&m.uuid, &m.parent_uuid) {
+            (Some(_), Some(p)) => index.get(p.as_str()).copied(),
+            (Some(_), None) => None,
+            // Untracked message inside a threaded session: follow the previous one
+            (None, _) => i.checked_sub(1),
+        };
+        parents.push(parent);
+        if let Some(ref uuid) = m.uuid {
+            index.insert(uuid.as_str(), i);
+        }
+    }
+    parents
+}
+
+/// Marks the path from the last message back to its root.
+fn live_set(parents: &[Option<usize>]) -> Vec<bool> {
+    let mut live = vec![false; parents.len()];
+    let mut node = parents.len().checked_sub(1);
+    while let Some(i) = node {
+        live[i] = true;
+        node = parents[i];
+    }
+    live
+}
+
+fn walk(
+    start: usize,
+    depth: usize,
+    abandoned: bool,
+    children: &[Vec<usize>],
+    live: &[bool],
+    out: &mut Vec<Item>,
+) {
+    let mut node = Some(start);
+    let mut first = abandoned;
+    while let Some(i) = node {
+        out.push(Item {
+            idx: i,
+            depth,
+            abandoned,
+            branch_start: first,
+        });
+        first = false;
+
+        let kids = &children[i];
+        // Continue along the live child, or the latest attempt inside a dead branch
+        let next = kids
+            .iter()
+            .copied()
+            .find(|k| live[*k])
+            .or_else(|| kids.last().copied());
+        for &kid in kids {
+            if Some(kid) != next {
+                walk(kid, depth + 1, true, children, live, out);
+            }
+        }
+        node = next;
+    }
+}
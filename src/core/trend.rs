@@ -0,0 +1,124 @@
+use crate::core::clock;
+use crate::core::db::{parse_datetime, Database};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+/// Weekly session counts and active time per agent and per project
+#[derive(Debug, Clone)]
+pub struct Trend {
+    /// First day of each week, oldest first
+    pub weeks: Vec<NaiveDate>,
+    pub total: Series,
+    pub agents: Vec<Series>,
+    pub projects: Vec<Series>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    /// One value per entry of `Trend::weeks`
+    pub sessions: Vec<i64>,
+    pub active_secs: Vec<i64>,
+}
+
+impl Series {
+    fn new(name: &str, weeks: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            sessions: vec![0; weeks],
+            active_secs: vec![0; weeks],
+        }
+    }
+
+    fn add(&mut self, week: usize, active_secs: i64) {
+        self.sessions[week] += 1;
+        self.active_secs[week] += active_secs;
+    }
+
+    pub fn total_sessions(&self) -> i64 {
+        self.sessions.iter().sum()
+    }
+
+    pub fn total_active_secs(&self) -> i64 {
+        self.active_secs.iter().sum()
+    }
+}
+
+/// The last `weeks` calendar weeks, ending with this one. Weeks start on the
+/// configured `week_start`; sessions count toward the week they started in.
+pub fn weekly_trend(
+    db: &Database,
+    weeks: usize,
+    agent: Option<&str>,
+    project: Option<&str>,
+    author: Option<&str>,
+) -> Result<Trend> {
+    let weeks = weeks.max(1);
+    let calendar = clock::calendar();
+    let first_week = calendar.week_of(calendar.today()) - Duration::weeks(weeks as i64 - 1);
+    let week_starts: Vec<NaiveDate> = (0..weeks)
+        .map(|i| first_week + Duration::weeks(i as i64))
+        .collect();
+
+    let from = calendar.day_start(first_week);
+    let sessions = db.list_all_sessions(agent, project, author, Some(from), None)?;
+
+    let mut total = Series::new("all sessions", weeks);
+    let mut agents: HashMap<String, Series> = HashMap::new();
+    let mut projects: HashMap<String, Series> = HashMap::new();
+    for session in &sessions {
+        let Some(started) = session.started_at.as_deref().and_then(parse_datetime) else {
+            continue;
+        };
+        let week = ((calendar.day_of(started) - first_week).num_days() / 7) as usize;
+        if week >= weeks {
+            continue;
+        }
+        let active = session.active_secs.unwrap_or(0);
+        total.add(week, active);
+        agents
+            .entry(session.agent.clone())
+            .or_insert_with(|| Series::new(&session.agent, weeks))
+            .add(week, active);
+        let project = session.project_name.as_deref().unwrap_or("unknown");
+        projects
+            .entry(project.to_string())
+            .or_insert_with(|| Series::new(project, weeks))
+            .add(week, active);
+    }
+
+    Ok(Trend {
+        weeks: week_starts,
+        total,
+        agents: by_size(agents),
+        projects: by_size(projects),
+    })
+}
+
+fn by_size(series: HashMap<String, Series>) -> Vec<Series> {
+    let mut series: Vec<Series> = series.into_values().collect();
+    series.sort_by(|a, b| {
+        b.total_sessions()
+            .cmp(&a.total_sessions())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    series
+}
+
+/// One block character per value, scaled to the largest value
+pub fn sparkline(values: &[i64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if v <= 0 || max == 0 {
+                ' '
+            } else {
+                let idx = ((v as f64 / max as f64) * (BARS.len() - 1) as f64).round() as usize;
+                BARS[idx.min(BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
@@ -7,33 +7,53 @@ mod core;
 mod mcp;
 mod tui;
 
-use crate::cli::{Cli, Commands};
+use crate::cli::{Cli, Commands, ReportAction, ReportArgs, SearchAction, SiteAction, TeamAction};
 use crate::config as cfg;
+use crate::core::activity;
+use crate::core::archive::{self, OnConflict, PathMapping};
+use crate::core::bundle::{self, BundleSelection};
+use crate::core::clock;
 use crate::core::context::{self, DetailLevel};
-use crate::core::db::{parse_duration, Database};
+use crate::core::crypto;
+use crate::core::html;
+use crate::core::convert;
+use crate::core::db::{parse_duration, Cursor, Database, SessionFilter, SessionRow};
 use crate::core::indexer;
-use crate::core::report::{self, ReportFormat};
+use crate::core::inject::{self, InjectTarget};
+use crate::core::outcome::{self, Outcome};
+use crate::core::redact::{self, FindingSummary, Redactor};
+use crate::core::related;
+use crate::core::report::{self, standup, GroupBy, ReportFormat};
+use crate::core::schedule;
 use crate::core::search::{self, SearchOptions};
+use crate::core::team::{self, Identity};
+use crate::core::thread;
+use crate::core::trend;
 use anyhow::{bail, Result};
 use chrono::Utc;
 use clap::Parser;
 use std::io::Write;
+use std::path::Path;
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    if cli.team {
+        cfg::set_team_mode();
+    }
+    clock::set(cfg::calendar());
 
     match cli.command {
         None => {
             // Launch TUI
-            tui::run_tui()?;
+            tui::run_tui(cli.no_redact)?;
         }
-        Some(cmd) => run_command(cmd, cli.json)?,
+        Some(cmd) => run_command(cmd, cli.json, cli.no_redact)?,
     }
 
     Ok(())
 }
 
-fn run_command(cmd: Commands, json_output: bool) -> Result<()> {
+fn run_command(cmd: Commands, json_output: bool, no_redact: bool) -> Result<()> {
     match cmd {
         Commands::Setup => cmd_setup(),
         Commands::Index { agent, rebuild } => cmd_index(agent, rebuild),
@@ -42,7 +62,12 @@ fn run_command(cmd: Commands, json_output: bool) -> Result<()> {
             project,
             last,
             query,
-        } => cmd_list(agent, project, last, query, json_output),
+            author,
+            outcome,
+            limit,
+            page,
+            after,
+        } => cmd_list(agent, project, author, outcome, last, query, limit, page, after, json_output, no_redact),
         Commands::Resume {
             session_id,
             last,
@@ -56,8 +81,14 @@ fn run_command(cmd: Commands, json_output: bool) -> Result<()> {
             project,
             last,
             file,
-        } => cmd_history(keyword, agent, project, last, file, json_output),
-        Commands::Show { session_id, files } => cmd_show(&session_id, files, json_output),
+            author,
+            limit,
+            page,
+            after,
+        } => cmd_history(keyword, agent, project, author, last, file, limit, page, after, json_output, no_redact),
+        Commands::Search { action } => cmd_search(action, json_output, no_redact),
+        Commands::Related { session_id, limit } => cmd_related(&session_id, limit, json_output, no_redact),
+        Commands::Show { session_id, files } => cmd_show(&session_id, files, json_output, no_redact),
         Commands::Tag {
             session_id,
             tags,
@@ -69,34 +100,106 @@ fn run_command(cmd: Commands, json_output: bool) -> Result<()> {
             interactive,
         } => cmd_clean(older_than, agent, interactive),
         Commands::Report {
-            day,
+            action: Some(ReportAction::RunScheduled { name, force, dry_run }),
+            ..
+        } => cmd_report_run_scheduled(name.as_deref(), force, dry_run, no_redact, json_output),
+        Commands::Report { action: None, args } => cmd_report(args, no_redact),
+        Commands::Standup {
             date,
-            week,
-            month,
-            quarter,
-            from,
-            to,
             project,
-            output,
+            author,
             format,
             summarize,
-        } => cmd_report(day, date, week, month, quarter, from, to, project, output, format, summarize),
+            clipboard,
+        } => cmd_standup(date, project, author, format, summarize, clipboard, no_redact),
+        Commands::Stats {
+            trend,
+            agent,
+            project,
+            author,
+        } => cmd_stats(&trend, agent, project, author, json_output),
         Commands::Export {
-            session_id,
+            session_ids,
             clipboard,
             stdout,
             detail,
-        } => cmd_export(&session_id, clipboard, stdout, &detail),
-        Commands::Inject { session_id, auto } => cmd_inject(session_id, auto),
-        Commands::Serve { mcp } => cmd_serve(mcp),
+            template,
+            max_tokens,
+            format,
+            project,
+            since,
+            tag,
+        } => {
+            let selection = bundle_selection(session_ids, project, since, tag)?;
+            match format.as_str() {
+                "markdown" | "md" => {
+                    cmd_export(selection, clipboard, stdout, detail.as_deref(), template.as_deref(), max_tokens, no_redact)
+                }
+                "html" => cmd_export_html(selection, clipboard, stdout, no_redact),
+                other => bail!("Unknown export format: {} (use markdown or html)", other),
+            }
+        }
+        Commands::Inject {
+            session_ids,
+            auto,
+            target,
+            remove,
+            project,
+            since,
+            tag,
+        } => cmd_inject(
+            bundle_selection(session_ids, project, since, tag)?,
+            auto,
+            &target,
+            remove,
+            no_redact,
+        ),
+        Commands::ScanSecrets { agent, project } => cmd_scan_secrets(agent, project, json_output),
+        Commands::Convert { session_id, to } => cmd_convert(&session_id, &to, json_output),
+        Commands::Archive {
+            output,
+            since,
+            project,
+            agent,
+            tag,
+            redact,
+        } => {
+            let filter = SessionFilter {
+                agent,
+                project,
+                tag,
+                from: since_to_date(since)?,
+                ..Default::default()
+            };
+            cmd_archive(output, &filter, redact, json_output)
+        }
+        Commands::Import {
+            path,
+            map_path,
+            replace,
+        } => cmd_import(&path, &map_path, replace, json_output),
+        Commands::Team { action } => cmd_team(action, no_redact, json_output),
+        Commands::Site { action } => cmd_site(action, no_redact),
+        Commands::Lock { keyfile } => cmd_lock(keyfile),
+        Commands::Unlock {
+            keyfile,
+            duration,
+            decrypt,
+        } => cmd_unlock(keyfile, &duration, decrypt),
+        Commands::Serve { mcp } => cmd_serve(mcp, no_redact),
         Commands::Config { edit } => cmd_config(edit),
     }
 }
 
 fn open_db() -> Result<Database> {
+    if cfg::team_mode() {
+        return Database::open_read_only(&cfg::db_path());
+    }
     cfg::ensure_data_dir()?;
     let db_path = cfg::db_path();
-    Database::open(&db_path)
+    let mut db = Database::open(&db_path)?;
+    db.apply_config()?;
+    Ok(db)
 }
 
 // ── Setup ──
@@ -250,56 +353,99 @@ fn cmd_index(agent: Option<String>, rebuild: bool) -> Result<()> {
         let total_updated: usize = results.iter().map(|r| r.sessions_updated).sum();
         println!("✓ {} new, {} updated", total_new, total_updated);
     }
+    db.refresh_outcomes()?;
 
     Ok(())
 }
 
 // ── List ──
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_list(
     agent: Option<String>,
     project: Option<String>,
+    author: Option<String>,
+    outcome: Option<String>,
     last: Option<String>,
     query: Option<String>,
+    limit: usize,
+    page: Option<usize>,
+    after: Option<String>,
     json_output: bool,
+    no_redact: bool,
 ) -> Result<()> {
+    if let Some(ref o) = outcome {
+        if Outcome::from_str(o).is_none() {
+            bail!("Unknown --outcome '{}' (expected: success, failed, abandoned, in-progress)", o);
+        }
+    }
     let db = open_db()?;
+    if outcome.is_some() {
+        db.refresh_outcomes()?;
+    }
 
-    let from = last.as_ref().and_then(|d| {
-        parse_duration(d).map(|dur| Utc::now() - dur)
-    });
-
-    let sessions = db.list_sessions(
-        agent.as_deref(),
-        project.as_deref(),
-        from,
-        None,
-        200,
-    )?;
+    let from = last.as_deref().and_then(|d| clock::calendar().since(d));
+
+    use fuzzy_matcher::skim::SkimMatcherV2;
+    use fuzzy_matcher::FuzzyMatcher;
+    let matcher = SkimMatcherV2::default();
+    let match_score = |s: &SessionRow| {
+        let q = query.as_deref()?;
+        let text = format!(
+            "{} {} {}",
+            s.project_name.as_deref().unwrap_or(""),
+            s.summary.as_deref().unwrap_or(""),
+            s.agent
+        );
+        matcher.fuzzy_match(&text, q)
+    };
 
-    // Apply fuzzy filter if query provided
-    let sessions = if let Some(ref q) = query {
-        use fuzzy_matcher::skim::SkimMatcherV2;
-        use fuzzy_matcher::FuzzyMatcher;
-        let matcher = SkimMatcherV2::default();
-        let mut scored: Vec<_> = sessions
-            .into_iter()
-            .filter_map(|s| {
-                let text = format!(
-                    "{} {} {}",
-                    s.project_name.as_deref().unwrap_or(""),
-                    s.summary.as_deref().unwrap_or(""),
-                    s.agent
-                );
-                matcher.fuzzy_match(&text, q).map(|score| (s, score))
-            })
-            .collect();
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
-        scored.into_iter().map(|(s, _)| s).collect()
+    let mut fetch = |cursor: Option<&Cursor>| match outcome {
+        Some(ref o) => {
+            let filter = SessionFilter {
+                agent: agent.clone(),
+                project: project.clone(),
+                author: author.clone(),
+                outcome: Outcome::from_str(o).map(|o| o.as_str().to_string()),
+                from,
+                ..Default::default()
+            };
+            db.query_sessions_page(&filter, cursor, limit)
+        }
+        None => db.list_sessions_page(agent.as_deref(), project.as_deref(), author.as_deref(), from, None, cursor, limit),
+    };
+    // The fuzzy filter runs while paging so the cursor follows the matches
+    let result = if query.is_some() {
+        search::fetch_page(page, after.as_deref(), |cursor| {
+            search::fetch_filtered_page(cursor, limit, &mut fetch, |s| match_score(s).is_some())
+        })?
     } else {
-        sessions
+        search::fetch_page(page, after.as_deref(), &mut fetch)?
     };
+    let mut sessions = result.items;
+    if query.is_some() {
+        sessions.sort_by_cached_key(|s| std::cmp::Reverse(match_score(s)));
+    }
+
+    print_sessions(&sessions, json_output, &Redactor::load(no_redact)?)?;
+    print_next_cursor(result.next_cursor.as_ref(), json_output);
+    Ok(())
+}
+
+/// Tell the user how to fetch the next page. In JSON mode this goes to
+/// stderr so stdout stays a plain array.
+fn print_next_cursor(cursor: Option<&Cursor>, json_output: bool) {
+    let Some(cursor) = cursor else {
+        return;
+    };
+    if json_output {
+        eprintln!("next_cursor: {}", cursor.encode());
+    } else {
+        println!("\nMore results: rerun with --after {}", cursor.encode());
+    }
+}
 
+fn print_sessions(sessions: &[SessionRow], json_output: bool, redactor: &Redactor) -> Result<()> {
     if json_output {
         let json_sessions: Vec<serde_json::Value> = sessions
             .iter()
@@ -309,9 +455,14 @@ fn cmd_list(
                     "agent": s.agent,
                     "project": s.project_name,
                     "project_path": s.project_path,
-                    "summary": s.summary,
+                    "author": s.author,
+                    "machine": s.machine,
+                    "summary": s.summary.as_deref().map(|t| redactor.redact(t)),
                     "started_at": s.started_at,
                     "message_count": s.message_count,
+                    "active_secs": s.active_secs,
+                    "outcome": s.outcome,
+                    "outcome_reason": s.outcome_reason,
                     "tags": s.tags,
                 })
             })
@@ -323,30 +474,32 @@ fn cmd_list(
             return Ok(());
         }
 
-        println!(
-            "{:<12} {:<14} {:<20} {:<6} {}",
-            "ID", "AGENT", "PROJECT", "MSGS", "SUMMARY"
-        );
-        println!("{}", "-".repeat(80));
-        for s in &sessions {
+        println!("ID           AGENT          PROJECT              MSGS   ACTIVE   SUMMARY");
+        println!("{}", "-".repeat(89));
+        for s in sessions {
             let short_id = &s.id[..s.id.len().min(10)];
-            let summary: String = s
-                .summary
-                .as_deref()
-                .unwrap_or("")
+            let summary: String = redactor
+                .redact(s.summary.as_deref().unwrap_or(""))
                 .chars()
                 .take(40)
                 .collect();
+            let active = s.active_secs.map(activity::format_active).unwrap_or_default();
             println!(
-                "{:<12} {:<14} {:<20} {:<6} {}",
+                "{:<12} {:<14} {:<20} {:<6} {:<8} {}",
                 short_id,
                 s.agent,
                 s.project_name.as_deref().unwrap_or("?"),
                 s.message_count,
+                active,
                 summary
             );
         }
-        println!("\n{} sessions", sessions.len());
+        let total_active: i64 = sessions.iter().filter_map(|s| s.active_secs).sum();
+        println!(
+            "\n{} sessions, {} active",
+            sessions.len(),
+            activity::format_active(total_active)
+        );
     }
 
     Ok(())
@@ -393,7 +546,7 @@ fn cmd_resume(
             }
         }
         adapters::traits::AgentType::Codex => {
-            format!("cd {} && codex --resume {}", project_dir, session.id)
+            format!("cd {} && codex resume {}", project_dir, resume_id)
         }
         adapters::traits::AgentType::Cursor => {
             format!("cursor {}", project_dir)
@@ -433,22 +586,45 @@ fn cmd_cd(session_id: &str) -> Result<()> {
 
 // ── History ──
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_history(
     keyword: Option<String>,
     agent: Option<String>,
     project: Option<String>,
+    author: Option<String>,
     last: Option<String>,
     file: Option<String>,
+    limit: usize,
+    page: Option<usize>,
+    after: Option<String>,
     json_output: bool,
+    no_redact: bool,
 ) -> Result<()> {
     let db = open_db()?;
+    let redactor = Redactor::load(no_redact)?;
+
+    let from = last.as_deref().and_then(|d| clock::calendar().since(d));
 
     if let Some(ref file_path) = file {
-        let sessions = search::search_by_file(&db, file_path, 50)?;
+        let mut names = search::file_name_variants(file_path);
+        let filter = SessionFilter {
+            agent,
+            project,
+            file: Some(names.remove(0)),
+            file_aliases: names,
+            author,
+            from,
+            ..Default::default()
+        };
+        let result = search::fetch_page(page, after.as_deref(), |cursor| {
+            db.query_sessions_page(&filter, cursor, limit)
+        })?;
+        let sessions = result.items;
         if json_output {
             println!("{}", serde_json::to_string_pretty(&serde_json::json!(
                 sessions.iter().map(|s| serde_json::json!({
-                    "id": s.id, "agent": s.agent, "project": s.project_name, "summary": s.summary,
+                    "id": s.id, "agent": s.agent, "project": s.project_name,
+                    "summary": s.summary.as_deref().map(|t| redactor.redact(t)),
                 })).collect::<Vec<_>>()
             ))?);
         } else {
@@ -459,33 +635,34 @@ fn cmd_history(
                     &s.id[..s.id.len().min(10)],
                     s.agent,
                     s.project_name.as_deref().unwrap_or("?"),
-                    s.summary.as_deref().unwrap_or("")
+                    redactor.redact(s.summary.as_deref().unwrap_or(""))
                 );
             }
         }
+        print_next_cursor(result.next_cursor.as_ref(), json_output);
         return Ok(());
     }
 
     if keyword.is_none() {
         // Launch history TUI (just use main TUI for now)
-        return tui::run_tui();
+        return tui::run_tui(no_redact);
     }
 
-    let from = last.as_ref().and_then(|d| {
-        parse_duration(d).map(|dur| Utc::now() - dur)
-    });
-
     let opts = SearchOptions {
         keyword,
         agent,
         project,
+        author,
         from,
         to: None,
         file: None,
-        limit: 50,
+        limit,
     };
 
-    let results = search::search_history(&db, &opts)?;
+    let page = search::fetch_page(page, after.as_deref(), |cursor| {
+        search::search_history_page(&db, &opts, cursor)
+    })?;
+    let results = page.items;
 
     if json_output {
         let json_results: Vec<serde_json::Value> = results
@@ -496,7 +673,7 @@ fn cmd_history(
                     "agent": r.agent,
                     "project": r.project_name,
                     "role": r.role,
-                    "content": r.content.chars().take(200).collect::<String>(),
+                    "content": redactor.redact(&r.content).chars().take(200).collect::<String>(),
                     "timestamp": r.timestamp,
                 })
             })
@@ -505,7 +682,7 @@ fn cmd_history(
     } else {
         println!("Found {} matches\n", results.len());
         for r in &results {
-            let content: String = r.content.chars().take(100).collect();
+            let content: String = redactor.redact(&r.content).chars().take(100).collect();
             let content = content.replace('\n', " ");
             println!(
                 "  {} | {} | {}",
@@ -518,13 +695,124 @@ fn cmd_history(
         }
     }
 
+    print_next_cursor(page.next_cursor.as_ref(), json_output);
+    Ok(())
+}
+
+// ── Search ──
+
+fn cmd_search(action: SearchAction, json_output: bool, no_redact: bool) -> Result<()> {
+    let db = open_db()?;
+
+    match action {
+        SearchAction::Save { name, query } => {
+            // Validate before saving so typos surface immediately
+            search::parse_query(&query)?;
+            db.save_search(&name, &query)?;
+            println!("Saved search '{}': {}", name, query);
+        }
+        SearchAction::List => {
+            let saved = db.list_saved_searches()?;
+            let mut rows = Vec::new();
+            for s in &saved {
+                let count = search::parse_query(&s.query)
+                    .and_then(|f| db.count_sessions(&f))
+                    .unwrap_or(0);
+                rows.push((s, count));
+            }
+
+            if json_output {
+                let json_rows: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|(s, count)| {
+                        serde_json::json!({
+                            "name": s.name,
+                            "query": s.query,
+                            "count": count,
+                            "created_at": s.created_at,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json_rows)?);
+            } else if rows.is_empty() {
+                println!("No saved searches. Create one with `ail search save <name> <query>`.");
+            } else {
+                for (s, count) in &rows {
+                    println!("  {:<20} {:>5}  {}", s.name, count, s.query);
+                }
+            }
+        }
+        SearchAction::Run {
+            name,
+            limit,
+            page,
+            after,
+        } => {
+            let result = search::fetch_page(page, after.as_deref(), |cursor| {
+                search::run_saved_search_page(&db, &name, cursor, limit)
+            })?;
+            print_sessions(&result.items, json_output, &Redactor::load(no_redact)?)?;
+            print_next_cursor(result.next_cursor.as_ref(), json_output);
+        }
+        SearchAction::Delete { name } => {
+            if db.delete_saved_search(&name)? {
+                println!("Deleted saved search '{}'", name);
+            } else {
+                bail!("Saved search not found: {}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ── Related ──
+
+fn cmd_related(session_id: &str, limit: usize, json_output: bool, no_redact: bool) -> Result<()> {
+    let db = open_db()?;
+    let redactor = Redactor::load(no_redact)?;
+    let results = related::find_related(&db, session_id, limit)?;
+
+    if json_output {
+        let json_results: Vec<serde_json::Value> = results
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "id": r.session.id,
+                    "agent": r.session.agent,
+                    "project": r.session.project_name,
+                    "summary": r.session.summary.as_deref().map(|t| redactor.redact(t)),
+                    "started_at": r.session.started_at,
+                    "score": r.score,
+                    "reasons": r.reasons,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_results)?);
+    } else if results.is_empty() {
+        println!("No related sessions found for {}", session_id);
+    } else {
+        println!("Sessions related to {}:\n", session_id);
+        for r in &results {
+            println!(
+                "  {} | {} | {} | {}",
+                &r.session.id[..r.session.id.len().min(10)],
+                r.session.agent,
+                r.session.project_name.as_deref().unwrap_or("?"),
+                redactor.redact(r.session.summary.as_deref().unwrap_or(""))
+            );
+            println!("    {}", r.reason());
+        }
+    }
+
     Ok(())
 }
 
 // ── Show ──
 
-fn cmd_show(session_id: &str, files_only: bool, json_output: bool) -> Result<()> {
+fn cmd_show(session_id: &str, files_only: bool, json_output: bool, no_redact: bool) -> Result<()> {
     let db = open_db()?;
+    let redactor = Redactor::load(no_redact)?;
 
     let session = db
         .get_session(session_id)?
@@ -569,42 +857,70 @@ fn cmd_show(session_id: &str, files_only: bool, json_output: bool) -> Result<()>
         }
     } else {
         let messages = db.get_messages(session_id)?;
+        let tool_calls = db.get_tool_calls(session_id)?;
+        let turns = thread::build_turns(&messages, &tool_calls);
 
         if json_output {
-            let json_msgs: Vec<serde_json::Value> = messages
+            let json_turns: Vec<serde_json::Value> = turns
                 .iter()
-                .map(|m| {
+                .map(|t| {
                     serde_json::json!({
-                        "role": m.role,
-                        "content": m.content,
-                        "timestamp": m.timestamp,
+                        "depth": t.depth,
+                        "abandoned": t.abandoned,
+                        "messages": t.messages().map(|m| serde_json::json!({
+                            "uuid": m.uuid,
+                            "parent_uuid": m.parent_uuid,
+                            "role": m.role,
+                            "content": redactor.redact(&m.content),
+                            "timestamp": m.timestamp,
+                        })).collect::<Vec<_>>(),
+                        "tool_calls": t.tool_calls.iter().map(|tc| serde_json::json!({
+                            "tool": tc.tool_name,
+                            "file_path": tc.file_path,
+                        })).collect::<Vec<_>>(),
                     })
                 })
                 .collect();
-            println!("{}", serde_json::to_string_pretty(&json_msgs)?);
+            println!("{}", serde_json::to_string_pretty(&json_turns)?);
         } else {
             println!(
-                "Session: {} | {} | {}\n",
+                "Session: {} | {} | {}",
                 session_id,
                 session.agent,
                 session.project_name.as_deref().unwrap_or("?")
             );
-            for m in &messages {
-                if m.role == "tool" {
-                    continue;
+            if let Some(ref outcome) = session.outcome {
+                println!("Outcome: {} ({})", outcome, session.outcome_reason.as_deref().unwrap_or("-"));
+            }
+            match thread::branch_count(&turns) {
+                0 => println!(),
+                1 => println!("(1 abandoned branch)\n"),
+                n => println!("({} abandoned branches)\n", n),
+            }
+            for turn in &turns {
+                let indent = "    ".repeat(turn.depth);
+                if turn.branch_start {
+                    println!("{}┆ abandoned branch\n", indent);
+                }
+                for m in turn.messages() {
+                    if m.role == "tool" {
+                        continue;
+                    }
+                    let label = if m.role == "user" { "You" } else { "AI" };
+                    let ts = m
+                        .timestamp
+                        .as_ref()
+                        .and_then(|t| clock::format_local(t, " (%H:%M)"))
+                        .unwrap_or_default();
+                    println!("{}--- {}{} ---", indent, label, ts);
+                    for line in redactor.redact(&m.content).lines() {
+                        println!("{}{}", indent, line);
+                    }
+                    println!();
+                }
+                if !turn.tool_calls.is_empty() {
+                    println!("{}[tools] {}\n", indent, thread::tool_call_summary(&turn.tool_calls));
                 }
-                let label = if m.role == "user" { "You" } else { "AI" };
-                let ts = m
-                    .timestamp
-                    .as_ref()
-                    .map(|t| {
-                        chrono::DateTime::parse_from_rfc3339(t)
-                            .map(|d| d.format(" (%H:%M)").to_string())
-                            .unwrap_or_default()
-                    })
-                    .unwrap_or_default();
-                println!("--- {}{} ---", label, ts);
-                println!("{}\n", m.content);
             }
         }
     }
@@ -660,21 +976,41 @@ fn cmd_clean(
 
 // ── Report ──
 
-fn cmd_report(
-    day: bool,
-    date: Option<String>,
-    week: bool,
-    month: bool,
-    quarter: Option<String>,
-    from: Option<String>,
-    to: Option<String>,
-    project: Option<String>,
-    output: Option<String>,
-    format: String,
-    summarize: bool,
-) -> Result<()> {
+fn cmd_report(args: ReportArgs, no_redact: bool) -> Result<()> {
+    let ReportArgs {
+        day,
+        date,
+        week,
+        month,
+        quarter,
+        from,
+        to,
+        project,
+        author,
+        output,
+        format,
+        summarize,
+        refine_outcomes,
+        compare,
+        group_by,
+    } = args;
+    let compare = match compare.as_deref() {
+        None => false,
+        Some("previous") => true,
+        Some(other) => bail!("Unknown --compare target '{}' (expected: previous)", other),
+    };
+    let Some(group_by) = GroupBy::from_str(&group_by) else {
+        bail!("Unknown --group-by '{}' (expected: project, agent, branch, ticket, tag)", group_by);
+    };
+    let fmt = ReportFormat::from_str(&format)?;
     let db = open_db()?;
+    db.refresh_outcomes()?;
     let config = cfg::load_config()?;
+    let redactor = if no_redact {
+        Redactor::disabled()
+    } else {
+        Redactor::new(&config.redact)?
+    };
 
     let period = report::resolve_period(
         day,
@@ -689,12 +1025,18 @@ fn cmd_report(
     // Run LLM summarization if --summarize flag or config enabled
     if summarize || config.report.summarize.enabled {
         let (from_dt, to_dt) = report::period_to_range(&period);
-        let sessions = db.list_sessions(None, project.as_deref(), Some(from_dt), Some(to_dt), 1000)?;
+        let sessions = db.list_all_sessions(None, project.as_deref(), author.as_deref(), Some(from_dt), Some(to_dt))?;
         crate::core::summarize::summarize_sessions(&db, &sessions, &config.report.summarize)?;
     }
+    if refine_outcomes || config.report.refine_outcomes {
+        let (from_dt, to_dt) = report::period_to_range(&period);
+        let sessions = db.list_all_sessions(None, project.as_deref(), author.as_deref(), Some(from_dt), Some(to_dt))?;
+        outcome::refine_outcomes(&db, &sessions, &config.report.summarize)?;
+    }
 
-    let fmt = ReportFormat::from_str(&format);
-    let report_content = report::generate_report(&db, &period, project.as_deref(), fmt)?;
+    let report_content =
+        report::generate_report(&db, &period, project.as_deref(), author.as_deref(), fmt, compare, group_by)?;
+    let report_content = redactor.redact_output(&report_content);
 
     if let Some(ref out_path) = output {
         std::fs::write(out_path, &report_content)?;
@@ -706,12 +1048,238 @@ fn cmd_report(
     Ok(())
 }
 
+fn cmd_standup(
+    date: Option<String>,
+    project: Option<String>,
+    author: Option<String>,
+    format: String,
+    summarize: bool,
+    clipboard: bool,
+    no_redact: bool,
+) -> Result<()> {
+    let today = match date.as_deref() {
+        Some(d) => chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| anyhow::anyhow!("Invalid --date '{}' (expected YYYY-MM-DD)", d))?,
+        None => clock::calendar().today(),
+    };
+    let fmt = ReportFormat::from_str(&format)?;
+    let db = open_db()?;
+    db.refresh_outcomes()?;
+    let config = cfg::load_config()?;
+    let redactor = if no_redact {
+        Redactor::disabled()
+    } else {
+        Redactor::new(&config.redact)?
+    };
+
+    if summarize || config.report.summarize.enabled {
+        let (from_dt, to_dt) = clock::calendar().days(standup::previous_workday(today), today);
+        let sessions = db.list_all_sessions(None, project.as_deref(), author.as_deref(), Some(from_dt), Some(to_dt))?;
+        crate::core::summarize::summarize_sessions(&db, &sessions, &config.report.summarize)?;
+    }
+
+    let content = standup::generate_standup(&db, today, project.as_deref(), author.as_deref(), fmt)?;
+    let content = redactor.redact_output(&content);
+
+    if clipboard {
+        let mut clip = arboard::Clipboard::new()?;
+        clip.set_text(&content)?;
+        println!("Standup copied to clipboard");
+    } else {
+        println!("{}", content.trim_end());
+    }
+    Ok(())
+}
+
+fn cmd_report_run_scheduled(
+    name: Option<&str>,
+    force: bool,
+    dry_run: bool,
+    no_redact: bool,
+    json_output: bool,
+) -> Result<()> {
+    let config = cfg::load_config()?;
+    if config.report.schedules.is_empty() {
+        println!("No schedules configured. Add one under [report.schedules.<name>] with `ail config --edit`.");
+        return Ok(());
+    }
+    let db = open_db()?;
+    db.refresh_outcomes()?;
+    let redactor = if no_redact {
+        Redactor::disabled()
+    } else {
+        Redactor::new(&config.redact)?
+    };
+
+    let outcomes = schedule::run_scheduled(&db, &config, &redactor, name, force, dry_run)?;
+    let failed = outcomes
+        .iter()
+        .filter(|o| matches!(o.status, schedule::Status::Failed(_)))
+        .count();
+
+    if json_output {
+        let rows: Vec<serde_json::Value> = outcomes
+            .iter()
+            .map(|o| {
+                let (status, error) = match &o.status {
+                    schedule::Status::Delivered => ("delivered", None),
+                    schedule::Status::AlreadyDelivered => ("already_delivered", None),
+                    schedule::Status::Due => ("due", None),
+                    schedule::Status::Failed(e) => ("failed", Some(e.as_str())),
+                };
+                serde_json::json!({
+                    "name": o.name,
+                    "period": o.period,
+                    "destination": o.destination,
+                    "status": status,
+                    "error": error,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        for o in &outcomes {
+            let status = match &o.status {
+                schedule::Status::Delivered => "delivered",
+                schedule::Status::AlreadyDelivered => "already delivered",
+                schedule::Status::Due => "due",
+                schedule::Status::Failed(_) => "FAILED",
+            };
+            println!("{:<16} {:<10} {:<18} {}", o.name, o.period, status, o.destination);
+            if let schedule::Status::Failed(ref e) = o.status {
+                println!("  {}", e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} scheduled reports failed", failed, outcomes.len());
+    }
+    Ok(())
+}
+
+// ── Stats ──
+
+fn cmd_stats(
+    span: &str,
+    agent: Option<String>,
+    project: Option<String>,
+    author: Option<String>,
+    json_output: bool,
+) -> Result<()> {
+    let db = open_db()?;
+    let span = parse_duration(span)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --trend span: {} (e.g. 12w, 6m)", span))?;
+    let weeks = (span.num_days() as usize).div_ceil(7);
+    let trend = trend::weekly_trend(&db, weeks, agent.as_deref(), project.as_deref(), author.as_deref())?;
+
+    if json_output {
+        let series_json = |series: &[trend::Series]| -> serde_json::Value {
+            series
+                .iter()
+                .map(|s| {
+                    (
+                        s.name.clone(),
+                        serde_json::json!({
+                            "sessions": s.sessions,
+                            "active_secs": s.active_secs,
+                        }),
+                    )
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        };
+        let output = serde_json::json!({
+            "weeks": trend.weeks.iter().map(|w| w.format("%Y-%m-%d").to_string()).collect::<Vec<_>>(),
+            "total": {
+                "sessions": trend.total.sessions,
+                "active_secs": trend.total.active_secs,
+            },
+            "agents": series_json(&trend.agents),
+            "projects": series_json(&trend.projects),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let (first, last) = (trend.weeks[0], trend.weeks[trend.weeks.len() - 1]);
+    println!(
+        "Weekly trend, {} weeks ({} ~ {})",
+        trend.weeks.len(),
+        first.format("%Y-%m-%d"),
+        (last + chrono::Duration::days(6)).format("%Y-%m-%d")
+    );
+    println!();
+    print_trend_rows("", std::slice::from_ref(&trend.total), trend.weeks.len());
+    print_trend_rows("AGENT", &trend.agents, trend.weeks.len());
+    print_trend_rows("PROJECT", &trend.projects, trend.weeks.len());
+    Ok(())
+}
+
+fn print_trend_rows(heading: &str, series: &[trend::Series], weeks: usize) {
+    const MAX_ROWS: usize = 10;
+    if series.is_empty() {
+        return;
+    }
+    if !heading.is_empty() {
+        println!(
+            "{:<20} {:<w$} {:>8}   {:<w$} {:>8}",
+            heading,
+            "SESSIONS",
+            "",
+            "ACTIVE",
+            "",
+            w = weeks
+        );
+    }
+    for s in series.iter().take(MAX_ROWS) {
+        let name: String = s.name.chars().take(20).collect();
+        println!(
+            "{:<20} {} {:>8}   {} {:>7.1}h",
+            name,
+            trend::sparkline(&s.sessions),
+            s.total_sessions(),
+            trend::sparkline(&s.active_secs),
+            activity::hours(s.total_active_secs())
+        );
+    }
+    if series.len() > MAX_ROWS {
+        println!("{:<20} (+{} more, see --json)", "", series.len() - MAX_ROWS);
+    }
+    println!();
+}
+
 // ── Export ──
 
-fn cmd_export(session_id: &str, clipboard: bool, stdout: bool, detail: &str) -> Result<()> {
+fn cmd_export(
+    selection: BundleSelection,
+    clipboard: bool,
+    stdout: bool,
+    detail: Option<&str>,
+    template: Option<&str>,
+    max_tokens: Option<usize>,
+    no_redact: bool,
+) -> Result<()> {
     let db = open_db()?;
-    let detail_level = DetailLevel::from_str(detail);
-    let content = context::export_context(&db, session_id, detail_level)?;
+    let redactor = Redactor::load(no_redact)?;
+    let detail_level = DetailLevel::from_str(detail.unwrap_or("summary"));
+    let content = if is_bundle(&selection) {
+        if template.is_some() || max_tokens.is_some() {
+            bail!("--template and --max-tokens apply to single-session exports only");
+        }
+        let sessions = bundle::select_sessions(&db, &selection)?;
+        bundle::export_bundle(&db, &sessions, detail_level)?
+    } else {
+        let Some(session_id) = selection.session_ids.first() else {
+            bail!("Provide a session ID, or --project/--since/--tag to bundle sessions");
+        };
+        match (template, max_tokens) {
+            (_, Some(budget)) => context::export_context_budgeted(&db, session_id, budget)?,
+            (Some(name), None) => context::export_context_with_template(&db, session_id, detail_level, name)?,
+            (None, None) => context::export_context(&db, session_id, detail_level)?,
+        }
+    };
+    let content = redactor.redact(&content);
 
     if clipboard {
         let mut clip = arboard::Clipboard::new()?;
@@ -728,30 +1296,429 @@ fn cmd_export(session_id: &str, clipboard: bool, stdout: bool, detail: &str) ->
     Ok(())
 }
 
+fn cmd_export_html(selection: BundleSelection, clipboard: bool, stdout: bool, no_redact: bool) -> Result<()> {
+    if is_bundle(&selection) {
+        bail!("--format html exports a single session; use `ail site build` for several");
+    }
+    let Some(session_id) = selection.session_ids.first() else {
+        bail!("Provide a session ID to export");
+    };
+    let db = open_db()?;
+    let redactor = Redactor::load(no_redact)?;
+    let content = html::export_session_html(&db, session_id, &redactor)?;
+
+    if clipboard {
+        let mut clip = arboard::Clipboard::new()?;
+        clip.set_text(&content)?;
+        println!("HTML copied to clipboard");
+    } else if stdout {
+        print!("{}", content);
+    } else {
+        let short: String = session_id.chars().take(8).collect();
+        let path = format!("ail-session-{}.html", short);
+        std::fs::write(&path, &content)?;
+        println!("Session exported to {}", path);
+    }
+
+    Ok(())
+}
+
+fn bundle_selection(
+    session_ids: Vec<String>,
+    project: Option<String>,
+    since: Option<String>,
+    tag: Option<String>,
+) -> Result<BundleSelection> {
+    Ok(BundleSelection {
+        session_ids,
+        project,
+        since: since_to_date(since)?,
+        tag,
+    })
+}
+
+/// `--since 3d` as the point in time it refers to
+fn since_to_date(since: Option<String>) -> Result<Option<chrono::DateTime<Utc>>> {
+    since.map(|d| clock::since_ago(&d)).transpose()
+}
+
+/// More than one session, or filters that may match several
+fn is_bundle(selection: &BundleSelection) -> bool {
+    selection.session_ids.len() > 1
+        || selection.project.is_some()
+        || selection.since.is_some()
+        || selection.tag.is_some()
+}
+
+// ── Archive / Import ──
+
+fn cmd_archive(output: Option<String>, filter: &SessionFilter, redact: bool, json_output: bool) -> Result<()> {
+    let db = open_db()?;
+    let redactor = if redact {
+        // --redact applies the configured rules even when redaction is off by default
+        let mut config = cfg::load_config()?.redact;
+        config.enabled = true;
+        Redactor::new(&config)?
+    } else {
+        Redactor::disabled()
+    };
+    let sessions = db.query_all_sessions(filter)?;
+    let path = output.unwrap_or_else(|| format!("ail-archive-{}.ail.tar.zst", Utc::now().format("%Y%m%d")));
+    let manifest = archive::write_archive(&db, &sessions, Path::new(&path), &redactor, redact, &Identity::load())?;
+
+    if json_output {
+        let out = serde_json::json!({ "path": path, "manifest": manifest });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        println!(
+            "Archived {} sessions ({} messages, {} tool calls) to {}",
+            manifest.sessions, manifest.messages, manifest.tool_calls, path
+        );
+        if redact {
+            println!("Secrets and PII were redacted.");
+        }
+    }
+    Ok(())
+}
+
+fn cmd_import(path: &str, map_path: &[String], replace: bool, json_output: bool) -> Result<()> {
+    let mappings = map_path
+        .iter()
+        .map(|m| PathMapping::parse(m))
+        .collect::<Result<Vec<_>>>()?;
+    let on_conflict = if replace { OnConflict::Replace } else { OnConflict::Skip };
+    let db = open_db()?;
+    let result = archive::import_archive(&db, Path::new(path), &mappings, on_conflict)?;
+
+    if json_output {
+        let out = serde_json::json!({
+            "imported": result.imported,
+            "replaced": result.replaced,
+            "skipped": result.skipped,
+            "manifest": result.manifest,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        println!(
+            "Imported {} sessions from {} (archived {} by ail {})",
+            result.imported + result.replaced,
+            path,
+            result.manifest.created_at.get(..10).unwrap_or(&result.manifest.created_at),
+            result.manifest.ail_version
+        );
+        if result.replaced > 0 {
+            println!("  {} replaced existing sessions", result.replaced);
+        }
+        if result.skipped > 0 {
+            println!("  {} already indexed, skipped (use --replace to overwrite)", result.skipped);
+        }
+    }
+    Ok(())
+}
+
+// ── Team ──
+
+fn cmd_team(action: TeamAction, no_redact: bool, json_output: bool) -> Result<()> {
+    match action {
+        TeamAction::Export { output, since } => {
+            if cfg::team_mode() {
+                bail!("`ail team export` shares your own index; drop --team");
+            }
+            let db = open_db()?;
+            let config = cfg::load_config()?;
+            let me = Identity::local(&config.team);
+            let redactor = if no_redact {
+                Redactor::disabled()
+            } else {
+                // Shared sessions are always redacted, even if output redaction is off
+                let mut redact = config.redact;
+                redact.enabled = true;
+                Redactor::new(&redact)?
+            };
+
+            let filter = SessionFilter {
+                from: since_to_date(since)?,
+                ..Default::default()
+            };
+            let mut excluded = 0;
+            let sessions: Vec<SessionRow> = db
+                .query_all_sessions(&filter)?
+                .into_iter()
+                // Only your own sessions: not ones imported from someone else
+                .filter(|s| s.author.as_deref().is_none_or(|a| a == me.author))
+                .filter(|s| {
+                    let keep = team::is_shareable(s, &config.team);
+                    excluded += usize::from(!keep);
+                    keep
+                })
+                .collect();
+
+            let path = output.unwrap_or_else(|| {
+                format!("ail-team-{}-{}.ail.tar.zst", me.author, Utc::now().format("%Y%m%d"))
+            });
+            let manifest = archive::write_archive(&db, &sessions, Path::new(&path), &redactor, !no_redact, &me)?;
+
+            if json_output {
+                let out = serde_json::json!({ "path": path, "excluded": excluded, "manifest": manifest });
+                println!("{}", serde_json::to_string_pretty(&out)?);
+            } else {
+                println!("Exported {} sessions as {} to {}", manifest.sessions, me.author, path);
+                if excluded > 0 {
+                    println!("  {} excluded by [team] exclude_projects / exclude_tags", excluded);
+                }
+            }
+            Ok(())
+        }
+        TeamAction::Merge { archives, into } => {
+            let db_path = into.map(std::path::PathBuf::from).unwrap_or_else(cfg::team_db_path);
+            let archives: Vec<std::path::PathBuf> = archives.iter().map(std::path::PathBuf::from).collect();
+            let results = team::merge_archives(&db_path, &archives)?;
+
+            if json_output {
+                let out: Vec<serde_json::Value> = archives
+                    .iter()
+                    .zip(&results)
+                    .map(|(path, r)| {
+                        serde_json::json!({
+                            "archive": path,
+                            "imported": r.imported,
+                            "replaced": r.replaced,
+                            "created_at": r.manifest.created_at,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&out)?);
+            } else {
+                for (path, r) in archives.iter().zip(&results) {
+                    println!("  {}: {} new, {} updated", path.display(), r.imported, r.replaced);
+                }
+                println!("Team index: {}", db_path.display());
+                println!("Query it with --team, e.g. `ail --team list --author <name>`");
+            }
+            Ok(())
+        }
+    }
+}
+
+// ── Site ──
+
+fn cmd_site(action: SiteAction, no_redact: bool) -> Result<()> {
+    match action {
+        SiteAction::Build {
+            out,
+            project,
+            since,
+            tag,
+            agent,
+        } => {
+            let db = open_db()?;
+            let redactor = Redactor::load(no_redact)?;
+            let filter = SessionFilter {
+                agent,
+                project,
+                tag,
+                from: since_to_date(since)?,
+                ..Default::default()
+            };
+            let sessions = db.query_all_sessions(&filter)?;
+            let out_dir = Path::new(&out);
+            let count = html::build_site(&db, &sessions, out_dir, &redactor)?;
+            println!("Built site with {} sessions in {}", count, out_dir.display());
+            println!("Open {}", out_dir.join("index.html").display());
+            Ok(())
+        }
+    }
+}
+
 // ── Inject ──
 
-fn cmd_inject(session_id: Option<String>, auto: bool) -> Result<()> {
+fn cmd_inject(
+    selection: BundleSelection,
+    auto: bool,
+    target: &str,
+    remove: bool,
+    no_redact: bool,
+) -> Result<()> {
+    let targets = InjectTarget::parse_list(target)?;
+    let cwd = std::env::current_dir()?;
+
+    if remove {
+        for t in &targets {
+            if inject::remove_injected(&cwd, *t)? {
+                println!("Removed ail context from {}", t.relative_path());
+            }
+        }
+        return Ok(());
+    }
+
     let db = open_db()?;
+    let redactor = Redactor::load(no_redact)?;
+    let (source, written) = if auto {
+        let (sid, written) = inject::auto_inject(&db, &targets, &redactor)?;
+        (format!("session {}", sid), written)
+    } else if is_bundle(&selection) {
+        let sessions = bundle::select_sessions(&db, &selection)?;
+        let content = redactor.redact(&bundle::export_bundle(&db, &sessions, DetailLevel::Summary)?);
+        let written = targets
+            .iter()
+            .map(|t| inject::inject_content(&content, &cwd, *t))
+            .collect::<Result<Vec<_>>>()?;
+        (format!("a bundle of {} sessions", sessions.len()), written)
+    } else if let Some(sid) = selection.session_ids.first() {
+        let written = inject::inject_context(&db, sid, &cwd, &targets, &redactor)?;
+        (format!("session {}", sid), written)
+    } else {
+        bail!("Provide a session ID, --project/--since/--tag, or use --auto");
+    };
 
-    if auto {
-        let sid = context::auto_inject(&db)?;
-        println!("Auto-injected context from session {} into CLAUDE.md", sid);
-    } else if let Some(ref sid) = session_id {
-        let cwd = std::env::current_dir()?;
-        context::inject_context(&db, sid, &cwd)?;
-        println!("Injected context from session {} into CLAUDE.md", sid);
+    for path in written {
+        let shown = path.strip_prefix(&cwd).unwrap_or(&path);
+        println!("Injected context from {} into {}", source, shown.display());
+    }
+
+    Ok(())
+}
+
+// ── Scan Secrets ──
+
+fn cmd_scan_secrets(agent: Option<String>, project: Option<String>, json_output: bool) -> Result<()> {
+    let db = open_db()?;
+    // Scanning ignores --no-redact and `enabled`: it only reports, never prints the values
+    let mut config = cfg::load_config()?.redact;
+    config.enabled = true;
+    let redactor = Redactor::new(&config)?;
+
+    let sessions = db.list_all_sessions(agent.as_deref(), project.as_deref(), None, None, None)?;
+    let mut hits: Vec<(SessionRow, Vec<FindingSummary>)> = Vec::new();
+    for session in sessions {
+        let messages = db.get_messages(&session.id)?;
+        let findings = redact::summarize_findings(messages.iter().flat_map(|m| redactor.scan(&m.content)));
+        if !findings.is_empty() {
+            hits.push((session, findings));
+        }
+    }
+
+    if json_output {
+        let out: Vec<serde_json::Value> = hits
+            .iter()
+            .map(|(s, findings)| {
+                serde_json::json!({
+                    "session_id": s.id,
+                    "agent": s.agent,
+                    "project": s.project_name,
+                    "started_at": s.started_at,
+                    "findings": findings.iter().map(|f| serde_json::json!({
+                        "rule": f.rule,
+                        "count": f.count,
+                        "preview": f.preview,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No likely secrets found.");
+        return Ok(());
+    }
+    println!("{:<12} {:<14} {:<20} FINDINGS", "ID", "AGENT", "PROJECT");
+    println!("{}", "-".repeat(80));
+    for (s, findings) in &hits {
+        let findings: Vec<String> = findings
+            .iter()
+            .map(|f| format!("{} ×{} ({})", f.rule, f.count, f.preview))
+            .collect();
+        println!(
+            "{:<12} {:<14} {:<20} {}",
+            &s.id[..s.id.len().min(10)],
+            s.agent,
+            s.project_name.as_deref().unwrap_or("-"),
+            findings.join(", ")
+        );
+    }
+    println!("\n{} sessions with likely secrets", hits.len());
+    Ok(())
+}
+
+// ── Convert ──
+
+fn cmd_convert(session_id: &str, to: &str, json_output: bool) -> Result<()> {
+    let target = adapters::traits::AgentType::from_str(to)
+        .ok_or_else(|| anyhow::anyhow!("Unknown agent: {} (use codex or claude-code)", to))?;
+    let db = open_db()?;
+    let converted = convert::convert_session(&db, session_id, target)?;
+
+    if json_output {
+        let out = serde_json::json!({
+            "session_id": converted.session_id,
+            "path": converted.path,
+            "resume_command": converted.resume_command,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
     } else {
-        bail!("Provide a session ID or use --auto");
+        println!("Converted {} to a {} session: {}", session_id, target.display_name(), converted.path.display());
+        println!("Resume with: {}", converted.resume_command);
+    }
+    Ok(())
+}
+
+// ── Lock / Unlock ──
+
+fn cmd_lock(keyfile: Option<String>) -> Result<()> {
+    let mut db = open_db()?;
+
+    if db.is_encrypted() {
+        if crypto::clear_cached_key()? {
+            println!("Index locked.");
+        } else {
+            println!("Index is already locked.");
+        }
+        return Ok(());
     }
 
+    let secret = crypto::read_secret(keyfile.as_deref().map(Path::new), true)?;
+    println!("Encrypting message content...");
+    db.enable_encryption(&secret)?;
+    crypto::clear_cached_key()?;
+    println!("Index encrypted and locked. Run `ail unlock` to use it.");
+    Ok(())
+}
+
+fn cmd_unlock(keyfile: Option<String>, duration: &str, decrypt: bool) -> Result<()> {
+    let Some(ttl) = parse_duration(duration) else {
+        bail!("Invalid duration: {} (use e.g. 8h, 1d)", duration);
+    };
+    let mut db = open_db()?;
+    if !db.is_encrypted() {
+        bail!("The index is not encrypted (run `ail lock` to encrypt it)");
+    }
+
+    if db.is_locked() {
+        let secret = crypto::read_secret(keyfile.as_deref().map(Path::new), false)?;
+        let key = db.unlock(&secret)?;
+        if !decrypt {
+            let path = crypto::cache_key(&key, ttl)?;
+            println!("Index unlocked for {} (key cached at {}).", duration, path.display());
+        }
+    } else if !decrypt {
+        println!("Index is already unlocked.");
+    }
+
+    if decrypt {
+        db.disable_encryption()?;
+        crypto::clear_cached_key()?;
+        println!("Index decrypted; encryption is off.");
+    }
     Ok(())
 }
 
 // ── Serve ──
 
-fn cmd_serve(mcp: bool) -> Result<()> {
+fn cmd_serve(mcp: bool, no_redact: bool) -> Result<()> {
     if mcp {
-        mcp::server::run_mcp_server()?;
+        mcp::server::run_mcp_server(Redactor::load(no_redact)?)?;
     } else {
         println!("MCP Server Setup Guide");
         println!("======================");
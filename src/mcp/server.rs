@@ -1,13 +1,27 @@
 use crate::config;
+use crate::core::bundle::{self, BundleSelection};
 use crate::core::context::{self, DetailLevel};
-use crate::core::db::Database;
+use crate::core::search;
+use crate::core::thread;
+use crate::core::db::{Cursor, Database};
+use crate::core::redact::Redactor;
 use anyhow::Result;
 use serde_json::{json, Value};
+use std::collections::HashSet;
 use std::io::{self, BufRead, Read, Write};
 
-pub fn run_mcp_server() -> Result<()> {
+pub fn run_mcp_server(redactor: Redactor) -> Result<()> {
     let db_path = config::db_path();
-    let db = Database::open(&db_path)?;
+    let db = if config::team_mode() {
+        Database::open_read_only(&db_path)?
+    } else {
+        let mut db = Database::open(&db_path)?;
+        db.apply_config()?;
+        db
+    };
+    if db.is_locked() {
+        anyhow::bail!("The index is locked. Run `ail unlock` before starting the MCP server.");
+    }
 
     let stdin = io::stdin();
     let mut stdout = io::stdout();
@@ -67,7 +81,7 @@ pub fn run_mcp_server() -> Result<()> {
         let response = match method {
             "initialize" => handle_initialize(id.clone()),
             "tools/list" => handle_tools_list(id.clone()),
-            "tools/call" => handle_tools_call(id.clone(), &params, &db),
+            "tools/call" => handle_tools_call(id.clone(), &params, &db, &redactor),
             "notifications/initialized" => continue, // No response needed
             "ping" => json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
             _ => json!({
@@ -113,16 +127,18 @@ fn handle_tools_list(id: Option<Value>) -> Value {
             "tools": [
                 {
                     "name": "search_sessions",
-                    "description": "Search AI coding sessions by keyword, agent, date range, and project",
+                    "description": "Search AI coding sessions by keyword, agent, author, date range, and project. Returns {results, next_cursor}; pass next_cursor back as cursor for more.",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
                             "keyword": { "type": "string", "description": "Search keyword" },
                             "agent": { "type": "string", "description": "Agent filter: claude-code, codex, cursor" },
+                            "author": { "type": "string", "description": "Author filter (team index)" },
                             "from": { "type": "string", "description": "Start date (ISO 8601)" },
                             "to": { "type": "string", "description": "End date (ISO 8601)" },
                             "project": { "type": "string", "description": "Project path filter" },
-                            "limit": { "type": "integer", "description": "Max results (default 20)" }
+                            "limit": { "type": "integer", "description": "Max results per page (default 20)" },
+                            "cursor": { "type": "string", "description": "next_cursor from a previous call, to fetch the following page" }
                         }
                     }
                 },
@@ -161,13 +177,15 @@ fn handle_tools_list(id: Option<Value>) -> Value {
                 },
                 {
                     "name": "get_stats",
-                    "description": "Get statistics for a time period: session count, by agent, by project, file changes",
+                    "description": "Get statistics for a time period: session count, active time, by agent, by project, by author, file changes",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
                             "from": { "type": "string", "description": "Start date (ISO 8601)" },
                             "to": { "type": "string", "description": "End date (ISO 8601)" },
-                            "project": { "type": "string", "description": "Project path filter" }
+                            "agent": { "type": "string", "description": "Agent filter (claude-code, codex, cursor)" },
+                            "project": { "type": "string", "description": "Project path filter" },
+                            "author": { "type": "string", "description": "Author filter (team index)" }
                         }
                     }
                 },
@@ -178,11 +196,48 @@ fn handle_tools_list(id: Option<Value>) -> Value {
                         "type": "object",
                         "properties": {
                             "session_id": { "type": "string", "description": "Session ID" },
-                            "detail": { "type": "string", "description": "Detail level: full, summary, minimal" }
+                            "detail": { "type": "string", "description": "Detail level: full, summary, minimal" },
+                            "template": { "type": "string", "description": "Template: default, handoff, pr, postmortem, or a user template name" },
+                            "max_tokens": { "type": "integer", "description": "Token budget: pack request, final state, files, errors and recent exchanges into about this many tokens (not combined with detail or template)" }
                         },
                         "required": ["session_id"]
                     }
                 },
+                {
+                    "name": "export_context_bundle",
+                    "description": "Merge several sessions (by ID and/or project, time and tag filters; filters narrow the given IDs) into one chronological context document with combined changed files and a per-session timeline",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "session_ids": { "type": "array", "items": { "type": "string" }, "description": "Session IDs to include" },
+                            "project": { "type": "string", "description": "Only sessions for this project path or name" },
+                            "since": { "type": "string", "description": "Only sessions since a duration ago (3d, 2w) or a date (ISO 8601)" },
+                            "tag": { "type": "string", "description": "Only sessions with this tag" },
+                            "detail": { "type": "string", "description": "Detail level: full, summary, minimal" }
+                        }
+                    }
+                },
+                {
+                    "name": "list_saved_searches",
+                    "description": "List the user's saved searches (named session filters) with current match counts",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "run_saved_search",
+                    "description": "Run one of the user's saved searches by name. Returns {results, next_cursor}; pass next_cursor back as cursor for more.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "description": "Saved search name" },
+                            "limit": { "type": "integer", "description": "Max results per page (default 20)" },
+                            "cursor": { "type": "string", "description": "next_cursor from a previous call, to fetch the following page" }
+                        },
+                        "required": ["name"]
+                    }
+                },
                 {
                     "name": "get_full_session",
                     "description": "Get full untruncated session content for summarization. Use this to get complete session messages, file changes, and metadata so the calling agent can generate its own summary.",
@@ -199,7 +254,7 @@ fn handle_tools_list(id: Option<Value>) -> Value {
     })
 }
 
-fn handle_tools_call(id: Option<Value>, params: &Value, db: &Database) -> Value {
+fn handle_tools_call(id: Option<Value>, params: &Value, db: &Database, redactor: &Redactor) -> Value {
     let tool_name = params
         .get("name")
         .and_then(|n| n.as_str())
@@ -213,6 +268,9 @@ fn handle_tools_call(id: Option<Value>, params: &Value, db: &Database) -> Value
         "get_session_summary" => tool_get_session_summary(&arguments, db),
         "get_stats" => tool_get_stats(&arguments, db),
         "export_context" => tool_export_context(&arguments, db),
+        "export_context_bundle" => tool_export_context_bundle(&arguments, db),
+        "list_saved_searches" => tool_list_saved_searches(db),
+        "run_saved_search" => tool_run_saved_search(&arguments, db),
         "get_full_session" => tool_get_full_session(&arguments, db),
         _ => Err(anyhow::anyhow!("Unknown tool: {}", tool_name)),
     };
@@ -224,7 +282,7 @@ fn handle_tools_call(id: Option<Value>, params: &Value, db: &Database) -> Value
             "result": {
                 "content": [{
                     "type": "text",
-                    "text": content
+                    "text": redactor.redact_output(&content)
                 }]
             }
         }),
@@ -254,46 +312,68 @@ fn tool_search_sessions(args: &Value, db: &Database) -> Result<String> {
         .and_then(|t| t.as_str())
         .and_then(crate::core::db::parse_datetime);
     let project = args.get("project").and_then(|p| p.as_str());
+    let author = args.get("author").and_then(|a| a.as_str());
     let limit = args
         .get("limit")
         .and_then(|l| l.as_u64())
         .unwrap_or(20) as usize;
+    let cursor = cursor_arg(args)?;
 
     if let Some(kw) = keyword {
-        let results = db.search_messages(kw, agent, project, from, to, limit)?;
-        let output: Vec<Value> = results
+        let page = db.search_messages_page(kw, agent, project, author, from, to, cursor.as_ref(), limit)?;
+        let output: Vec<Value> = page
+            .items
             .iter()
             .map(|r| {
                 json!({
                     "session_id": r.session_id,
                     "agent": r.agent,
                     "project": r.project_name,
+                    "author": r.author,
                     "role": r.role,
                     "content_preview": r.content.chars().take(200).collect::<String>(),
                     "started_at": r.started_at,
                 })
             })
             .collect();
-        Ok(serde_json::to_string_pretty(&output)?)
+        paged_output(output, page.next_cursor)
     } else {
-        let sessions = db.list_sessions(agent, project, from, to, limit)?;
-        let output: Vec<Value> = sessions
+        let page = db.list_sessions_page(agent, project, author, from, to, cursor.as_ref(), limit)?;
+        let output: Vec<Value> = page
+            .items
             .iter()
             .map(|s| {
                 json!({
                     "id": s.id,
                     "agent": s.agent,
                     "project": s.project_name,
+                    "author": s.author,
                     "summary": s.summary,
                     "started_at": s.started_at,
                     "message_count": s.message_count,
+                    "outcome": s.outcome,
                 })
             })
             .collect();
-        Ok(serde_json::to_string_pretty(&output)?)
+        paged_output(output, page.next_cursor)
     }
 }
 
+fn cursor_arg(args: &Value) -> Result<Option<Cursor>> {
+    args.get("cursor")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(Cursor::decode)
+        .transpose()
+}
+
+fn paged_output(results: Vec<Value>, next_cursor: Option<Cursor>) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&json!({
+        "results": results,
+        "next_cursor": next_cursor.map(|c| c.encode()),
+    }))?)
+}
+
 fn tool_get_session_history(args: &Value, db: &Database) -> Result<String> {
     let session_id = args
         .get("session_id")
@@ -301,11 +381,15 @@ fn tool_get_session_history(args: &Value, db: &Database) -> Result<String> {
         .ok_or_else(|| anyhow::anyhow!("session_id is required"))?;
 
     let messages = db.get_messages(session_id)?;
+    let live: HashSet<i64> = thread::live_branch(&messages).iter().map(|m| m.id).collect();
     let output: Vec<Value> = messages
         .iter()
         .filter(|m| m.role != "tool")
         .map(|m| {
             json!({
+                "uuid": m.uuid,
+                "parent_uuid": m.parent_uuid,
+                "abandoned": !live.contains(&m.id),
                 "role": m.role,
                 "content": m.content,
                 "timestamp": m.timestamp,
@@ -366,6 +450,7 @@ fn tool_get_session_summary(args: &Value, db: &Database) -> Result<String> {
         "started_at": session.started_at,
         "ended_at": session.ended_at,
         "message_count": session.message_count,
+        "active_secs": session.active_secs,
         "files_created": session.files_created,
         "files_modified": session.files_modified,
         "files_deleted": session.files_deleted,
@@ -384,14 +469,20 @@ fn tool_get_stats(args: &Value, db: &Database) -> Result<String> {
         .get("to")
         .and_then(|t| t.as_str())
         .and_then(crate::core::db::parse_datetime);
+    let agent = args.get("agent").and_then(|a| a.as_str());
     let project = args.get("project").and_then(|p| p.as_str());
+    let author = args.get("author").and_then(|a| a.as_str());
 
-    let stats = db.get_stats(from, to, project)?;
+    let stats = db.get_stats(from, to, agent, project, author)?;
 
     let output = json!({
         "total_sessions": stats.total_sessions,
         "sessions_by_agent": stats.sessions_by_agent,
         "sessions_by_project": stats.sessions_by_project,
+        "sessions_by_author": stats.sessions_by_author,
+        "active_secs": stats.total_active_secs,
+        "active_secs_by_agent": stats.active_secs_by_agent,
+        "active_secs_by_project": stats.active_secs_by_project,
         "files_created": stats.total_files_created,
         "files_modified": stats.total_files_modified,
         "files_deleted": stats.total_files_deleted,
@@ -401,6 +492,80 @@ fn tool_get_stats(args: &Value, db: &Database) -> Result<String> {
     Ok(serde_json::to_string_pretty(&output)?)
 }
 
+fn tool_export_context_bundle(args: &Value, db: &Database) -> Result<String> {
+    let session_ids: Vec<String> = args
+        .get("session_ids")
+        .and_then(|ids| ids.as_array())
+        .map(|ids| ids.iter().filter_map(|id| id.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let since = args
+        .get("since")
+        .and_then(|s| s.as_str())
+        .map(crate::core::clock::since_ago)
+        .transpose()?;
+    let selection = BundleSelection {
+        session_ids,
+        project: args.get("project").and_then(|p| p.as_str()).map(String::from),
+        since,
+        tag: args.get("tag").and_then(|t| t.as_str()).map(String::from),
+    };
+    let detail = args
+        .get("detail")
+        .and_then(|d| d.as_str())
+        .unwrap_or("summary");
+
+    let sessions = bundle::select_sessions(db, &selection)?;
+    bundle::export_bundle(db, &sessions, DetailLevel::from_str(detail))
+}
+
+fn tool_list_saved_searches(db: &Database) -> Result<String> {
+    let saved = db.list_saved_searches()?;
+    let output: Vec<Value> = saved
+        .iter()
+        .map(|s| {
+            let count = search::parse_query(&s.query)
+                .and_then(|f| db.count_sessions(&f))
+                .unwrap_or(0);
+            json!({
+                "name": s.name,
+                "query": s.query,
+                "count": count,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+fn tool_run_saved_search(args: &Value, db: &Database) -> Result<String> {
+    let name = args
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| anyhow::anyhow!("name is required"))?;
+    let limit = args
+        .get("limit")
+        .and_then(|l| l.as_u64())
+        .unwrap_or(20) as usize;
+    let cursor = cursor_arg(args)?;
+
+    let page = search::run_saved_search_page(db, name, cursor.as_ref(), limit)?;
+    let output: Vec<Value> = page
+        .items
+        .iter()
+        .map(|s| {
+            json!({
+                "id": s.id,
+                "agent": s.agent,
+                "project": s.project_name,
+                "summary": s.summary,
+                "started_at": s.started_at,
+                "message_count": s.message_count,
+                "tags": s.tags,
+            })
+        })
+        .collect();
+    paged_output(output, page.next_cursor)
+}
+
 fn tool_get_full_session(args: &Value, db: &Database) -> Result<String> {
     let session_id = args
         .get("session_id")
@@ -415,11 +580,15 @@ fn tool_get_full_session(args: &Value, db: &Database) -> Result<String> {
     let tool_calls = db.get_tool_calls(session_id)?;
 
     // Build full untruncated messages
+    let live: HashSet<i64> = thread::live_branch(&messages).iter().map(|m| m.id).collect();
     let full_messages: Vec<Value> = messages
         .iter()
         .filter(|m| m.role != "tool")
         .map(|m| {
             json!({
+                "uuid": m.uuid,
+                "parent_uuid": m.parent_uuid,
+                "abandoned": !live.contains(&m.id),
                 "role": m.role,
                 "content": m.content,
                 "timestamp": m.timestamp,
@@ -455,6 +624,7 @@ fn tool_get_full_session(args: &Value, db: &Database) -> Result<String> {
         "started_at": session.started_at,
         "ended_at": session.ended_at,
         "message_count": session.message_count,
+        "active_secs": session.active_secs,
         "messages": full_messages,
         "files_changed": files,
         "tags": session.tags,
@@ -469,10 +639,19 @@ fn tool_export_context(args: &Value, db: &Database) -> Result<String> {
         .and_then(|s| s.as_str())
         .ok_or_else(|| anyhow::anyhow!("session_id is required"))?;
 
-    let detail = args
-        .get("detail")
-        .and_then(|d| d.as_str())
-        .unwrap_or("summary");
+    let detail = args.get("detail").and_then(|d| d.as_str());
+    let template = args.get("template").and_then(|t| t.as_str());
 
-    context::export_context(db, session_id, DetailLevel::from_str(detail))
+    if let Some(budget) = args.get("max_tokens").and_then(|t| t.as_u64()) {
+        if detail.is_some() || template.is_some() {
+            anyhow::bail!("max_tokens can't be combined with detail or template");
+        }
+        return context::export_context_budgeted(db, session_id, budget as usize);
+    }
+
+    let detail = detail.unwrap_or("summary");
+    match template {
+        Some(name) => context::export_context_with_template(db, session_id, DetailLevel::from_str(detail), name),
+        None => context::export_context(db, session_id, DetailLevel::from_str(detail)),
+    }
 }
@@ -1,6 +1,13 @@
 use crate::config;
+use crate::core::activity;
+use crate::core::clock;
 use crate::core::context::{self, DetailLevel};
-use crate::core::db::{Database, MessageRow, SessionRow, ToolCallRow};
+use crate::core::db::{Database, MessageRow, SavedSearchRow, SessionRow, ToolCallRow};
+use crate::core::redact::Redactor;
+use crate::core::related::{self, RelatedSession};
+use crate::core::search;
+use crate::core::thread;
+use crate::tui::dashboard::{self, Dashboard};
 use crate::tui::theme::Theme;
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
@@ -26,8 +33,10 @@ pub enum View {
     SessionList,
     SessionDetail,
     HistorySearch,
+    Related,
     ActionMenu,
     ConfirmDelete,
+    Dashboard,
 }
 
 pub struct App {
@@ -49,6 +58,10 @@ pub struct App {
     pub agent_filter_idx: usize,
     pub period_filter: Option<String>,
 
+    // Collections (saved searches pinned in the sidebar)
+    pub collections: Vec<(SavedSearchRow, i64)>,
+    pub collection_idx: usize,
+
     // Session detail
     pub detail_session_id: Option<String>,
     pub detail_messages: Vec<MessageRow>,
@@ -60,15 +73,28 @@ pub struct App {
     pub history_results: Vec<crate::core::db::SearchResult>,
     pub history_state: ListState,
 
+    // Related sessions
+    pub related_source: Option<String>,
+    pub related_results: Vec<RelatedSession>,
+    pub related_state: ListState,
+
     // Action menu
     pub action_items: Vec<String>,
     pub action_state: ListState,
+
+    // Dashboard (loaded when opened and when the agent filter changes)
+    pub dashboard: Option<Dashboard>,
+    /// Dashboard shows active time instead of session counts
+    pub dashboard_active: bool,
+
+    // Applied to exported context
+    pub redactor: Redactor,
 }
 
 const AGENTS: &[&str] = &["All", "claude-code", "codex", "cursor"];
 
 impl App {
-    pub fn new(db: Database) -> Self {
+    pub fn new(db: Database, redactor: Redactor) -> Self {
         Self {
             db,
             theme: Theme::dark(),
@@ -83,6 +109,8 @@ impl App {
             agent_filter: None,
             agent_filter_idx: 0,
             period_filter: None,
+            collections: Vec::new(),
+            collection_idx: 0,
             detail_session_id: None,
             detail_messages: Vec::new(),
             detail_tool_calls: Vec::new(),
@@ -90,25 +118,41 @@ impl App {
             history_input: String::new(),
             history_results: Vec::new(),
             history_state: ListState::default(),
+            related_source: None,
+            related_results: Vec::new(),
+            related_state: ListState::default(),
             action_items: vec![
                 "Resume session".to_string(),
                 "Export context".to_string(),
                 "Open project directory".to_string(),
                 "View conversation".to_string(),
+                "Find related sessions".to_string(),
                 "Delete session".to_string(),
             ],
             action_state: ListState::default(),
+            dashboard: None,
+            dashboard_active: false,
+            redactor,
         }
     }
 
     pub fn load_sessions(&mut self) -> Result<()> {
-        self.sessions = self.db.list_sessions(
-            self.agent_filter.as_deref(),
-            None,
-            None,
-            None,
-            500,
-        )?;
+        self.load_collections()?;
+        self.sessions = if let Some((saved, _)) = self.active_collection() {
+            let mut filter = search::parse_query(&saved.query)?;
+            if self.agent_filter.is_some() {
+                filter.agent = self.agent_filter.clone();
+            }
+            self.db.query_sessions(&filter, 500)?
+        } else {
+            self.db.list_sessions(
+                self.agent_filter.as_deref(),
+                None,
+                None,
+                None,
+                500,
+            )?
+        };
         self.apply_filter();
         if !self.filtered_indices.is_empty() {
             self.list_state.select(Some(0));
@@ -116,6 +160,31 @@ impl App {
         Ok(())
     }
 
+    /// Refresh saved searches and their live match counts.
+    fn load_collections(&mut self) -> Result<()> {
+        let saved = self.db.list_saved_searches()?;
+        self.collections = saved
+            .into_iter()
+            .map(|s| {
+                let count = search::parse_query(&s.query)
+                    .and_then(|f| self.db.count_sessions(&f))
+                    .unwrap_or(0);
+                (s, count)
+            })
+            .collect();
+        if self.collection_idx > self.collections.len() {
+            self.collection_idx = 0;
+        }
+        Ok(())
+    }
+
+    /// Index 0 means "no collection"; 1..=n select `collections[idx - 1]`.
+    fn active_collection(&self) -> Option<&(SavedSearchRow, i64)> {
+        self.collection_idx
+            .checked_sub(1)
+            .and_then(|i| self.collections.get(i))
+    }
+
     fn apply_filter(&mut self) {
         if self.search_input.is_empty() {
             self.filtered_indices = (0..self.sessions.len()).collect();
@@ -162,6 +231,18 @@ impl App {
         Ok(())
     }
 
+    fn open_related(&mut self) -> Result<()> {
+        if let Some(session) = self.selected_session() {
+            let sid = session.id.clone();
+            self.related_results = related::find_related(&self.db, &sid, 20)?;
+            self.related_source = Some(sid);
+            self.related_state
+                .select(if self.related_results.is_empty() { None } else { Some(0) });
+            self.view = View::Related;
+        }
+        Ok(())
+    }
+
     fn handle_key_session_list(&mut self, key: KeyEvent) -> Result<()> {
         if self.search_active {
             match key.code {
@@ -227,29 +308,66 @@ impl App {
                     let project = session.project_name.clone().unwrap_or_else(|| "session".to_string());
                     if let Ok(ctx) = context::export_context(&self.db, &sid, DetailLevel::Summary) {
                         let path = export_filename(&project);
-                        let _ = std::fs::write(&path, &ctx);
+                        let _ = std::fs::write(&path, self.redactor.redact(&ctx));
                         self.status_msg = Some(format!("Exported to {}", path));
                     }
                 }
             }
             KeyCode::Tab => {
-                // Cycle agent filter
-                self.agent_filter_idx = (self.agent_filter_idx + 1) % AGENTS.len();
-                self.agent_filter = if self.agent_filter_idx == 0 {
-                    None
-                } else {
-                    Some(AGENTS[self.agent_filter_idx].to_string())
-                };
+                self.cycle_agent_filter()?;
+            }
+            KeyCode::Char('c') if !self.collections.is_empty() => {
+                // Cycle pinned collections
+                self.collection_idx = (self.collection_idx + 1) % (self.collections.len() + 1);
                 self.load_sessions()?;
             }
             KeyCode::Char('d') => {
                 self.open_detail()?;
             }
+            KeyCode::Char('s') => {
+                self.open_related()?;
+            }
             KeyCode::Char('h') => {
                 self.view = View::HistorySearch;
                 self.history_input.clear();
                 self.history_results.clear();
             }
+            KeyCode::Char('g') => {
+                self.dashboard = Some(Dashboard::load(&self.db, self.agent_filter.as_deref())?);
+                self.view = View::Dashboard;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Step to the next agent filter; the list and dashboard share it.
+    fn cycle_agent_filter(&mut self) -> Result<()> {
+        self.agent_filter_idx = (self.agent_filter_idx + 1) % AGENTS.len();
+        self.agent_filter = if self.agent_filter_idx == 0 {
+            None
+        } else {
+            Some(AGENTS[self.agent_filter_idx].to_string())
+        };
+        self.load_sessions()?;
+        if self.dashboard.is_some() {
+            self.dashboard = Some(Dashboard::load(&self.db, self.agent_filter.as_deref())?);
+        }
+        Ok(())
+    }
+
+    fn handle_key_dashboard(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('g') => {
+                self.dashboard = None;
+                self.view = View::SessionList;
+            }
+            KeyCode::Tab => {
+                self.cycle_agent_filter()?;
+            }
+            KeyCode::Char('t') => {
+                self.dashboard_active = !self.dashboard_active;
+            }
             _ => {}
         }
         Ok(())
@@ -279,7 +397,7 @@ impl App {
                         .unwrap_or_else(|| "session".to_string());
                     if let Ok(ctx) = context::export_context(&self.db, sid, DetailLevel::Summary) {
                         let path = export_filename(&project);
-                        let _ = std::fs::write(&path, &ctx);
+                        let _ = std::fs::write(&path, self.redactor.redact(&ctx));
                         self.status_msg = Some(format!("Exported to {}", path));
                     }
                 }
@@ -332,6 +450,49 @@ impl App {
         Ok(())
     }
 
+    fn handle_key_related(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.view = View::SessionList;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let len = self.related_results.len();
+                if len > 0 {
+                    let i = self.related_state.selected().unwrap_or(0);
+                    self.related_state.select(Some((i + 1).min(len - 1)));
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let i = self.related_state.selected().unwrap_or(0);
+                self.related_state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::Enter => {
+                // Jump to the related session in the main list
+                if let Some(r) = self
+                    .related_state
+                    .selected()
+                    .and_then(|i| self.related_results.get(i))
+                {
+                    let target = r.session.id.clone();
+                    let pos = self
+                        .filtered_indices
+                        .iter()
+                        .position(|&idx| self.sessions[idx].id == target);
+                    match pos {
+                        Some(p) => self.list_state.select(Some(p)),
+                        None => {
+                            self.status_msg =
+                                Some("Session not in current list (clear filters)".to_string())
+                        }
+                    }
+                }
+                self.view = View::SessionList;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn handle_key_action_menu(&mut self, key: KeyEvent) -> Result<()> {
         match key.code {
             KeyCode::Esc | KeyCode::Char('q') => {
@@ -366,7 +527,7 @@ impl App {
                                 context::export_context(&self.db, &sid, DetailLevel::Summary)
                             {
                                 let path = export_filename(&project);
-                                let _ = std::fs::write(&path, &ctx);
+                                let _ = std::fs::write(&path, self.redactor.redact(&ctx));
                                 self.status_msg = Some(format!("Exported to {}", path));
                             }
                         }
@@ -386,6 +547,10 @@ impl App {
                         self.open_detail()?;
                     }
                     4 => {
+                        // Find related sessions
+                        self.open_related()?;
+                    }
+                    5 => {
                         // Delete — show confirmation
                         self.view = View::ConfirmDelete;
                     }
@@ -415,8 +580,10 @@ impl App {
                     View::SessionList => self.handle_key_session_list(key)?,
                     View::SessionDetail => self.handle_key_session_detail(key)?,
                     View::HistorySearch => self.handle_key_history(key)?,
+                    View::Related => self.handle_key_related(key)?,
                     View::ActionMenu => self.handle_key_action_menu(key)?,
                     View::ConfirmDelete => self.handle_key_confirm_delete(key)?,
+                    View::Dashboard => self.handle_key_dashboard(key)?,
                 }
             }
         }
@@ -447,6 +614,7 @@ impl App {
             View::SessionList => self.draw_session_list(frame),
             View::SessionDetail => self.draw_session_detail(frame),
             View::HistorySearch => self.draw_history_search(frame),
+            View::Related => self.draw_related(frame),
             View::ActionMenu => {
                 self.draw_session_list(frame);
                 self.draw_action_popup(frame);
@@ -455,7 +623,35 @@ impl App {
                 self.draw_session_list(frame);
                 self.draw_confirm_delete_popup(frame);
             }
+            View::Dashboard => self.draw_dashboard(frame),
+        }
+    }
+
+    fn draw_dashboard(&mut self, frame: &mut ratatui::Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // filter bar
+                Constraint::Min(10),  // charts
+                Constraint::Length(2), // status bar
+            ])
+            .split(frame.area());
+
+        let header = Paragraph::new(format!(" Agent: {}", AGENTS[self.agent_filter_idx])).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border_style())
+                .title(Span::styled(" ail dashboard ", self.theme.title_style())),
+        );
+        frame.render_widget(header, chunks[0]);
+
+        if let Some(ref dash) = self.dashboard {
+            dashboard::draw(frame, chunks[1], dash, self.dashboard_active, &self.theme);
         }
+
+        let status = Paragraph::new(" Tab: Agent | t: Sessions/active time | g/Esc: Back to sessions")
+            .style(self.theme.status_bar_style());
+        frame.render_widget(status, chunks[2]);
     }
 
     fn draw_session_list(&mut self, frame: &mut ratatui::Frame) {
@@ -480,10 +676,13 @@ impl App {
         };
 
         let agent_label = AGENTS[self.agent_filter_idx];
-        let filter_line = format!(
+        let mut filter_line = format!(
             "{}    Agent: {}",
             search_text, agent_label
         );
+        if let Some((saved, _)) = self.active_collection() {
+            filter_line.push_str(&format!("    Collection: {}", saved.name));
+        }
 
         let search_bar = Paragraph::new(filter_line).block(
             Block::default()
@@ -493,11 +692,22 @@ impl App {
         );
         frame.render_widget(search_bar, chunks[0]);
 
-        // Main content: session list + preview
+        // Main content: collections sidebar (if any) + session list + preview
+        let content_area = if self.collections.is_empty() {
+            chunks[1]
+        } else {
+            let side_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(26), Constraint::Min(20)])
+                .split(chunks[1]);
+            self.draw_collections(frame, side_chunks[0]);
+            side_chunks[1]
+        };
+
         let main_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
-            .split(chunks[1]);
+            .split(content_area);
 
         // Session list
         let items: Vec<ListItem> = self
@@ -515,10 +725,13 @@ impl App {
                     .as_ref()
                     .map(|t| format_time_ago(t))
                     .unwrap_or_default();
-                let summary = session
-                    .summary
-                    .as_deref()
-                    .unwrap_or("")
+                let active = session
+                    .active_secs
+                    .map(|secs| format!("  {}", activity::format_active(secs)))
+                    .unwrap_or_default();
+                let summary = self
+                    .redactor
+                    .redact(session.summary.as_deref().unwrap_or(""))
                     .chars()
                     .take(50)
                     .collect::<String>();
@@ -541,6 +754,7 @@ impl App {
                     agent_span,
                     Span::raw(format!("  {}  ", project)),
                     Span::styled(time_ago, self.theme.muted_style()),
+                    Span::styled(active, self.theme.muted_style()),
                 ]);
                 let line2 = Line::from(vec![
                     Span::raw(format!("  \"{}\"", summary)),
@@ -552,13 +766,18 @@ impl App {
             .collect();
 
         let count = self.filtered_indices.len();
+        let active: i64 = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&idx| self.sessions[idx].active_secs)
+            .sum();
         let list = List::new(items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(self.theme.border_style())
                     .title(Span::styled(
-                        format!(" SESSIONS  {}  ", count),
+                        format!(" SESSIONS  {}  ·  {} active ", count, activity::format_active(active)),
                         self.theme.title_style(),
                     )),
             )
@@ -575,12 +794,38 @@ impl App {
         } else if self.search_active {
             " Type to search | Enter: confirm | Esc: cancel".to_string()
         } else {
-            " j/k: Navigate | Enter: Actions | /: Search | Tab: Agent | d: Detail | e: Export | r: Resume | h: History | q: Quit".to_string()
+            " j/k: Navigate | Enter: Actions | /: Search | Tab: Agent | c: Collection | d: Detail | s: Similar | e: Export | r: Resume | h: History | g: Dashboard | q: Quit".to_string()
         };
         let status = Paragraph::new(help_text).style(self.theme.status_bar_style());
         frame.render_widget(status, chunks[2]);
     }
 
+    fn draw_collections(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let mut items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
+            " All sessions",
+            self.theme.muted_style(),
+        )))];
+        for (saved, count) in &self.collections {
+            items.push(ListItem::new(Line::from(vec![
+                Span::raw(format!(" {}", saved.name)),
+                Span::styled(format!("  {}", count), self.theme.muted_style()),
+            ])));
+        }
+
+        let mut state = ListState::default();
+        state.select(Some(self.collection_idx));
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(Span::styled(" COLLECTIONS ", self.theme.title_style())),
+            )
+            .highlight_style(self.theme.highlight_style());
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
     fn draw_preview(&self, frame: &mut ratatui::Frame, area: Rect) {
         let session = self.selected_session();
 
@@ -604,12 +849,17 @@ impl App {
                     Span::raw(p.as_str()),
                 ]));
             }
-            if let Some(ref t) = session.started_at {
-                let duration = format_duration_between(t, session.ended_at.as_deref());
+            if session.started_at.is_some() {
+                // Idle gaps are left out, so a session left open overnight
+                // doesn't read as a 14-hour one
+                let active = session
+                    .active_secs
+                    .map(|secs| format!("  {} active", activity::format_active(secs)))
+                    .unwrap_or_default();
                 lines.push(Line::from(vec![
                     Span::styled("Time: ", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(format!("{} messages", session.message_count)),
-                    Span::styled(format!("  {}", duration), self.theme.muted_style()),
+                    Span::styled(active, self.theme.muted_style()),
                 ]));
             }
             lines.push(Line::raw(""));
@@ -669,7 +919,7 @@ impl App {
                     } else {
                         ("AI: ", self.theme.assistant_role_style())
                     };
-                    let content: String = msg.content.chars().take(150).collect();
+                    let content: String = self.redactor.redact(&msg.content).chars().take(150).collect();
                     let content = content.replace('\n', " ");
                     lines.push(Line::from(vec![
                         Span::styled(label, style),
@@ -734,36 +984,49 @@ impl App {
         );
         frame.render_widget(header, chunks[0]);
 
-        // Messages
+        // Messages, grouped into turns along the conversation tree
         let mut lines: Vec<Line> = Vec::new();
-        for msg in &self.detail_messages {
-            if msg.role == "tool" {
-                continue;
+        for turn in thread::build_turns(&self.detail_messages, &self.detail_tool_calls) {
+            let indent = "  ".repeat(turn.depth);
+            if turn.branch_start {
+                lines.push(Line::from(Span::styled(
+                    format!("{}┆ abandoned branch", indent),
+                    self.theme.muted_style(),
+                )));
             }
-            let (icon, style) = if msg.role == "user" {
-                ("You", self.theme.user_role_style())
-            } else {
-                ("AI", self.theme.assistant_role_style())
-            };
-            let ts = msg
-                .timestamp
-                .as_ref()
-                .map(|t| {
-                    chrono::DateTime::parse_from_rfc3339(t)
-                        .map(|d| d.format("%H:%M").to_string())
-                        .unwrap_or_default()
-                })
-                .unwrap_or_default();
+            for msg in turn.messages() {
+                if msg.role == "tool" {
+                    continue;
+                }
+                let (icon, style) = if msg.role == "user" {
+                    ("You", self.theme.user_role_style())
+                } else {
+                    ("AI", self.theme.assistant_role_style())
+                };
+                let ts = msg
+                    .timestamp
+                    .as_ref()
+                    .and_then(|t| clock::format_local(t, "%H:%M"))
+                    .unwrap_or_default();
 
-            lines.push(Line::from(vec![
-                Span::styled(format!("{} ", icon), style),
-                Span::styled(ts, self.theme.muted_style()),
-            ]));
+                lines.push(Line::from(vec![
+                    Span::raw(indent.clone()),
+                    Span::styled(format!("{} ", icon), style),
+                    Span::styled(ts, self.theme.muted_style()),
+                ]));
 
-            for text_line in msg.content.lines() {
-                lines.push(Line::raw(format!("  {}", text_line)));
+                for text_line in self.redactor.redact(&msg.content).lines() {
+                    lines.push(Line::raw(format!("{}  {}", indent, text_line)));
+                }
+                lines.push(Line::raw(""));
+            }
+            if !turn.tool_calls.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("{}  ⚙ {}", indent, thread::tool_call_summary(&turn.tool_calls)),
+                    self.theme.muted_style(),
+                )));
+                lines.push(Line::raw(""));
             }
-            lines.push(Line::raw(""));
         }
 
         let content = Paragraph::new(lines)
@@ -814,7 +1077,7 @@ impl App {
             .iter()
             .map(|r| {
                 let role_icon = if r.role == "user" { "You" } else { "AI" };
-                let content: String = r.content.chars().take(100).collect();
+                let content: String = self.redactor.redact(&r.content).chars().take(100).collect();
                 let content = content.replace('\n', " ");
 
                 let line1 = Line::from(vec![
@@ -866,6 +1129,71 @@ impl App {
         frame.render_widget(status, chunks[2]);
     }
 
+    fn draw_related(&mut self, frame: &mut ratatui::Frame) {
+        let area = frame.area();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(2),
+            ])
+            .split(area);
+
+        let sid = self.related_source.as_deref().unwrap_or("?");
+        let header = Paragraph::new(format!(" Sessions similar to {}", &sid[..sid.len().min(12)]))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(Span::styled(" Related ", self.theme.title_style())),
+            );
+        frame.render_widget(header, chunks[0]);
+
+        let count = self.related_results.len();
+        let items: Vec<ListItem> = self
+            .related_results
+            .iter()
+            .map(|r| {
+                let s = &r.session;
+                let line1 = Line::from(vec![
+                    Span::styled(format!(" {} ", s.agent), self.theme.agent_style(&s.agent)),
+                    Span::raw(format!(" {}  ", s.project_name.as_deref().unwrap_or("?"))),
+                    Span::styled(
+                        s.started_at.as_deref().map(format_time_ago).unwrap_or_default(),
+                        self.theme.muted_style(),
+                    ),
+                ]);
+                let summary: String = self
+                    .redactor
+                    .redact(s.summary.as_deref().unwrap_or(""))
+                    .chars()
+                    .take(80)
+                    .collect();
+                let line2 = Line::raw(format!("  \"{}\"", summary));
+                let line3 = Line::styled(format!("  {}", r.reason()), self.theme.tag_style());
+                ListItem::new(vec![line1, line2, line3, Line::raw("")])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(Span::styled(
+                        format!(" Results ({}) ", count),
+                        self.theme.title_style(),
+                    )),
+            )
+            .highlight_style(self.theme.highlight_style());
+        frame.render_stateful_widget(list, chunks[1], &mut self.related_state);
+
+        let status = Paragraph::new(" j/k: Navigate | Enter: Jump to session | Esc: Back")
+            .style(self.theme.status_bar_style());
+        frame.render_widget(status, chunks[2]);
+    }
+
     fn draw_action_popup(&mut self, frame: &mut ratatui::Frame) {
         let area = frame.area();
         // Center popup
@@ -928,7 +1256,7 @@ fn build_resume_cmd(session: &SessionRow) -> String {
     let project_dir = session.project_path.as_deref().unwrap_or(".");
     match session.agent.as_str() {
         "claude-code" => format!("cd {} && claude --resume {}", project_dir, resume_id),
-        "codex" => format!("cd {} && codex --resume {}", project_dir, session.id),
+        "codex" => format!("cd {} && codex resume {}", project_dir, resume_id),
         "cursor" => format!("cursor {}", project_dir),
         _ => format!("cd {} && claude --resume {}", project_dir, resume_id),
     }
@@ -940,7 +1268,7 @@ fn export_filename(project: &str) -> String {
     format!(".ail-context-{}-{}.md", project, ts)
 }
 
-pub fn run_tui() -> Result<()> {
+pub fn run_tui(no_redact: bool) -> Result<()> {
     let db_path = config::db_path();
 
     // Auto-index on startup
@@ -951,12 +1279,23 @@ pub fn run_tui() -> Result<()> {
         config::ensure_data_dir()?;
     }
 
-    let db = Database::open(&db_path)?;
+    let db = if config::team_mode() {
+        Database::open_read_only(&db_path)?
+    } else {
+        let mut db = Database::open(&db_path)?;
+        db.apply_config()?;
+        db
+    };
+    if db.is_locked() {
+        anyhow::bail!("The index is locked. Run `ail unlock` first.");
+    }
 
-    // Quick auto-index
-    let _ = crate::core::indexer::index_all(&db);
+    // Quick auto-index (the team index is read-only)
+    if !config::team_mode() {
+        let _ = crate::core::indexer::index_all(&db);
+    }
 
-    let mut app = App::new(db);
+    let mut app = App::new(db, Redactor::load(no_redact)?);
     app.load_sessions()?;
 
     // Setup terminal
@@ -1081,26 +1420,3 @@ fn format_time_ago(ts_str: &str) -> String {
         format!("{}w", diff.num_weeks())
     }
 }
-
-fn format_duration_between(start_str: &str, end_str: Option<&str>) -> String {
-    let start = match chrono::DateTime::parse_from_rfc3339(start_str) {
-        Ok(t) => t,
-        Err(_) => return String::new(),
-    };
-
-    let end = if let Some(e) = end_str {
-        match chrono::DateTime::parse_from_rfc3339(e) {
-            Ok(t) => t,
-            Err(_) => return String::new(),
-        }
-    } else {
-        return String::new();
-    };
-
-    let diff = end.signed_duration_since(start);
-    if diff.num_hours() > 0 {
-        format!("{}h{}m", diff.num_hours(), diff.num_minutes() % 60)
-    } else {
-        format!("{}min", diff.num_minutes())
-    }
-}
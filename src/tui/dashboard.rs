@@ -0,0 +1,285 @@
+use crate::core::activity;
+use crate::core::clock;
+use crate::core::db::{parse_datetime, Database};
+use crate::tui::theme::Theme;
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Timelike, Utc};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, Paragraph};
+use std::collections::HashMap;
+
+/// Weeks of history the heatmap can show (one year, like GitHub)
+const HEATMAP_WEEKS: i64 = 53;
+const MAX_PROJECTS: usize = 8;
+
+/// Aggregates behind the dashboard view, for one agent filter
+pub struct Dashboard {
+    pub total_sessions: i64,
+    pub total_active_secs: i64,
+    /// Sessions started per work day
+    pub day_counts: HashMap<NaiveDate, u64>,
+    /// Active seconds per work day
+    pub day_active: HashMap<NaiveDate, u64>,
+    pub by_agent: Vec<(String, u64)>,
+    pub by_project: Vec<(String, u64)>,
+    /// Active seconds per agent and per project
+    pub active_by_agent: Vec<(String, u64)>,
+    pub active_by_project: Vec<(String, u64)>,
+    /// Messages per hour of day, in the configured timezone
+    pub hours: [u64; 24],
+    pub top_files: Vec<(String, i64)>,
+}
+
+impl Dashboard {
+    pub fn load(db: &Database, agent: Option<&str>) -> Result<Self> {
+        let stats = db.get_stats(None, None, agent, None, None)?;
+
+        let calendar = clock::calendar();
+        let since = Utc::now() - Duration::weeks(HEATMAP_WEEKS);
+        let mut day_counts = HashMap::new();
+        let mut day_active = HashMap::new();
+        for session in db.list_all_sessions(agent, None, None, Some(since), None)? {
+            if let Some(started) = session.started_at.as_deref().and_then(parse_datetime) {
+                *day_counts.entry(calendar.day_of(started)).or_insert(0) += 1;
+            }
+            let timestamps = db.activity_timestamps(&session.id)?;
+            for (day, secs) in activity::active_seconds_by_day(&timestamps, db.idle_threshold()) {
+                *day_active.entry(day).or_insert(0) += secs.max(0) as u64;
+            }
+        }
+
+        let mut hours = [0u64; 24];
+        for t in db.message_times(agent)? {
+            hours[calendar.local(t).hour() as usize] += 1;
+        }
+
+        let counts = |rows: Vec<(String, i64)>| -> Vec<(String, u64)> {
+            rows.into_iter().map(|(name, n)| (name, n.max(0) as u64)).collect()
+        };
+        Ok(Self {
+            total_sessions: stats.total_sessions,
+            total_active_secs: stats.total_active_secs,
+            day_counts,
+            day_active,
+            by_agent: counts(stats.sessions_by_agent),
+            by_project: counts(stats.sessions_by_project),
+            active_by_agent: counts(stats.active_secs_by_agent),
+            active_by_project: counts(stats.active_secs_by_project),
+            hours,
+            top_files: stats.most_modified_files,
+        })
+    }
+}
+
+/// Render the dashboard body (everything between the header and status bar).
+/// With `active`, the heatmap and the agent/project bars show active time
+/// instead of session counts.
+pub fn draw(frame: &mut ratatui::Frame, area: Rect, dash: &Dashboard, active: bool, theme: &Theme) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(10), // heatmap: month row + 7 days + borders
+            Constraint::Min(8),
+            Constraint::Min(8),
+        ])
+        .split(area);
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[1]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[2]);
+
+    let (days, by_agent, by_project, unit) = if active {
+        (&dash.day_active, &dash.active_by_agent, &dash.active_by_project, "ACTIVE TIME")
+    } else {
+        (&dash.day_counts, &dash.by_agent, &dash.by_project, "SESSIONS")
+    };
+    let text = |value: u64| {
+        if active {
+            activity::format_active(value as i64)
+        } else {
+            value.to_string()
+        }
+    };
+    draw_heatmap(frame, rows[0], dash, days, theme);
+    draw_bars(
+        frame,
+        middle[0],
+        &format!(" {} BY AGENT ", unit),
+        by_agent,
+        |agent| theme.agent_style(agent),
+        text,
+        theme,
+    );
+    let projects: Vec<(String, u64)> = by_project.iter().take(MAX_PROJECTS).cloned().collect();
+    draw_bars(
+        frame,
+        middle[1],
+        &format!(" {} BY PROJECT ", unit),
+        &projects,
+        |_| Style::default().fg(theme.accent),
+        text,
+        theme,
+    );
+    draw_hours(frame, bottom[0], dash, theme);
+    draw_top_files(frame, bottom[1], dash, theme);
+}
+
+fn block<'a>(title: String, theme: &Theme) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title(Span::styled(title, theme.title_style()))
+}
+
+/// GitHub-style calendar: one column per week, one row per weekday,
+/// weeks starting on the configured `week_start`, shaded by `days`
+fn draw_heatmap(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    dash: &Dashboard,
+    days: &HashMap<NaiveDate, u64>,
+    theme: &Theme,
+) {
+    const LABEL_WIDTH: u16 = 4;
+    let weeks = (area.width.saturating_sub(2 + LABEL_WIDTH) / 2).min(HEATMAP_WEEKS as u16) as i64;
+    let calendar = clock::calendar();
+    let today = calendar.today();
+    let first_week = calendar.week_of(today) - Duration::weeks(weeks.max(1) - 1);
+    let max = days.values().copied().max().unwrap_or(0);
+
+    let mut months = String::from("    ");
+    let mut week = 0;
+    while week < weeks {
+        let start = first_week + Duration::weeks(week);
+        let end = start + Duration::days(6);
+        if start.month() != end.month() || start.day() == 1 {
+            let label = end.format("%b").to_string();
+            months.push_str(&label);
+            months.push(' ');
+            week += 2;
+        } else {
+            months.push_str("  ");
+            week += 1;
+        }
+    }
+    let mut lines = vec![Line::styled(months, theme.muted_style())];
+
+    for weekday in 0..7 {
+        // Every other row is labelled: Mon/Wed/Fri, or Sun/Tue/Thu for Sunday weeks
+        let label = if weekday % 2 == 0 && weekday < 6 {
+            format!("{} ", (first_week + Duration::days(weekday)).format("%a"))
+        } else {
+            "    ".to_string()
+        };
+        let mut spans = vec![Span::styled(label, theme.muted_style())];
+        for week in 0..weeks {
+            let day = first_week + Duration::weeks(week) + Duration::days(weekday);
+            if day > today {
+                spans.push(Span::raw("  "));
+                continue;
+            }
+            let count = days.get(&day).copied().unwrap_or(0);
+            spans.push(Span::styled("■ ", theme.heatmap_style(heat_level(count, max))));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let title = format!(
+        " ACTIVITY  {} sessions  ·  {} active ",
+        dash.total_sessions,
+        activity::format_active(dash.total_active_secs)
+    );
+    frame.render_widget(Paragraph::new(lines).block(block(title, theme)), area);
+}
+
+/// 0 for an idle day, then 1-4 by quarter of the busiest day
+fn heat_level(count: u64, max: u64) -> u8 {
+    if count == 0 || max == 0 {
+        0
+    } else {
+        (((count * 4).div_ceil(max)) as u8).clamp(1, 4)
+    }
+}
+
+fn draw_bars(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    title: &str,
+    rows: &[(String, u64)],
+    style: impl Fn(&str) -> Style,
+    text: impl Fn(u64) -> String,
+    theme: &Theme,
+) {
+    let bars: Vec<Bar> = rows
+        .iter()
+        .map(|(name, value)| {
+            Bar::default()
+                .value(*value)
+                .text_value(text(*value))
+                .label(Line::from(name.chars().take(18).collect::<String>()))
+                .style(style(name))
+                .value_style(Style::default().add_modifier(Modifier::REVERSED))
+        })
+        .collect();
+    let chart = BarChart::default()
+        .block(block(title.to_string(), theme))
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .data(BarGroup::default().bars(&bars));
+    frame.render_widget(chart, area);
+}
+
+fn draw_hours(frame: &mut ratatui::Frame, area: Rect, dash: &Dashboard, theme: &Theme) {
+    // Two columns per hour fit the "12"/"18" labels; gaps only when there's room
+    let slot = (area.width.saturating_sub(2) / 24).max(1);
+    let (bar_width, bar_gap) = if slot >= 3 { (slot - 1, 1) } else { (slot, 0) };
+    let bars: Vec<Bar> = dash
+        .hours
+        .iter()
+        .enumerate()
+        .map(|(hour, count)| {
+            let label = if hour % 6 == 0 { hour.to_string() } else { String::new() };
+            Bar::default()
+                .value(*count)
+                .label(Line::from(label))
+                .text_value(String::new())
+        })
+        .collect();
+    let chart = BarChart::default()
+        .block(block(" MESSAGES BY HOUR ".to_string(), theme))
+        .bar_width(bar_width)
+        .bar_gap(bar_gap)
+        .bar_style(Style::default().fg(theme.accent))
+        .data(BarGroup::default().bars(&bars));
+    frame.render_widget(chart, area);
+}
+
+fn draw_top_files(frame: &mut ratatui::Frame, area: Rect, dash: &Dashboard, theme: &Theme) {
+    let width = area.width.saturating_sub(10) as usize;
+    let items: Vec<ListItem> = dash
+        .top_files
+        .iter()
+        .map(|(path, count)| {
+            // Keep the end of long paths, where the file name is
+            let chars: Vec<char> = path.chars().collect();
+            let shown: String = if chars.len() > width {
+                let tail: String = chars[chars.len() - width.saturating_sub(1)..].iter().collect();
+                format!("…{}", tail)
+            } else {
+                path.clone()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:>5} ", count), theme.file_modified_style()),
+                Span::raw(shown),
+            ]))
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(block(" TOP MODIFIED FILES ".to_string(), theme)), area);
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod dashboard;
 pub mod theme;
 
 pub use app::run_tui;
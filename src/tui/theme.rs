@@ -96,6 +96,18 @@ impl Theme {
         Style::default().bg(Color::DarkGray).fg(Color::White)
     }
 
+    /// Dashboard heatmap cell, level 0 (no sessions) to 4 (busiest)
+    pub fn heatmap_style(&self, level: u8) -> Style {
+        let color = match level {
+            0 => self.border,
+            1 => Color::Rgb(14, 68, 41),
+            2 => Color::Rgb(0, 109, 50),
+            3 => Color::Rgb(38, 166, 65),
+            _ => Color::Rgb(57, 211, 83),
+        };
+        Style::default().fg(color)
+    }
+
     pub fn search_match_style(&self) -> Style {
         Style::default()
             .fg(Color::Yellow)